@@ -0,0 +1,392 @@
+//! 资源清理模块
+//!
+//! 扫描一个或多个 Markdown 文档引用的图片，找出资源目录中未被引用的文件和失效的图片引用，
+//! 并按配置删除未被引用的文件或将其移动到回收目录
+
+use crate::config::{CleanAssetsAction, CleanAssetsConfig};
+use crate::error::{MarkdownError, Result};
+use crate::image_localizer::{collect_document_images, markdown_dir, resolve_local_image_path};
+use crate::links::collect_links;
+use crate::mst::{ImageSource, ImageType, MSTNode};
+use crate::parser::MarkdownParser;
+use crate::tr;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 视为图片的文件扩展名，只有图片文件才会被当作未引用的候选
+const IMAGE_EXTENSIONS: [&str; 12] =
+  ["png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "tif", "tiff", "avif", "heic", "svg"];
+
+/// 失效的图片引用
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenReference {
+  /// 引用所在的文档
+  pub file: String,
+  /// 引用所在的行号
+  pub line_number: usize,
+  /// 文档中的原始地址
+  pub url: String,
+}
+
+/// 资源扫描报告
+#[derive(Debug, Clone, Default)]
+pub struct AssetReport {
+  /// 资源目录（规范化后的绝对路径）
+  pub assets_dir: PathBuf,
+  /// 资源目录中的图片文件数量
+  pub asset_count: usize,
+  /// 未被任何文档引用的图片文件
+  pub orphans: Vec<PathBuf>,
+  /// 指向不存在文件的本地图片引用
+  pub broken_references: Vec<BrokenReference>,
+}
+
+impl AssetReport {
+  /// 资源文件相对于资源目录的显示路径
+  pub fn display_path(&self, path: &Path) -> String {
+    path.strip_prefix(&self.assets_dir).unwrap_or(path).to_string_lossy().replace('\\', "/")
+  }
+}
+
+/// 资源清理器
+pub struct AssetCleaner {
+  config: CleanAssetsConfig,
+  parser: MarkdownParser,
+}
+
+impl AssetCleaner {
+  /// 创建新的资源清理器
+  pub fn new(config: CleanAssetsConfig) -> Self {
+    Self { config, parser: MarkdownParser::default() }
+  }
+
+  /// 扫描文档引用和资源目录，生成报告
+  pub fn scan(&self) -> Result<AssetReport> {
+    let assets_dir = PathBuf::from(self.config.get_resolved_assets_dir());
+    let assets_dir = assets_dir.canonicalize().map_err(|e| MarkdownError::io(assets_dir.display().to_string(), e))?;
+
+    let mut referenced = HashSet::new();
+    let mut broken_references = Vec::new();
+    for file in &self.config.full_file_paths {
+      self.collect_references(file, &mut referenced, &mut broken_references)?;
+    }
+
+    let trash_dir = self.resolved_trash_dir(&assets_dir).canonicalize().ok();
+    let mut assets = Vec::new();
+    collect_image_files(&assets_dir, trash_dir.as_deref(), &mut assets)?;
+    assets.sort();
+
+    let asset_count = assets.len();
+    let orphans = assets.into_iter().filter(|asset| !referenced.contains(asset)).collect();
+
+    Ok(AssetReport { assets_dir, asset_count, orphans, broken_references })
+  }
+
+  /// 按配置处理未被引用的文件，返回每个文件的处理结果
+  pub fn apply(&self, report: &AssetReport) -> Vec<String> {
+    let mut results = Vec::new();
+    let trash_dir = self.resolved_trash_dir(&report.assets_dir);
+
+    for orphan in &report.orphans {
+      let display = report.display_path(orphan);
+      // 只处理确实位于资源目录内的文件，防止通过符号链接删除或移动目录之外的文件
+      let inside = orphan.canonicalize().is_ok_and(|path| path.starts_with(&report.assets_dir));
+      if self.config.action != CleanAssetsAction::Report && !inside {
+        results.push(tr!("❌ 拒绝处理资源目录之外的文件: {}", orphan.display()));
+        continue;
+      }
+      match self.config.action {
+        CleanAssetsAction::Report => {}
+        CleanAssetsAction::Delete => match fs::remove_file(orphan) {
+          Ok(()) => results.push(tr!("✅ 已删除: {}", display)),
+          Err(e) => results.push(tr!("❌ 删除失败: {} - {}", display, e)),
+        },
+        CleanAssetsAction::Trash => match move_to_trash(orphan, &report.assets_dir, &trash_dir) {
+          Ok(target) => results.push(tr!("✅ 已移动: {} -> {}", display, target.display())),
+          Err(e) => results.push(tr!("❌ 移动失败: {} - {}", display, e)),
+        },
+      }
+    }
+
+    results
+  }
+
+  /// 回收目录，未配置时为资源目录下的 .trash
+  fn resolved_trash_dir(&self, assets_dir: &Path) -> PathBuf {
+    match self.config.get_resolved_trash_dir() {
+      Some(dir) => PathBuf::from(dir),
+      None => assets_dir.join(".trash"),
+    }
+  }
+
+  /// 收集单个文档中引用的本地文件，记录失效的引用
+  fn collect_references(
+    &self,
+    file: &str,
+    referenced: &mut HashSet<PathBuf>,
+    broken_references: &mut Vec<BrokenReference>,
+  ) -> Result<()> {
+    let content = fs::read_to_string(file).map_err(|e| MarkdownError::io(file, e))?;
+    let mst = self
+      .parser
+      .parse(&content)
+      .map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {} - {}", file, e)))?;
+    let md_dir = markdown_dir(file)?;
+
+    for (line_number, url) in self.collect_urls(&mst) {
+      if ImageSource::from_url(&url) != ImageSource::Local {
+        continue;
+      }
+      let Ok(Some(path)) = resolve_local_image_path(md_dir, &url) else {
+        continue;
+      };
+      match path.canonicalize() {
+        Ok(path) => {
+          referenced.insert(path);
+        }
+        Err(_) => broken_references.push(BrokenReference { file: file.to_string(), line_number, url }),
+      }
+    }
+
+    // 普通链接指向的文件同样视为被引用，链接失效不在这里报告
+    for url in collect_links(&self.parser, &content).into_iter().filter_map(|link| link.url) {
+      if ImageSource::from_url(&url) != ImageSource::Local {
+        continue;
+      }
+      if let Ok(Some(path)) = resolve_local_image_path(md_dir, &url) {
+        referenced.extend(path.canonicalize());
+      }
+    }
+
+    Ok(())
+  }
+
+  /// 收集文档中的图片地址和链接引用定义地址（行号, 地址）
+  ///
+  /// 所有链接引用定义都视为引用，避免误删被普通链接使用的文件；
+  /// 引用式图片的地址来自其定义，不再重复收集
+  fn collect_urls(&self, mst: &MSTNode) -> Vec<(usize, String)> {
+    let mut urls: Vec<(usize, String)> = collect_document_images(&self.parser, mst)
+      .iter()
+      .filter_map(|image| {
+        let info = image.get_image_info().filter(|info| info.image_type != ImageType::Reference)?;
+        Some((image.line_number, info.original_url.clone()))
+      })
+      .collect();
+
+    mst.walk(&mut |node| {
+      if let Some(definition) = node.get_link_definition() {
+        urls.push((node.line_number, definition.url.clone()));
+      }
+    });
+
+    urls
+  }
+}
+
+/// 递归收集目录中的图片文件，跳过回收目录、隐藏目录和符号链接
+///
+/// `dir` 应为规范化路径；不进入符号链接，收集到的路径因此也都是规范化的，且都位于 `dir` 之内
+fn collect_image_files(dir: &Path, trash_dir: Option<&Path>, files: &mut Vec<PathBuf>) -> Result<()> {
+  let io_error = |e| MarkdownError::io(dir.display().to_string(), e);
+  let entries = fs::read_dir(dir).map_err(io_error)?;
+
+  for entry in entries {
+    let path = entry.map_err(io_error)?.path();
+    let Ok(metadata) = fs::symlink_metadata(&path) else {
+      continue;
+    };
+
+    if metadata.is_dir() {
+      let is_hidden = path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with('.'));
+      if !is_hidden && Some(path.as_path()) != trash_dir {
+        collect_image_files(&path, trash_dir, files)?;
+      }
+    } else if metadata.is_file() && is_image_file(&path) {
+      files.push(path);
+    }
+  }
+
+  Ok(())
+}
+
+/// 根据扩展名判断是否为图片文件
+fn is_image_file(path: &Path) -> bool {
+  path
+    .extension()
+    .and_then(|ext| ext.to_str())
+    .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// 将文件移动到回收目录，保留其在资源目录中的相对路径，重名时追加序号
+fn move_to_trash(file: &Path, assets_dir: &Path, trash_dir: &Path) -> std::result::Result<PathBuf, String> {
+  let relative = file.strip_prefix(assets_dir).unwrap_or(file.file_name().map(Path::new).unwrap_or(file));
+  let mut target = trash_dir.join(relative);
+
+  let mut suffix = 1;
+  while target.exists() {
+    let stem = relative.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+    let file_name = match relative.extension().and_then(|e| e.to_str()) {
+      Some(ext) => format!("{}-{}.{}", stem, suffix, ext),
+      None => format!("{}-{}", stem, suffix),
+    };
+    target = trash_dir.join(relative).with_file_name(file_name);
+    suffix += 1;
+  }
+
+  if let Some(parent) = target.parent() {
+    fs::create_dir_all(parent).map_err(|e| tr!("创建回收目录失败: {}", e))?;
+  }
+
+  // 跨文件系统时 rename 会失败，退回到复制后删除
+  if fs::rename(file, &target).is_err() {
+    fs::copy(file, &target).map_err(|e| tr!("复制文件失败: {}", e))?;
+    fs::remove_file(file).map_err(|e| tr!("删除原文件失败: {}", e))?;
+  }
+
+  Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  /// 创建测试目录：两个文档和一个资源目录
+  fn setup() -> (TempDir, Vec<String>) {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir_all(root.join("assets/icons")).unwrap();
+    for name in ["used.png", "ref.jpg", "orphan.png", "icons/used.svg", "icons/old.svg", "notes.txt"] {
+      fs::write(root.join("assets").join(name), b"data").unwrap();
+    }
+
+    let doc_a = root.join("a.md");
+    fs::write(&doc_a, "# A\n\n![用到](assets/used.png)\n\n![引用][r]\n\n[r]: ./assets/ref.jpg\n").unwrap();
+    let doc_b = root.join("b.md");
+    fs::write(&doc_b, "# B\n\n行内 <img src=\"assets/icons/used.svg\">\n\n![丢失](assets/missing.png)\n").unwrap();
+
+    let files = vec![doc_a.to_str().unwrap().to_string(), doc_b.to_str().unwrap().to_string()];
+    (temp_dir, files)
+  }
+
+  fn create_test_config(files: Vec<String>, action: CleanAssetsAction) -> CleanAssetsConfig {
+    CleanAssetsConfig {
+      full_file_paths: files,
+      assets_dir: "{full_dir_of_original_file}/assets".to_string(),
+      action,
+      trash_dir: None,
+    }
+  }
+
+  /// 测试扫描未引用的文件和失效引用
+  #[test]
+  fn test_scan_reports_orphans_and_broken_references() {
+    let (_temp_dir, files) = setup();
+    let cleaner = AssetCleaner::new(create_test_config(files.clone(), CleanAssetsAction::Report));
+
+    let report = cleaner.scan().unwrap();
+    assert_eq!(report.asset_count, 5);
+
+    let orphans: Vec<String> = report.orphans.iter().map(|p| report.display_path(p)).collect();
+    assert_eq!(orphans, vec!["icons/old.svg", "orphan.png"]);
+
+    assert_eq!(
+      report.broken_references,
+      vec![BrokenReference { file: files[1].clone(), line_number: 5, url: "assets/missing.png".to_string() }]
+    );
+
+    // 仅报告时不修改文件
+    assert!(cleaner.apply(&report).is_empty());
+    assert!(report.orphans.iter().all(|p| p.exists()));
+  }
+
+  /// 测试只通过普通链接（行内链接和引用式链接）使用的文件不是未引用的文件
+  #[test]
+  fn test_linked_assets_are_referenced() {
+    let (temp_dir, files) = setup();
+    let root = temp_dir.path();
+    fs::write(root.join("c.md"), "# C\n\n[下载](assets/orphan.png) 和 [图标][icon]\n\n[icon]: assets/icons/old.svg\n")
+      .unwrap();
+    let files = [files, vec![root.join("c.md").to_str().unwrap().to_string()]].concat();
+    let cleaner = AssetCleaner::new(create_test_config(files, CleanAssetsAction::Delete));
+
+    let report = cleaner.scan().unwrap();
+    assert!(report.orphans.is_empty(), "{:?}", report.orphans);
+    assert!(cleaner.apply(&report).is_empty());
+    assert!(root.join("assets/orphan.png").exists());
+  }
+
+  /// 测试不进入符号链接的子目录，也不会删除资源目录之外的文件
+  #[cfg(unix)]
+  #[test]
+  fn test_symlinked_directories_are_not_followed() {
+    let (temp_dir, files) = setup();
+    let outside = temp_dir.path().join("pics");
+    fs::create_dir_all(&outside).unwrap();
+    fs::write(outside.join("family.png"), b"data").unwrap();
+    std::os::unix::fs::symlink(&outside, temp_dir.path().join("assets/linked")).unwrap();
+    std::os::unix::fs::symlink(outside.join("family.png"), temp_dir.path().join("assets/family.png")).unwrap();
+    let cleaner = AssetCleaner::new(create_test_config(files, CleanAssetsAction::Delete));
+
+    let report = cleaner.scan().unwrap();
+    assert_eq!(report.asset_count, 5);
+    cleaner.apply(&report);
+    assert!(outside.join("family.png").exists());
+
+    // 即使报告中包含资源目录之外的文件，也拒绝删除
+    let report = AssetReport { orphans: vec![outside.join("family.png")], ..report };
+    let results = cleaner.apply(&report);
+    assert!(results[0].starts_with("❌"), "{:?}", results);
+    assert!(outside.join("family.png").exists());
+  }
+
+  /// 测试删除未引用的文件
+  #[test]
+  fn test_delete_orphans() {
+    let (temp_dir, files) = setup();
+    let cleaner = AssetCleaner::new(create_test_config(files, CleanAssetsAction::Delete));
+
+    let report = cleaner.scan().unwrap();
+    let results = cleaner.apply(&report);
+    assert_eq!(results.len(), 2);
+    assert!(!temp_dir.path().join("assets/orphan.png").exists());
+    assert!(temp_dir.path().join("assets/used.png").exists());
+    assert!(temp_dir.path().join("assets/notes.txt").exists());
+  }
+
+  /// 测试移动到回收目录，再次扫描时忽略回收目录
+  #[test]
+  fn test_move_orphans_to_trash() {
+    let (temp_dir, files) = setup();
+    let cleaner = AssetCleaner::new(create_test_config(files, CleanAssetsAction::Trash));
+
+    let report = cleaner.scan().unwrap();
+    cleaner.apply(&report);
+
+    let trash = temp_dir.path().join("assets/.trash");
+    assert!(trash.join("orphan.png").exists());
+    assert!(trash.join("icons/old.svg").exists());
+    assert!(!temp_dir.path().join("assets/orphan.png").exists());
+
+    let report = cleaner.scan().unwrap();
+    assert!(report.orphans.is_empty());
+  }
+
+  /// 测试回收目录中重名文件追加序号
+  #[test]
+  fn test_move_to_trash_with_name_collision() {
+    let temp_dir = TempDir::new().unwrap();
+    let assets = temp_dir.path().join("assets");
+    let trash = temp_dir.path().join("trash");
+    fs::create_dir_all(&assets).unwrap();
+    fs::create_dir_all(&trash).unwrap();
+    fs::write(assets.join("a.png"), b"new").unwrap();
+    fs::write(trash.join("a.png"), b"old").unwrap();
+
+    let target = move_to_trash(&assets.join("a.png"), &assets, &trash).unwrap();
+    assert_eq!(target, trash.join("a-1.png"));
+    assert_eq!(fs::read(trash.join("a.png")).unwrap(), b"old");
+  }
+}
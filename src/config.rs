@@ -1,133 +1,1303 @@
 use crate::error::{MarkdownError, Result};
+use crate::i18n::{self, Lang, LANG_ENV};
+use crate::outline::HeadingSelector;
+use crate::sandbox::canonicalize_allow_dir;
+use crate::tr;
+use crate::utils::PathTemplate;
+use schemars::r#gen::SchemaSettings;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// 默认的单张图片大小上限（10 MB）
+pub const DEFAULT_MAX_IMAGE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// HTTP 传输默认监听的地址
+pub const DEFAULT_HTTP_BIND: &str = "127.0.0.1";
+
+/// HTTP 传输默认监听的端口
+pub const DEFAULT_HTTP_PORT: u16 = 8080;
+
+/// 未通过命令行指定时，从该环境变量读取 Bearer Token
+pub const AUTH_TOKEN_ENV: &str = "MCP_MARKDOWN_TOOLS_AUTH_TOKEN";
+
+/// 命令行帮助信息
+pub const SERVER_USAGE: &str = r#"用法: mcp-markdown-tools [选项]
+
+选项:
+  --transport <stdio|http>  传输方式，默认 stdio
+  --bind <地址>             HTTP 传输监听的地址，默认 127.0.0.1
+  --port <端口>             HTTP 传输监听的端口，默认 8080
+  --auth-token <token>      HTTP 传输要求的 Bearer Token，也可通过环境变量 MCP_MARKDOWN_TOOLS_AUTH_TOKEN 设置
+  --allow-dir <目录>        只允许读写该目录内的文件，可多次指定；客户端声明的 roots 只能进一步缩小范围
+  --lang <zh|en>            返回信息使用的语言，默认 zh，也可通过环境变量 MCP_MARKDOWN_TOOLS_LANG 设置
+  -h, --help                显示帮助信息"#;
+
+/// MCP 服务器的传输方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransportKind {
+  /// 标准输入输出，每个客户端启动独立进程
+  Stdio,
+  /// Streamable HTTP（SSE），多个客户端共享一个实例
+  Http,
+}
 
+/// 服务器启动配置，来自命令行参数
 #[derive(Debug, Clone)]
+pub struct ServerConfig {
+  pub transport: TransportKind,
+  pub bind: String,
+  pub port: u16,
+  /// HTTP 传输要求的 Bearer Token，为空时不校验
+  pub auth_token: Option<String>,
+  /// 允许读写的目录（已解析符号链接），为空时由客户端的 roots 决定
+  pub allow_dirs: Vec<PathBuf>,
+  /// 返回信息的默认语言，工具调用可通过 lang 参数覆盖
+  pub lang: Lang,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct GenerateChapterConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
   pub full_file_path: String,
+  /// 是否忽略一级标题（# 标题）
+  #[serde(default)]
   pub ignore_h1: bool,
+  /// 是否使用中文编号（一、二、三...）
+  #[serde(default)]
   pub use_chinese_number: bool,
+  #[serde(default = "default_true")]
+  #[schemars(description = r#"一级以下编号是否使用独立的阿拉伯数字编号。use_chinese_number=true 时生效。
+为 true 时，只有一级编号使用中文，每个一级编号下的二级编号都是用从1开始的一级阿拉伯数字编号，三级编号都是用从1.1开始的二级阿拉伯数字编号，以此类推。
+
+示例一（ignore_h1=false, use_chinese_number=true, use_arabic_number_for_sublevel=true）：
+# 一、
+## 一、一、
+### 一、一、一、
+### 一、一、二、
+## 一、二、
+# 二、
+## 二、一、
+## 二、二、
+# 三、
+## 三、一、
+
+示例二（ignore_h1=true, use_chinese_number=true, use_arabic_number_for_sublevel=false）：
+## 一、
+### 1.
+#### 1.1.
+#### 1.2.
+### 2.
+## 二、
+### 1.
+### 2.
+## 三、
+### 1.
+"#)]
   pub use_arabic_number_for_sublevel: bool,
+  /// 编辑后，是否另存为新文件，为false时将覆盖原文件。
+  #[serde(default)]
   pub save_as_new_file: bool,
+  /// 新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
+  ///
+  /// 默认与原文档同目录，默认文件名为：{original_file_name}_numed.md。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  #[schemars(default = "default_numed_file_path")]
   pub new_full_file_path: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct RemoveChapterConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
   pub full_file_path: String,
+  /// 编辑后，是否另存为新文件。为false时将覆盖原文件。
+  #[serde(default)]
   pub save_as_new_file: bool,
+  /// 新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
+  ///
+  /// 默认与原文档同目录，默认文件名为：{original_file_name}_unnumed.md。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  #[schemars(default = "default_unnumed_file_path")]
   pub new_full_file_path: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct CheckHeadingConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
   pub full_file_path: String,
+  /// 是否允许标题跳级（如 H1 直接到 H3）
+  #[serde(default)]
+  pub allow_skipped_levels: bool,
+  /// 允许使用的最深标题级别，更深的标题会被报告
+  #[serde(default = "default_max_heading_level")]
+  #[schemars(range(min = 1, max = 6))]
+  pub max_heading_level: u8,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ShowEffectiveConfigConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 只显示该工具最终使用的参数，例如 generate_chapter_number；为空时显示全部项目设置
+  #[serde(default)]
+  pub tool: Option<String>,
+}
+
+/// 文档大纲的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlineFormat {
+  /// 按章节层级缩进的文本
+  Text,
+  /// 带嵌套子章节的 JSON
+  Json,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OutlineConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// text：按章节层级缩进的文本；json：带嵌套子章节的 JSON
+  #[serde(default = "default_outline_format")]
+  pub format: OutlineFormat,
+  /// 只列出不超过该级别的标题，章节的统计信息仍包含更深的子章节
+  #[serde(default = "default_max_heading_level")]
+  #[schemars(range(min = 1, max = 6))]
+  pub max_level: u8,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CheckLinksConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 是否通过网络检查 http(s) 外部链接，默认只检查内部链接
+  #[serde(default)]
+  pub check_external: bool,
+  /// 每个外部链接请求的超时时间（秒）
+  #[serde(default = "default_link_timeout_secs")]
+  #[schemars(range(min = 1))]
+  pub timeout_secs: u64,
+  /// 同时检查的外部链接数量上限
+  #[serde(default = "default_link_concurrency")]
+  #[schemars(range(min = 1, max = 64))]
+  pub max_concurrency: usize,
+  /// 不检查的域名，其子域名同样不检查，例如 example.com
+  #[serde(default)]
+  pub ignore_domains: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct LocalizeImagesConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
   pub full_file_path: String,
+  /// 保存到本地的图片文件名格式，不包含扩展名。支持的通配符有：
+  ///
+  /// - multilevel_num: 图片所在的多层级编号，例如：1.2.1.
+  ///
+  /// - index: 序号，从零开始
+  ///
+  /// - hash: 6位哈希字符
+  #[serde(default = "default_image_file_name_pattern")]
   pub image_file_name_pattern: String,
+  /// 图片保存的目录，默认为原文档同目录下的 assets 目录。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}
+  #[serde(default = "default_assets_dir")]
   pub image_dir: String,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  pub new_full_file_path: Option<String>,
+  /// 单张图片允许下载的最大字节数，超过时中止下载并跳过该图片
+  #[serde(default = "default_max_image_size")]
+  #[schemars(range(min = 1))]
+  pub max_image_size: u64,
+  /// 是否将 data URI 内嵌图片解码保存为文件并改写引用
+  #[serde(default)]
+  pub localize_data_uris: bool,
+  /// 是否将文档目录之外的本地图片（如 ../shared/a.png）复制到图片目录并改写引用
+  #[serde(default)]
+  pub copy_local_images: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ShiftHeadingLevelsConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 调整的级别数，正数降级（# 变为 ##），负数升级（## 变为 #）
+  #[schemars(range(min = -5, max = 5))]
+  pub shift: i32,
+  /// 只调整该锚点对应的标题及其下所有子标题，为空时调整全部标题。锚点可从 markdown://outline/{path} 资源中获取
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 调整后的级别超出 H1-H6 时，是否限制在范围内；为 false 时拒绝调整
+  #[serde(default)]
+  pub clamp: bool,
+  /// 文档原有章节编号时，调整后是否按原有风格重新生成编号
+  #[serde(default)]
+  pub renumber: bool,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  pub new_full_file_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ExtractSectionConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 要提取的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 以 / 分隔的标题路径，例如 设计/存储，每一级可以带编号或不带编号。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub title_path: Option<String>,
+  /// 章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub line_number: Option<usize>,
+  /// 新文档的路径，必须使用绝对路径，默认为原文档同目录下以章节锚点命名的 .md 文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}
+  #[serde(default)]
+  pub output_file_path: Option<String>,
+  /// 新文档已存在时是否覆盖
+  #[serde(default)]
+  pub overwrite: bool,
+  /// 是否将原文档中的章节内容替换为指向新文档的链接（保留章节标题）
+  #[serde(default)]
+  pub replace_with_link: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SplitDocumentConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 按该级别的标题拆分，每个该级别的标题及其下所有内容成为一个文件
+  #[serde(default = "default_split_level")]
+  #[schemars(range(min = 1, max = 6))]
+  pub level: u8,
+  /// 拆分后文件的保存目录，必须使用绝对路径。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}
+  #[serde(default = "default_split_output_dir")]
+  pub output_dir: String,
+  /// 索引文件的文件名，保存在 output_dir 中，包含第一个章节之前的内容和指向各个文件的链接
+  #[serde(default = "default_index_file_name")]
+  pub index_file_name: String,
+  /// 是否将章节引用的本地图片移动到 output_dir 中；为 false 时改写图片的相对路径，图片保持原位
+  #[serde(default)]
+  pub move_images: bool,
+  /// 拆分后的文件已存在时是否覆盖
+  #[serde(default)]
+  pub overwrite: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MergeDocumentsConfig {
+  /// 按顺序合并的 Markdown 文档路径列表，必须使用绝对路径。full_file_paths 和 input_dir 必须且只能提供一个
+  #[serde(default)]
+  pub full_file_paths: Vec<String>,
+  /// 合并该目录下的所有 Markdown 文档（不包括子目录），按文件名自然排序，如 2-a.md 排在 10-b.md 之前。full_file_paths 和 input_dir 必须且只能提供一个
+  #[serde(default)]
+  pub input_dir: Option<String>,
+  /// 合并后文档的路径，必须使用绝对路径
+  pub output_file_path: String,
+  /// 合并后文档的一级标题，所有文档的标题调整到该标题之下
+  #[serde(default)]
+  pub title: Option<String>,
+  /// 以第一个文档的最高级标题作为上级标题，其余文档的标题调整到该标题之下。不能与 title 同时使用
+  #[serde(default)]
+  pub first_file_as_parent: bool,
+  /// 合并后的文档已存在时是否覆盖
+  #[serde(default)]
+  pub overwrite: bool,
+}
+
+/// 章节移动到的位置
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionPosition {
+  /// 目标标题之前，与目标标题同级
+  Before,
+  /// 目标章节之后，与目标标题同级
+  After,
+  /// 目标章节之内，作为最后一个子章节
+  Into,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct MoveSectionConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 要移动的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 要移动的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub title_path: Option<String>,
+  /// 要移动的章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub line_number: Option<usize>,
+  /// 目标标题的锚点。target_anchor、target_title_path、target_line_number 必须且只能提供一个
+  #[serde(default)]
+  pub target_anchor: Option<String>,
+  /// 目标标题的标题路径，以 / 分隔。target_anchor、target_title_path、target_line_number 必须且只能提供一个
+  #[serde(default)]
+  pub target_title_path: Option<String>,
+  /// 目标标题所在的行号（从 1 开始）。target_anchor、target_title_path、target_line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub target_line_number: Option<usize>,
+  /// before：移动到目标标题之前；after：移动到目标章节之后；into：移动到目标章节之内，作为最后一个子章节
+  pub position: SectionPosition,
+  /// 文档原有章节编号时，移动后是否按原有风格重新生成编号
+  #[serde(default)]
+  pub renumber: bool,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  pub new_full_file_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteSectionConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 要删除的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 要删除的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub title_path: Option<String>,
+  /// 要删除的章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub line_number: Option<usize>,
+  /// 文档原有章节编号时，删除后是否按原有风格重新生成编号
+  #[serde(default)]
+  pub renumber: bool,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  pub new_full_file_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReadSectionConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 要读取的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 要读取的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub title_path: Option<String>,
+  /// 要读取的章节的编号路径，例如 2.3，优先匹配标题自带的编号，没有匹配时按章节的位置定位。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub number_path: Option<String>,
+  /// 要读取的章节标题所在的行号（从 1 开始）。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub line_number: Option<usize>,
+  /// 是否包含子章节，为 false 时只返回第一个子章节之前的内容
+  #[serde(default = "default_true")]
+  pub include_children: bool,
+  /// 最多返回的字符数（一个汉字计为一个字符），超出时按整行截断并附加截断标记。为空时返回完整章节
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub max_chars: Option<usize>,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ReplaceSectionConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// 要替换的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub anchor: Option<String>,
+  /// 要替换的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub title_path: Option<String>,
+  /// 要替换的章节的编号路径，例如 2.3。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  pub number_path: Option<String>,
+  /// 要替换的章节标题所在的行号（从 1 开始）。anchor、title_path、number_path、line_number 必须且只能提供一个
+  #[serde(default)]
+  #[schemars(range(min = 1))]
+  pub line_number: Option<usize>,
+  /// 新的章节内容（Markdown）。replace_heading=false 时不包含章节标题，替换标题下的所有内容（包括子章节）
+  pub content: String,
+  /// 是否连同章节标题一起替换，为 true 时 content 必须以一个与章节标题同级的标题开始
+  #[serde(default)]
+  pub replace_heading: bool,
+  /// content 中的标题级别不适合放在该章节时，是否自动调整级别；为 false 时拒绝替换
+  #[serde(default)]
+  pub adjust_levels: bool,
+  /// 文档原有章节编号时，替换后是否按原有风格重新生成编号
+  #[serde(default)]
+  pub renumber: bool,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
+  pub new_full_file_path: Option<String>,
+}
+
+/// 本地图片的发布方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PublishImagesMode {
+  /// 以 base64 data URI 内嵌到文档中
+  Inline,
+  /// 按路径映射改写为远程 URL
+  BaseUrl,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct PublishImagesConfig {
+  /// Markdown 文档的文件路径，必须使用绝对路径
+  pub full_file_path: String,
+  /// inline：内嵌为 base64 data URI；base_url：改写为远程 URL
+  #[serde(default = "default_publish_images_mode")]
+  pub mode: PublishImagesMode,
+  /// mode=base_url 时，未匹配 path_mapping 的本地图片使用的 URL 前缀，与图片相对于文档的路径拼接
+  #[serde(default)]
+  pub base_url: Option<String>,
+  /// 本地路径前缀（相对于文档目录）到 URL 前缀的映射，例如 {"assets/": "https://cdn.example.com/docs/assets/"}
+  #[serde(default, deserialize_with = "deserialize_path_mapping")]
+  #[schemars(with = "BTreeMap<String, String>", default = "empty_path_mapping")]
+  pub path_mapping: Vec<(String, String)>,
+  /// 新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。
+  #[serde(default)]
   pub new_full_file_path: Option<String>,
+  /// mode=inline 时单张图片允许内嵌的最大字节数
+  #[serde(default = "default_max_image_size")]
+  #[schemars(range(min = 1))]
+  pub max_image_size: u64,
+}
+
+/// 未被引用的资源文件的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanAssetsAction {
+  /// 仅报告
+  Report,
+  /// 直接删除
+  Delete,
+  /// 移动到回收目录
+  Trash,
+}
+
+#[derive(Debug, Clone, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct CleanAssetsConfig {
+  /// 参与扫描的 Markdown 文档路径列表，必须使用绝对路径。所有引用该资源目录的文档都应包含在内
+  #[schemars(length(min = 1))]
+  pub full_file_paths: Vec<String>,
+  /// 资源目录，占位符以第一个文档为准。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}
+  #[serde(default = "default_assets_dir")]
+  pub assets_dir: String,
+  /// report：仅报告；delete：删除未被引用的文件；trash：移动到回收目录
+  #[serde(default = "default_clean_assets_action")]
+  pub action: CleanAssetsAction,
+  /// action=trash 时的回收目录，默认为资源目录下的 .trash。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}
+  #[serde(default)]
+  pub trash_dir: Option<String>,
+}
+
+fn default_true() -> bool {
+  true
+}
+
+fn default_numed_file_path() -> Option<String> {
+  Some("{full_dir_of_original_file}/{original_file_name}_numed.md".to_string())
+}
+
+fn default_unnumed_file_path() -> Option<String> {
+  Some("{full_dir_of_original_file}/{original_file_name}_unnumed.md".to_string())
+}
+
+fn default_max_heading_level() -> u8 {
+  6
+}
+
+fn default_outline_format() -> OutlineFormat {
+  OutlineFormat::Text
+}
+
+fn default_split_level() -> u8 {
+  1
+}
+
+fn default_split_output_dir() -> String {
+  "{full_dir_of_original_file}/{original_file_name}".to_string()
+}
+
+fn default_index_file_name() -> String {
+  "index.md".to_string()
+}
+
+fn default_image_file_name_pattern() -> String {
+  "{multilevel_num}-{index}".to_string()
+}
+
+fn default_assets_dir() -> String {
+  "{full_dir_of_original_file}/assets/".to_string()
+}
+
+fn default_link_timeout_secs() -> u64 {
+  10
+}
+
+fn default_link_concurrency() -> usize {
+  8
+}
+
+fn default_max_image_size() -> u64 {
+  DEFAULT_MAX_IMAGE_SIZE
+}
+
+fn default_publish_images_mode() -> PublishImagesMode {
+  PublishImagesMode::Inline
+}
+
+fn default_clean_assets_action() -> CleanAssetsAction {
+  CleanAssetsAction::Report
+}
+
+fn empty_path_mapping() -> BTreeMap<String, String> {
+  BTreeMap::new()
+}
+
+/// path_mapping 以对象形式传入，转换为按前缀长度降序排列的列表，最长前缀优先匹配
+fn deserialize_path_mapping<'de, D>(deserializer: D) -> std::result::Result<Vec<(String, String)>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let mut path_mapping: Vec<(String, String)> =
+    BTreeMap::<String, String>::deserialize(deserializer)?.into_iter().collect();
+  path_mapping.sort_by_key(|(local_prefix, _)| std::cmp::Reverse(local_prefix.len()));
+  Ok(path_mapping)
+}
+
+impl Default for ServerConfig {
+  fn default() -> Self {
+    Self {
+      transport: TransportKind::Stdio,
+      bind: DEFAULT_HTTP_BIND.to_string(),
+      port: DEFAULT_HTTP_PORT,
+      auth_token: None,
+      allow_dirs: Vec::new(),
+      lang: Lang::default(),
+    }
+  }
+}
+
+impl ServerConfig {
+  /// 从命令行参数（不含程序名）解析服务器配置
+  pub fn from_cli_args<I>(args: I) -> Result<Self>
+  where
+    I: IntoIterator<Item = String>,
+  {
+    let mut config = Self::default();
+    let mut lang = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+      // 同时支持 --name value 和 --name=value
+      let (name, inline_value) = match arg.split_once('=') {
+        Some((name, value)) => (name.to_string(), Some(value.to_string())),
+        None => (arg, None),
+      };
+      let mut value = || {
+        inline_value
+          .clone()
+          .or_else(|| args.next())
+          .ok_or_else(|| MarkdownError::ConfigError(tr!("参数 {} 缺少取值", name)))
+      };
+
+      match name.as_str() {
+        "--transport" => {
+          config.transport = match value()?.as_str() {
+            "stdio" => TransportKind::Stdio,
+            "http" => TransportKind::Http,
+            other => {
+              return Err(MarkdownError::ConfigError(tr!("不支持的传输方式: {}，可选值为 stdio 或 http", other)))
+            }
+          }
+        }
+        "--bind" => config.bind = value()?,
+        "--port" => {
+          let port = value()?;
+          config.port = port.parse().map_err(|_| MarkdownError::ConfigError(tr!("无效的端口: {}", port)))?;
+        }
+        "--auth-token" => config.auth_token = Some(value()?).filter(|token| !token.is_empty()),
+        "--allow-dir" => config.allow_dirs.push(canonicalize_allow_dir(&value()?)?),
+        "--lang" => {
+          let code = value()?;
+          lang = Some(
+            Lang::parse(&code)
+              .ok_or_else(|| MarkdownError::ConfigError(tr!("不支持的语言: {}，可选值为 zh 或 en", code)))?,
+          );
+        }
+        other => {
+          return Err(MarkdownError::ConfigError(tr!("未知参数: {}\n\n{}", other, i18n::translate(SERVER_USAGE))))
+        }
+      }
+    }
+
+    if config.auth_token.is_none() {
+      config.auth_token = std::env::var(AUTH_TOKEN_ENV).ok().filter(|token| !token.is_empty());
+    }
+    config.lang = lang.or_else(lang_from_env).unwrap_or_default();
+
+    Ok(config)
+  }
+
+  /// HTTP 传输监听的地址和端口
+  pub fn socket_addr(&self) -> Result<std::net::SocketAddr> {
+    use std::net::ToSocketAddrs;
+
+    (self.bind.as_str(), self.port)
+      .to_socket_addrs()
+      .map_err(|e| MarkdownError::ConfigError(tr!("无效的监听地址 {}:{} - {}", self.bind, self.port, e)))?
+      .next()
+      .ok_or_else(|| MarkdownError::ConfigError(tr!("无效的监听地址 {}:{}", self.bind, self.port)))
+  }
 }
 
 impl GenerateChapterConfig {
   pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
-    let args = args.ok_or_else(|| MarkdownError::ConfigError("缺少参数".to_string()))?;
+    parse_tool_args(args)
+  }
 
-    let full_file_path = args
-      .get("full_file_path")
-      .and_then(|v| v.as_str())
-      .ok_or_else(|| MarkdownError::ConfigError("缺少 full_file_path 参数".to_string()))?
-      .to_string();
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    let new_file_path = self.new_full_file_path.as_deref().filter(|path| self.save_as_new_file && !path.is_empty());
+    [Some(self.full_file_path.clone()), new_file_path.map(|path| PathTemplate::new(&self.full_file_path).resolve(path))]
+      .into_iter()
+      .flatten()
+      .collect()
+  }
+}
 
-    let ignore_h1 = args.get("ignore_h1").and_then(|v| v.as_bool()).unwrap_or(false);
+impl RemoveChapterConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    parse_tool_args(args)
+  }
 
-    let use_chinese_number = args.get("use_chinese_number").and_then(|v| v.as_bool()).unwrap_or(false);
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    let new_file_path = self.new_full_file_path.as_deref().filter(|path| self.save_as_new_file && !path.is_empty());
+    [Some(self.full_file_path.clone()), new_file_path.map(|path| PathTemplate::new(&self.full_file_path).resolve(path))]
+      .into_iter()
+      .flatten()
+      .collect()
+  }
+}
 
-    let use_arabic_number_for_sublevel =
-      args.get("use_arabic_number_for_sublevel").and_then(|v| v.as_bool()).unwrap_or(true);
+impl CheckHeadingConfig {
+  /// 使用默认检查规则创建配置
+  pub fn new(full_file_path: impl Into<String>) -> Self {
+    Self {
+      full_file_path: full_file_path.into(),
+      allow_skipped_levels: false,
+      max_heading_level: default_max_heading_level(),
+    }
+  }
+
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    parse_tool_args(args)
+  }
 
-    let save_as_new_file = args.get("save_as_new_file").and_then(|v| v.as_bool()).unwrap_or(false);
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    vec![self.full_file_path.clone()]
+  }
+}
 
-    let new_full_file_path = args.get("new_full_file_path").and_then(|v| v.as_str()).map(|s| s.to_string());
+impl OutlineConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    parse_tool_args(args)
+  }
 
-    Ok(Self {
-      full_file_path,
-      ignore_h1,
-      use_chinese_number,
-      use_arabic_number_for_sublevel,
-      save_as_new_file,
-      new_full_file_path,
-    })
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    vec![self.full_file_path.clone()]
   }
 }
 
-impl RemoveChapterConfig {
+impl CheckLinksConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    parse_tool_args(args)
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    vec![self.full_file_path.clone()]
+  }
+}
+
+impl ShowEffectiveConfigConfig {
   pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
-    let args = args.ok_or_else(|| MarkdownError::ConfigError("缺少参数".to_string()))?;
+    let mut config: Self = parse_tool_args(args)?;
+    config.tool = config.tool.filter(|s| !s.is_empty());
+    Ok(config)
+  }
+}
 
-    let full_file_path = args
-      .get("full_file_path")
-      .and_then(|v| v.as_str())
-      .ok_or_else(|| MarkdownError::ConfigError("缺少 full_file_path 参数".to_string()))?
-      .to_string();
+impl ShiftHeadingLevelsConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.anchor = config.anchor.filter(|s| !s.is_empty());
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+    Ok(config)
+  }
 
-    let save_as_new_file = args.get("save_as_new_file").and_then(|v| v.as_bool()).unwrap_or(false);
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
 
-    let new_full_file_path = args.get("new_full_file_path").and_then(|v| v.as_str()).map(|s| s.to_string());
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), self.get_resolved_new_file_path()].into_iter().flatten().collect()
+  }
+}
 
-    Ok(Self { full_file_path, save_as_new_file, new_full_file_path })
+impl ExtractSectionConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.output_file_path = config.output_file_path.filter(|s| !s.is_empty());
+    config.selector()?;
+    Ok(config)
+  }
+
+  /// 要提取的章节标题
+  pub fn selector(&self) -> Result<HeadingSelector> {
+    heading_selector(self.anchor.as_deref(), self.title_path.as_deref(), self.line_number, "")
+  }
+
+  /// 获取处理占位符后的新文档路径，未指定时为原文档同目录下的 `{file_stem}.md`
+  pub fn get_resolved_output_file_path(&self, file_stem: &str) -> String {
+    let template = PathTemplate::new(&self.full_file_path);
+    match self.output_file_path.as_deref() {
+      Some(path) => template.resolve(path),
+      None => template.resolve(&format!("{{full_dir_of_original_file}}/{}.md", file_stem)),
+    }
+  }
+
+  /// 工具会读写的路径，用于沙箱校验；未指定新文档路径时，解析出锚点后再校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    [Some(self.full_file_path.clone()), self.output_file_path.as_deref().map(|path| template.resolve(path))]
+      .into_iter()
+      .flatten()
+      .collect()
   }
 }
 
-impl CheckHeadingConfig {
+impl SplitDocumentConfig {
   pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
-    let args = args.ok_or_else(|| MarkdownError::ConfigError("缺少参数".to_string()))?;
+    let config: Self = parse_tool_args(args)?;
+    let index_file_name = Path::new(&config.index_file_name);
+    if index_file_name.file_name() != Some(index_file_name.as_os_str()) {
+      return Err(MarkdownError::ConfigError(tr!("index_file_name 只能是文件名: {}", config.index_file_name)));
+    }
+    Ok(config)
+  }
 
-    let full_file_path = args
-      .get("full_file_path")
-      .and_then(|v| v.as_str())
-      .ok_or_else(|| MarkdownError::ConfigError("缺少 full_file_path 参数".to_string()))?
-      .to_string();
+  /// 获取处理占位符后的保存目录
+  pub fn get_resolved_output_dir(&self) -> String {
+    PathTemplate::new(&self.full_file_path).resolve(&self.output_dir)
+  }
 
-    Ok(Self { full_file_path })
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    vec![self.full_file_path.clone(), self.get_resolved_output_dir()]
   }
 }
 
-impl LocalizeImagesConfig {
+impl MergeDocumentsConfig {
   pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
-    let args = args.ok_or_else(|| MarkdownError::ConfigError("缺少参数".to_string()))?;
+    let mut config: Self = parse_tool_args(args)?;
+    config.full_file_paths.retain(|path| !path.is_empty());
+    config.input_dir = config.input_dir.filter(|s| !s.is_empty());
+    config.title = config.title.filter(|s| !s.trim().is_empty());
 
-    let full_file_path = args
-      .get("full_file_path")
-      .and_then(|v| v.as_str())
-      .ok_or_else(|| MarkdownError::ConfigError("缺少 full_file_path 参数".to_string()))?
-      .to_string();
+    if config.full_file_paths.is_empty() == config.input_dir.is_none() {
+      return Err(MarkdownError::ConfigError(tr!("full_file_paths 和 input_dir 必须且只能提供其中一个").to_string()));
+    }
+    if config.title.is_some() && config.first_file_as_parent {
+      return Err(MarkdownError::ConfigError(tr!("title 和 first_file_as_parent 不能同时使用").to_string()));
+    }
+    Ok(config)
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    let mut paths = self.full_file_paths.clone();
+    paths.extend(self.input_dir.clone());
+    paths.push(self.output_file_path.clone());
+    paths
+  }
+}
+
+impl MoveSectionConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+    config.selector()?;
+    config.target_selector()?;
+    Ok(config)
+  }
+
+  /// 要移动的章节标题
+  pub fn selector(&self) -> Result<HeadingSelector> {
+    heading_selector(self.anchor.as_deref(), self.title_path.as_deref(), self.line_number, "")
+  }
+
+  /// 目标标题
+  pub fn target_selector(&self) -> Result<HeadingSelector> {
+    heading_selector(
+      self.target_anchor.as_deref(),
+      self.target_title_path.as_deref(),
+      self.target_line_number,
+      "target_",
+    )
+  }
+
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), self.get_resolved_new_file_path()].into_iter().flatten().collect()
+  }
+}
+
+impl DeleteSectionConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+    config.selector()?;
+    Ok(config)
+  }
+
+  /// 要删除的章节标题
+  pub fn selector(&self) -> Result<HeadingSelector> {
+    heading_selector(self.anchor.as_deref(), self.title_path.as_deref(), self.line_number, "")
+  }
+
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
 
-    let image_file_name_pattern =
-      args.get("image_file_name_pattern").and_then(|v| v.as_str()).unwrap_or("{multilevel_num}-{index}").to_string();
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), self.get_resolved_new_file_path()].into_iter().flatten().collect()
+  }
+}
 
-    let image_dir =
-      args.get("image_dir").and_then(|v| v.as_str()).unwrap_or("{full_dir_of_original_file}/assets/").to_string();
+impl ReadSectionConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let config: Self = parse_tool_args(args)?;
+    config.selector()?;
+    Ok(config)
+  }
 
-    let new_full_file_path = args.get("new_full_file_path").and_then(|v| v.as_str()).map(|s| s.to_string());
+  /// 要读取的章节标题
+  pub fn selector(&self) -> Result<HeadingSelector> {
+    section_selector(self.anchor.as_deref(), self.title_path.as_deref(), self.number_path.as_deref(), self.line_number)
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    vec![self.full_file_path.clone()]
+  }
+}
+
+impl ReplaceSectionConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+    config.selector()?;
+    Ok(config)
+  }
+
+  /// 要替换的章节标题
+  pub fn selector(&self) -> Result<HeadingSelector> {
+    section_selector(self.anchor.as_deref(), self.title_path.as_deref(), self.number_path.as_deref(), self.line_number)
+  }
 
-    Ok(Self { full_file_path, image_file_name_pattern, image_dir, new_full_file_path })
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), self.get_resolved_new_file_path()].into_iter().flatten().collect()
+  }
+}
+
+impl LocalizeImagesConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+    Ok(config)
   }
 
   /// 获取处理占位符后的保存目录
   pub fn get_resolved_save_dir(&self) -> String {
-    use std::path::Path;
-
-    if self.image_dir.contains("{full_dir_of_original_file}") {
-      let path = Path::new(&self.full_file_path);
-      let full_dir = path.parent().unwrap_or(Path::new(".")).to_str().unwrap_or(".");
-      self.image_dir.replace("{full_dir_of_original_file}", full_dir)
-    } else {
-      self.image_dir.clone()
+    PathTemplate::new(&self.full_file_path).resolve(&self.image_dir)
+  }
+
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), Some(self.get_resolved_save_dir()), self.get_resolved_new_file_path()]
+      .into_iter()
+      .flatten()
+      .collect()
+  }
+}
+
+impl PublishImagesConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.base_url = config.base_url.filter(|s| !s.is_empty());
+    config.new_full_file_path = config.new_full_file_path.filter(|s| !s.is_empty());
+
+    if config.mode == PublishImagesMode::BaseUrl && config.base_url.is_none() && config.path_mapping.is_empty() {
+      return Err(MarkdownError::ConfigError(tr!("mode 为 base_url 时必须提供 base_url 或 path_mapping").to_string()));
+    }
+
+    Ok(config)
+  }
+
+  /// 获取处理占位符后的新文件路径，为空时覆盖原文件
+  pub fn get_resolved_new_file_path(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_path);
+    self.new_full_file_path.as_deref().map(|path| template.resolve(path))
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    [Some(self.full_file_path.clone()), self.get_resolved_new_file_path()].into_iter().flatten().collect()
+  }
+}
+
+impl CleanAssetsConfig {
+  pub fn from_args(args: Option<&Map<String, Value>>) -> Result<Self> {
+    let mut config: Self = parse_tool_args(args)?;
+    config.trash_dir = config.trash_dir.filter(|s| !s.is_empty());
+    Ok(config)
+  }
+
+  /// 获取处理占位符后的资源目录
+  pub fn get_resolved_assets_dir(&self) -> String {
+    PathTemplate::new(&self.full_file_paths[0]).resolve(&self.assets_dir)
+  }
+
+  /// 获取处理占位符后的回收目录，未配置时返回 None
+  pub fn get_resolved_trash_dir(&self) -> Option<String> {
+    let template = PathTemplate::new(&self.full_file_paths[0]);
+    self.trash_dir.as_deref().map(|dir| template.resolve(dir))
+  }
+
+  /// 工具会读写的路径，用于沙箱校验
+  pub fn accessed_paths(&self) -> Vec<String> {
+    let mut paths = self.full_file_paths.clone();
+    paths.push(self.get_resolved_assets_dir());
+    paths.extend(self.get_resolved_trash_dir());
+    paths
+  }
+}
+
+/// 由 anchor、title_path、line_number 参数（参数名带有 `prefix` 前缀）确定要定位的标题，必须且只能提供其中一个，空字符串视为未提供
+fn heading_selector(
+  anchor: Option<&str>,
+  title_path: Option<&str>,
+  line_number: Option<usize>,
+  prefix: &str,
+) -> Result<HeadingSelector> {
+  let selectors: Vec<HeadingSelector> = [
+    anchor.filter(|s| !s.is_empty()).map(|s| HeadingSelector::Anchor(s.to_string())),
+    title_path.filter(|s| !s.is_empty()).map(|s| HeadingSelector::TitlePath(s.to_string())),
+    line_number.map(HeadingSelector::Line),
+  ]
+  .into_iter()
+  .flatten()
+  .collect();
+
+  match <[HeadingSelector; 1]>::try_from(selectors) {
+    Ok([selector]) => Ok(selector),
+    Err(_) => {
+      Err(MarkdownError::ConfigError(tr!("{0}anchor、{0}title_path、{0}line_number 必须且只能提供其中一个", prefix)))
+    }
+  }
+}
+
+/// 由 anchor、title_path、number_path、line_number 参数确定要定位的章节，必须且只能提供其中一个，空字符串视为未提供
+fn section_selector(
+  anchor: Option<&str>,
+  title_path: Option<&str>,
+  number_path: Option<&str>,
+  line_number: Option<usize>,
+) -> Result<HeadingSelector> {
+  let number_path = number_path.filter(|s| !s.is_empty()).map(|s| HeadingSelector::NumberPath(s.to_string()));
+  let no_other_selector =
+    anchor.unwrap_or_default().is_empty() && title_path.unwrap_or_default().is_empty() && line_number.is_none();
+  match (number_path, heading_selector(anchor, title_path, line_number, "")) {
+    (None, Ok(selector)) => Ok(selector),
+    (Some(selector), Err(_)) if no_other_selector => Ok(selector),
+    _ => Err(MarkdownError::ConfigError(
+      tr!("anchor、title_path、number_path、line_number 必须且只能提供其中一个").to_string(),
+    )),
+  }
+}
+
+/// 已注册的工具：名称和参数类型
+pub struct ToolSpec {
+  /// 工具名称
+  pub name: &'static str,
+  /// 参数类型的 JSON Schema
+  schema: fn() -> Map<String, Value>,
+}
+
+impl ToolSpec {
+  const fn new<T: JsonSchema>(name: &'static str) -> Self {
+    Self { name, schema: tool_schema::<T> }
+  }
+
+  /// 生成工具参数的 JSON Schema，作为 MCP 工具的 inputSchema
+  ///
+  /// 额外包含所有工具通用的 `lang` 参数，说明按当前语言翻译
+  pub fn input_schema(&self) -> Arc<Map<String, Value>> {
+    input_schema_with_lang((self.schema)())
+  }
+
+  /// 工具支持的参数名，不包括 `lang`
+  pub fn arg_names(&self) -> Vec<String> {
+    schema_property_names(&(self.schema)())
+  }
+}
+
+/// 服务器提供的所有工具，按 list_tools 返回的顺序排列
+pub const TOOLS: &[ToolSpec] = &[
+  ToolSpec::new::<CheckHeadingConfig>("check_heading"),
+  ToolSpec::new::<OutlineConfig>("outline"),
+  ToolSpec::new::<CheckLinksConfig>("check_links"),
+  ToolSpec::new::<GenerateChapterConfig>("generate_chapter_number"),
+  ToolSpec::new::<RemoveChapterConfig>("remove_all_chapter_numbers"),
+  ToolSpec::new::<LocalizeImagesConfig>("localize_images"),
+  ToolSpec::new::<PublishImagesConfig>("publish_images"),
+  ToolSpec::new::<CleanAssetsConfig>("clean_assets"),
+  ToolSpec::new::<ShiftHeadingLevelsConfig>("shift_heading_levels"),
+  ToolSpec::new::<ReadSectionConfig>("read_section"),
+  ToolSpec::new::<ReplaceSectionConfig>("replace_section"),
+  ToolSpec::new::<MoveSectionConfig>("move_section"),
+  ToolSpec::new::<DeleteSectionConfig>("delete_section"),
+  ToolSpec::new::<ExtractSectionConfig>("extract_section"),
+  ToolSpec::new::<SplitDocumentConfig>("split_document"),
+  ToolSpec::new::<MergeDocumentsConfig>("merge_documents"),
+  ToolSpec::new::<ShowEffectiveConfigConfig>("show_effective_config"),
+];
+
+/// 按名称查找已注册的工具
+pub fn find_tool(name: &str) -> Option<&'static ToolSpec> {
+  TOOLS.iter().find(|tool| tool.name == name)
+}
+
+/// 在参数的 JSON Schema 中加入所有工具通用的 `lang` 参数，并按当前语言翻译说明
+fn input_schema_with_lang(mut schema: Map<String, Value>) -> Arc<Map<String, Value>> {
+  if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+    properties.insert(
+      i18n::LANG_ARG.to_string(),
+      serde_json::json!({
+        "type": "string",
+        "enum": ["zh", "en"],
+        "description": "返回信息使用的语言，默认使用服务器的语言设置",
+      }),
+    );
+  }
+  i18n::translate_schema_descriptions(&mut schema);
+  Arc::new(schema)
+}
+
+/// 环境变量 MCP_MARKDOWN_TOOLS_LANG 指定的语言，未设置或无法识别时返回 None
+pub fn lang_from_env() -> Option<Lang> {
+  std::env::var(LANG_ENV).ok().and_then(|code| Lang::parse(&code))
+}
+
+/// 工具支持的参数名
+pub(crate) fn tool_arg_names<T: JsonSchema>() -> Vec<String> {
+  schema_property_names(&tool_schema::<T>())
+}
+
+/// JSON Schema 中的属性名
+fn schema_property_names(schema: &Map<String, Value>) -> Vec<String> {
+  match schema.get("properties") {
+    Some(Value::Object(properties)) => properties.keys().cloned().collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// 生成内联所有子模式、不含元信息的 JSON Schema 对象
+fn tool_schema<T: JsonSchema>() -> Map<String, Value> {
+  let settings = SchemaSettings::draft07().with(|settings| {
+    settings.option_add_null_type = false;
+    settings.inline_subschemas = true;
+  });
+  let schema = settings.into_generator().into_root_schema_for::<T>();
+
+  let mut schema = match serde_json::to_value(schema) {
+    Ok(Value::Object(schema)) => schema,
+    _ => Map::new(),
+  };
+  schema.remove("$schema");
+  schema.remove("title");
+  schema.remove("definitions");
+  schema
+}
+
+/// 按工具参数的 JSON Schema 校验并解析参数
+///
+/// 校验在反序列化之前进行，以便在错误信息中指明具体的参数名
+fn parse_tool_args<T: DeserializeOwned + JsonSchema>(args: Option<&Map<String, Value>>) -> Result<T> {
+  let args = args.ok_or_else(|| MarkdownError::ConfigError(tr!("缺少参数").to_string()))?;
+  let schema = tool_schema::<T>();
+
+  // 可选参数传入 null 时视为未提供，使用默认值；必填参数的 null 由校验报告类型错误
+  let required: Vec<&str> =
+    schema.get("required").and_then(|v| v.as_array()).into_iter().flatten().filter_map(|v| v.as_str()).collect();
+  let args: Map<String, Value> = args
+    .iter()
+    .filter(|(key, value)| !value.is_null() || required.contains(&key.as_str()))
+    .map(|(key, value)| (key.clone(), value.clone()))
+    .collect();
+
+  validate_value("", &Value::Object(args.clone()), &Value::Object(schema))?;
+
+  serde_json::from_value(Value::Object(args)).map_err(|e| MarkdownError::ConfigError(tr!("参数错误: {}", e)))
+}
+
+/// 按 JSON Schema 校验参数值，支持本项目工具参数用到的关键字
+fn validate_value(name: &str, value: &Value, schema: &Value) -> Result<()> {
+  let invalid = |message: String| Err(MarkdownError::ConfigError(message));
+
+  // 只含枚举值的 oneOf（带说明的枚举）
+  let variants = schema.get("oneOf").and_then(|v| v.as_array());
+  let allowed: Vec<&Value> = match variants {
+    Some(variants) => variants.iter().filter_map(|v| v.get("enum")?.as_array()).flatten().collect(),
+    None => schema.get("enum").and_then(|v| v.as_array()).map(|v| v.iter().collect()).unwrap_or_default(),
+  };
+  let expected_type =
+    schema.get("type").or_else(|| variants.and_then(|variants| variants.first()?.get("type"))).and_then(|v| v.as_str());
+
+  if let Some(expected_type) = expected_type {
+    let matches = match expected_type {
+      "string" => value.is_string(),
+      "boolean" => value.is_boolean(),
+      "integer" => value.is_i64() || value.is_u64(),
+      "number" => value.is_number(),
+      "array" => value.is_array(),
+      "object" => value.is_object(),
+      _ => true,
+    };
+    if !matches {
+      return invalid(tr!("参数 {} 的类型应为 {}，实际为 {}", name, expected_type, json_type_name(value)));
     }
   }
+
+  if !allowed.is_empty() && !allowed.contains(&value) {
+    let options: Vec<String> = allowed.iter().map(|v| v.to_string()).collect();
+    return invalid(tr!("参数 {} 的值 {} 无效，可选值为: {}", name, value, options.join(", ")));
+  }
+
+  let minimum = schema.get("minimum").and_then(|v| v.as_f64());
+  if let Some(minimum) = minimum.filter(|minimum| value.as_f64().is_some_and(|number| number < *minimum)) {
+    return invalid(tr!("参数 {} 不能小于 {}", name, minimum));
+  }
+  let maximum = schema.get("maximum").and_then(|v| v.as_f64());
+  if let Some(maximum) = maximum.filter(|maximum| value.as_f64().is_some_and(|number| number > *maximum)) {
+    return invalid(tr!("参数 {} 不能大于 {}", name, maximum));
+  }
+
+  if let Some(items) = value.as_array() {
+    let min_items = schema.get("minItems").and_then(|v| v.as_u64()).unwrap_or(0);
+    if (items.len() as u64) < min_items {
+      return invalid(tr!("参数 {} 至少需要包含 {} 个元素", name, min_items));
+    }
+    if let Some(item_schema) = schema.get("items") {
+      for (i, item) in items.iter().enumerate() {
+        validate_value(&format!("{}[{}]", name, i), item, item_schema)?;
+      }
+    }
+  }
+
+  if let Some(object) = value.as_object() {
+    let properties = schema.get("properties").and_then(|v| v.as_object());
+    let additional = schema.get("additionalProperties");
+    let field_name = |key: &str| if name.is_empty() { key.to_string() } else { format!("{}.{}", name, key) };
+
+    for required in schema.get("required").and_then(|v| v.as_array()).into_iter().flatten() {
+      let required = required.as_str().unwrap_or_default();
+      if !object.contains_key(required) {
+        return invalid(tr!("缺少 {} 参数", field_name(required)));
+      }
+    }
+
+    for (key, item) in object {
+      match (properties.and_then(|properties| properties.get(key)), additional) {
+        (Some(property_schema), _) => validate_value(&field_name(key), item, property_schema)?,
+        (None, Some(Value::Bool(false))) => {
+          let known: Vec<&str> = properties.into_iter().flat_map(|p| p.keys()).map(|k| k.as_str()).collect();
+          return invalid(tr!("未知参数: {}，可用的参数有: {}", field_name(key), known.join(", ")));
+        }
+        (None, Some(additional_schema)) => validate_value(&field_name(key), item, additional_schema)?,
+        (None, None) => {}
+      }
+    }
+  }
+
+  Ok(())
+}
+
+/// JSON 值的类型名称，用于错误信息
+fn json_type_name(value: &Value) -> &'static str {
+  match value {
+    Value::Null => "null",
+    Value::Bool(_) => "boolean",
+    Value::Number(n) if n.is_f64() => "number",
+    Value::Number(_) => "integer",
+    Value::String(_) => "string",
+    Value::Array(_) => "array",
+    Value::Object(_) => "object",
+  }
 }
 
 #[cfg(test)]
@@ -149,10 +1319,10 @@ mod tests {
     let config = GenerateChapterConfig::from_args(Some(&args)).unwrap();
 
     assert_eq!(config.full_file_path, "/path/to/file.md");
-    assert_eq!(config.ignore_h1, true);
-    assert_eq!(config.use_chinese_number, true);
-    assert_eq!(config.use_arabic_number_for_sublevel, false);
-    assert_eq!(config.save_as_new_file, true);
+    assert!(config.ignore_h1);
+    assert!(config.use_chinese_number);
+    assert!(!config.use_arabic_number_for_sublevel);
+    assert!(config.save_as_new_file);
     assert_eq!(config.new_full_file_path, Some("/path/to/new_file.md".to_string()));
   }
 
@@ -165,10 +1335,10 @@ mod tests {
     let config = GenerateChapterConfig::from_args(Some(&args)).unwrap();
 
     assert_eq!(config.full_file_path, "/path/to/file.md");
-    assert_eq!(config.ignore_h1, false);
-    assert_eq!(config.use_chinese_number, false);
-    assert_eq!(config.use_arabic_number_for_sublevel, true);
-    assert_eq!(config.save_as_new_file, false);
+    assert!(!config.ignore_h1);
+    assert!(!config.use_chinese_number);
+    assert!(config.use_arabic_number_for_sublevel);
+    assert!(!config.save_as_new_file);
     assert_eq!(config.new_full_file_path, None);
   }
 
@@ -203,7 +1373,132 @@ mod tests {
 
     assert!(result.is_err());
     let error_msg = result.unwrap_err().to_string();
-    assert!(error_msg.contains("缺少 full_file_path 参数"));
+    assert!(error_msg.contains("参数 full_file_path 的类型应为 string，实际为 integer"), "{}", error_msg);
+
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+    args.insert("ignore_h1".to_string(), Value::String("true".to_string()));
+    let error_msg = GenerateChapterConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("参数 ignore_h1 的类型应为 boolean，实际为 string"), "{}", error_msg);
+  }
+
+  /// 测试可选参数显式传入 null 时按未提供处理，必填参数不能为 null
+  #[test]
+  fn test_config_accepts_null_for_optional_args() {
+    let args = serde_json::json!({ "full_file_path": "/docs/a.md", "tool": null });
+    let config = ShowEffectiveConfigConfig::from_args(args.as_object()).unwrap();
+    assert_eq!(config.tool, None);
+
+    let args = serde_json::json!({ "full_file_path": "/docs/a.md", "timeout_secs": null, "ignore_domains": null });
+    let config = CheckLinksConfig::from_args(args.as_object()).unwrap();
+    assert_eq!(config.timeout_secs, 10);
+    assert!(config.ignore_domains.is_empty());
+
+    let args = serde_json::json!({ "full_file_path": null });
+    let error_msg = ShowEffectiveConfigConfig::from_args(args.as_object()).unwrap_err().to_string();
+    assert!(error_msg.contains("参数 full_file_path 的类型应为 string，实际为 null"), "{}", error_msg);
+  }
+
+  /// 测试拒绝未知参数
+  #[test]
+  fn test_config_rejects_unknown_args() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+    args.insert("ignore_h2".to_string(), Value::Bool(true));
+
+    let error_msg = GenerateChapterConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("未知参数: ignore_h2"), "{}", error_msg);
+    assert!(error_msg.contains("ignore_h1"), "应列出可用的参数: {}", error_msg);
+
+    let error_msg = CheckHeadingConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("未知参数: ignore_h2"), "{}", error_msg);
+  }
+
+  /// 测试嵌套参数的错误信息包含路径
+  #[test]
+  fn test_config_nested_arg_errors() {
+    let mut args = Map::new();
+    args.insert("full_file_paths".to_string(), serde_json::json!(["/docs/a.md", 1]));
+    let error_msg = CleanAssetsConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("参数 full_file_paths[1] 的类型应为 string"), "{}", error_msg);
+
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/a.md".to_string()));
+    args.insert("path_mapping".to_string(), serde_json::json!({ "assets/": false }));
+    let error_msg = PublishImagesConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("参数 path_mapping.assets/ 的类型应为 string"), "{}", error_msg);
+
+    args.insert("path_mapping".to_string(), serde_json::json!({}));
+    args.insert("mode".to_string(), Value::String("upload".to_string()));
+    let error_msg = PublishImagesConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("可选值为: \"inline\", \"base_url\""), "{}", error_msg);
+  }
+
+  /// 测试由配置结构生成的 JSON Schema
+  #[test]
+  fn test_tool_input_schema() {
+    let schema = find_tool("generate_chapter_number").unwrap().input_schema();
+    assert_eq!(schema["type"], "object");
+    assert_eq!(schema["required"], serde_json::json!(["full_file_path"]));
+    assert_eq!(schema["additionalProperties"], false);
+    assert_eq!(schema["properties"]["use_arabic_number_for_sublevel"]["default"], true);
+    assert_eq!(
+      schema["properties"]["new_full_file_path"]["default"],
+      "{full_dir_of_original_file}/{original_file_name}_numed.md"
+    );
+    assert_eq!(schema["properties"]["new_full_file_path"]["type"], "string");
+    assert!(schema.get("$schema").is_none());
+
+    let schema = find_tool("localize_images").unwrap().input_schema();
+    assert_eq!(schema["properties"]["max_image_size"]["default"], DEFAULT_MAX_IMAGE_SIZE);
+    assert_eq!(schema["properties"]["image_dir"]["default"], "{full_dir_of_original_file}/assets/");
+
+    let schema = find_tool("clean_assets").unwrap().input_schema();
+    assert_eq!(schema["properties"]["action"]["default"], "report");
+    assert_eq!(schema["properties"]["full_file_paths"]["minItems"], 1);
+
+    let schema = find_tool("publish_images").unwrap().input_schema();
+    assert_eq!(schema["properties"]["path_mapping"]["default"], serde_json::json!({}));
+    assert_eq!(schema["properties"]["path_mapping"]["additionalProperties"]["type"], "string");
+  }
+
+  /// 收集 JSON Schema 中的所有说明
+  fn schema_descriptions(value: &Value, descriptions: &mut Vec<String>) {
+    match value {
+      Value::Object(object) => {
+        for (key, item) in object {
+          match item {
+            Value::String(text) if key == "description" => descriptions.push(text.clone()),
+            _ => schema_descriptions(item, descriptions),
+          }
+        }
+      }
+      Value::Array(items) => items.iter().for_each(|item| schema_descriptions(item, descriptions)),
+      _ => {}
+    }
+  }
+
+  /// 测试 JSON Schema 包含 lang 参数，且英文下所有参数说明都有翻译
+  #[tokio::test]
+  async fn test_tool_input_schema_lang() {
+    fn all_descriptions() -> Vec<String> {
+      let mut descriptions = Vec::new();
+      for schema in TOOLS.iter().map(ToolSpec::input_schema) {
+        assert_eq!(schema["properties"][i18n::LANG_ARG]["enum"], serde_json::json!(["zh", "en"]));
+        schema_descriptions(&Value::Object((*schema).clone()), &mut descriptions);
+      }
+      descriptions
+    }
+
+    let zh = i18n::with_lang(Lang::Zh, async { all_descriptions() }).await;
+    let en = i18n::with_lang(Lang::En, async { all_descriptions() }).await;
+    assert_eq!(zh.len(), en.len());
+    for (zh, en) in zh.iter().zip(&en) {
+      assert_ne!(zh, en, "参数说明缺少英文翻译: {}", zh);
+    }
+
+    // lang 参数不参与配置解析和项目配置
+    assert!(!tool_arg_names::<CheckHeadingConfig>().contains(&i18n::LANG_ARG.to_string()));
+    assert!(TOOLS.iter().all(|tool| !tool.arg_names().contains(&i18n::LANG_ARG.to_string())));
   }
 
   /// 测试 RemoveChapterConfig 的有效参数解析
@@ -217,7 +1512,7 @@ mod tests {
     let config = RemoveChapterConfig::from_args(Some(&args)).unwrap();
 
     assert_eq!(config.full_file_path, "/path/to/file.md");
-    assert_eq!(config.save_as_new_file, true);
+    assert!(config.save_as_new_file);
     assert_eq!(config.new_full_file_path, Some("/path/to/new_file.md".to_string()));
   }
 
@@ -230,7 +1525,7 @@ mod tests {
     let config = RemoveChapterConfig::from_args(Some(&args)).unwrap();
 
     assert_eq!(config.full_file_path, "/path/to/file.md");
-    assert_eq!(config.save_as_new_file, false);
+    assert!(!config.save_as_new_file);
     assert_eq!(config.new_full_file_path, None);
   }
 
@@ -252,12 +1547,16 @@ mod tests {
     args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
     args.insert("image_file_name_pattern".to_string(), Value::String("{index}-{hash}".to_string()));
     args.insert("image_dir".to_string(), Value::String("/path/to/images/".to_string()));
+    args.insert("localize_data_uris".to_string(), Value::Bool(true));
+    args.insert("copy_local_images".to_string(), Value::Bool(true));
 
     let config = LocalizeImagesConfig::from_args(Some(&args)).unwrap();
 
     assert_eq!(config.full_file_path, "/path/to/file.md");
     assert_eq!(config.image_file_name_pattern, "{index}-{hash}");
     assert_eq!(config.image_dir, "/path/to/images/");
+    assert!(config.localize_data_uris);
+    assert!(config.copy_local_images);
   }
 
   /// 测试 LocalizeImagesConfig 的默认值
@@ -271,6 +1570,39 @@ mod tests {
     assert_eq!(config.full_file_path, "/path/to/file.md");
     assert_eq!(config.image_file_name_pattern, "{multilevel_num}-{index}");
     assert_eq!(config.image_dir, "{full_dir_of_original_file}/assets/");
+    assert_eq!(config.max_image_size, DEFAULT_MAX_IMAGE_SIZE);
+    assert!(!config.localize_data_uris);
+    assert!(!config.copy_local_images);
+  }
+
+  /// 测试 LocalizeImagesConfig 的 max_image_size 参数
+  #[test]
+  fn test_localize_images_config_max_image_size() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+    args.insert("max_image_size".to_string(), Value::Number(2048.into()));
+
+    let config = LocalizeImagesConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.max_image_size, 2048);
+
+    args.insert("max_image_size".to_string(), Value::Number(0.into()));
+    let result = LocalizeImagesConfig::from_args(Some(&args));
+    assert!(result.unwrap_err().to_string().contains("参数 max_image_size 不能小于 1"));
+  }
+
+  /// 测试 CheckHeadingConfig 的检查规则参数
+  #[test]
+  fn test_check_heading_config_lint_args() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+
+    let config = CheckHeadingConfig::from_args(Some(&args)).unwrap();
+    assert!(!config.allow_skipped_levels);
+    assert_eq!(config.max_heading_level, 6);
+
+    args.insert("max_heading_level".to_string(), Value::Number(7.into()));
+    let result = CheckHeadingConfig::from_args(Some(&args));
+    assert!(result.unwrap_err().to_string().contains("参数 max_heading_level 不能大于 6"));
   }
 
   /// 测试 LocalizeImagesConfig 的 get_resolved_save_dir 方法
@@ -313,6 +1645,33 @@ mod tests {
     assert_eq!(resolved_dir, "/assets/");
   }
 
+  /// 测试新文件路径和回收目录中的占位符
+  #[test]
+  fn test_resolved_paths_with_placeholders() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/guide.md".to_string()));
+    args.insert(
+      "new_full_file_path".to_string(),
+      Value::String("{full_dir_of_original_file}/out/{original_file_name}-{date}.{ext}".to_string()),
+    );
+
+    let config = LocalizeImagesConfig::from_args(Some(&args)).unwrap();
+    let expected = format!("/docs/out/guide-{}.md", chrono::Local::now().format("%Y-%m-%d"));
+    assert_eq!(config.get_resolved_new_file_path(), Some(expected.clone()));
+
+    let config = PublishImagesConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.get_resolved_new_file_path(), Some(expected));
+
+    let mut args = Map::new();
+    args.insert("full_file_paths".to_string(), serde_json::json!(["/docs/guide.md"]));
+    args.insert(
+      "trash_dir".to_string(),
+      Value::String("{full_dir_of_original_file}/.trash-{original_file_name}".to_string()),
+    );
+    let config = CleanAssetsConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.get_resolved_trash_dir(), Some("/docs/.trash-guide".to_string()));
+  }
+
   /// 测试配置结构的 Clone 和 Debug 特性
   #[test]
   fn test_config_traits() {
@@ -329,4 +1688,256 @@ mod tests {
     assert!(debug_output.contains("GenerateChapterConfig"));
     assert!(debug_output.contains("/path/to/file.md"));
   }
+
+  /// 测试 PublishImagesConfig 的参数解析
+  #[test]
+  fn test_publish_images_config_from_args() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+
+    let config = PublishImagesConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.mode, PublishImagesMode::Inline);
+    assert!(config.base_url.is_none());
+    assert!(config.path_mapping.is_empty());
+    assert_eq!(config.max_image_size, DEFAULT_MAX_IMAGE_SIZE);
+
+    let mut mapping = Map::new();
+    mapping.insert("assets/".to_string(), Value::String("https://cdn.example.com/a/".to_string()));
+    mapping.insert("assets/icons/".to_string(), Value::String("https://cdn.example.com/i/".to_string()));
+    args.insert("mode".to_string(), Value::String("base_url".to_string()));
+    args.insert("path_mapping".to_string(), Value::Object(mapping));
+
+    let config = PublishImagesConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.mode, PublishImagesMode::BaseUrl);
+    assert_eq!(config.path_mapping[0].0, "assets/icons/");
+    assert_eq!(config.path_mapping[1].0, "assets/");
+  }
+
+  /// 测试 PublishImagesConfig 的无效参数
+  #[test]
+  fn test_publish_images_config_invalid_args() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/path/to/file.md".to_string()));
+    args.insert("mode".to_string(), Value::String("base_url".to_string()));
+
+    // base_url 模式缺少 URL 配置
+    assert!(matches!(PublishImagesConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+
+    args.insert("mode".to_string(), Value::String("upload".to_string()));
+    assert!(matches!(PublishImagesConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+  }
+
+  /// 测试 CleanAssetsConfig 的参数解析
+  #[test]
+  fn test_clean_assets_config_from_args() {
+    let mut args = Map::new();
+    args.insert(
+      "full_file_paths".to_string(),
+      Value::Array(vec![Value::String("/docs/a.md".to_string()), Value::String("/docs/b.md".to_string())]),
+    );
+
+    let config = CleanAssetsConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.full_file_paths.len(), 2);
+    assert_eq!(config.action, CleanAssetsAction::Report);
+    assert_eq!(config.get_resolved_assets_dir(), "/docs/assets/");
+    assert!(config.trash_dir.is_none());
+
+    args.insert("action".to_string(), Value::String("trash".to_string()));
+    args.insert("trash_dir".to_string(), Value::String("/tmp/trash".to_string()));
+    let config = CleanAssetsConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.action, CleanAssetsAction::Trash);
+    assert_eq!(config.trash_dir, Some("/tmp/trash".to_string()));
+  }
+
+  /// 测试 CleanAssetsConfig 的无效参数
+  #[test]
+  fn test_clean_assets_config_invalid_args() {
+    let mut args = Map::new();
+    assert!(CleanAssetsConfig::from_args(Some(&args)).is_err());
+
+    args.insert("full_file_paths".to_string(), Value::Array(vec![]));
+    assert!(CleanAssetsConfig::from_args(Some(&args)).is_err());
+
+    args.insert("full_file_paths".to_string(), Value::Array(vec![Value::String("/docs/a.md".to_string())]));
+    args.insert("action".to_string(), Value::String("purge".to_string()));
+    assert!(matches!(CleanAssetsConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+  }
+
+  /// 测试 ShiftHeadingLevelsConfig 的参数解析和级别范围校验
+  #[test]
+  fn test_shift_heading_levels_config_from_args() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/a.md".to_string()));
+    args.insert("shift".to_string(), Value::from(-2));
+    args.insert("anchor".to_string(), Value::String(String::new()));
+    let config = ShiftHeadingLevelsConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.shift, -2);
+    assert_eq!(config.anchor, None);
+    assert!(!config.clamp);
+    assert!(!config.renumber);
+
+    args.insert("shift".to_string(), Value::from(6));
+    let error_msg = ShiftHeadingLevelsConfig::from_args(Some(&args)).unwrap_err().to_string();
+    assert!(error_msg.contains("shift"), "{}", error_msg);
+
+    args.remove("shift");
+    assert!(ShiftHeadingLevelsConfig::from_args(Some(&args)).is_err());
+  }
+
+  /// 测试 ExtractSectionConfig 必须且只能提供一种标题定位方式
+  #[test]
+  fn test_extract_section_config_selector() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/a.md".to_string()));
+    assert!(matches!(ExtractSectionConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+
+    args.insert("title_path".to_string(), Value::String("设计/存储".to_string()));
+    let config = ExtractSectionConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.selector().unwrap(), HeadingSelector::TitlePath("设计/存储".to_string()));
+    assert_eq!(config.get_resolved_output_file_path("存储"), "/docs/存储.md");
+
+    args.insert("line_number".to_string(), Value::from(3));
+    assert!(ExtractSectionConfig::from_args(Some(&args)).is_err());
+
+    args.insert("title_path".to_string(), Value::String(String::new()));
+    let config = ExtractSectionConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.selector().unwrap(), HeadingSelector::Line(3));
+  }
+
+  /// 测试 SplitDocumentConfig 的默认值和索引文件名校验
+  #[test]
+  fn test_split_document_config() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/guide.md".to_string()));
+    let config = SplitDocumentConfig::from_args(Some(&args)).unwrap();
+    assert_eq!((config.level, config.index_file_name.as_str()), (1, "index.md"));
+    assert_eq!(config.get_resolved_output_dir(), "/docs/guide");
+
+    args.insert("level".to_string(), Value::from(7));
+    assert!(SplitDocumentConfig::from_args(Some(&args)).is_err());
+
+    args.insert("level".to_string(), Value::from(2));
+    args.insert("index_file_name".to_string(), Value::String("../index.md".to_string()));
+    assert!(matches!(SplitDocumentConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+  }
+
+  /// 测试 MergeDocumentsConfig 的输入来源和上级标题校验
+  #[test]
+  fn test_merge_documents_config() {
+    let mut args = Map::new();
+    args.insert("output_file_path".to_string(), Value::String("/docs/all.md".to_string()));
+    assert!(matches!(MergeDocumentsConfig::from_args(Some(&args)), Err(MarkdownError::ConfigError(_))));
+
+    args.insert("input_dir".to_string(), Value::String("/docs/parts".to_string()));
+    let config = MergeDocumentsConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.accessed_paths(), ["/docs/parts", "/docs/all.md"]);
+
+    args.insert("full_file_paths".to_string(), serde_json::json!(["/docs/a.md"]));
+    assert!(MergeDocumentsConfig::from_args(Some(&args)).is_err());
+
+    args.remove("input_dir");
+    args.insert("title".to_string(), Value::String("指南".to_string()));
+    args.insert("first_file_as_parent".to_string(), Value::Bool(true));
+    assert!(MergeDocumentsConfig::from_args(Some(&args)).is_err());
+  }
+
+  /// 测试 MoveSectionConfig 分别校验章节和目标标题的定位方式
+  #[test]
+  fn test_move_section_config() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/a.md".to_string()));
+    args.insert("anchor".to_string(), Value::String("存储".to_string()));
+    args.insert("position".to_string(), Value::String("into".to_string()));
+    let err = MoveSectionConfig::from_args(Some(&args)).unwrap_err();
+    assert!(err.to_string().contains("target_anchor"), "{}", err);
+
+    args.insert("target_line_number".to_string(), Value::from(1));
+    let config = MoveSectionConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.position, SectionPosition::Into);
+    assert_eq!(config.target_selector().unwrap(), HeadingSelector::Line(1));
+
+    args.insert("position".to_string(), Value::String("inside".to_string()));
+    assert!(MoveSectionConfig::from_args(Some(&args)).is_err());
+  }
+
+  /// 测试 ReadSectionConfig 的章节定位方式和默认值
+  #[test]
+  fn test_read_section_config() {
+    let mut args = Map::new();
+    args.insert("full_file_path".to_string(), Value::String("/docs/a.md".to_string()));
+    let err = ReadSectionConfig::from_args(Some(&args)).unwrap_err();
+    assert!(err.to_string().contains("number_path"), "{}", err);
+
+    args.insert("number_path".to_string(), Value::String("2.3".to_string()));
+    let config = ReadSectionConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.selector().unwrap(), HeadingSelector::NumberPath("2.3".to_string()));
+    assert!(config.include_children);
+    assert_eq!(config.max_chars, None);
+
+    args.insert("anchor".to_string(), Value::String("存储".to_string()));
+    assert!(ReadSectionConfig::from_args(Some(&args)).is_err());
+
+    args.insert("number_path".to_string(), Value::String(String::new()));
+    let config = ReadSectionConfig::from_args(Some(&args)).unwrap();
+    assert_eq!(config.selector().unwrap(), HeadingSelector::Anchor("存储".to_string()));
+  }
+
+  fn cli_args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+  }
+
+  /// 测试服务器命令行参数解析
+  #[test]
+  fn test_server_config_from_cli_args() {
+    let config = ServerConfig::from_cli_args(Vec::new()).unwrap();
+    assert_eq!(config.transport, TransportKind::Stdio);
+    assert_eq!(config.bind, DEFAULT_HTTP_BIND);
+    assert_eq!(config.port, DEFAULT_HTTP_PORT);
+
+    let config = ServerConfig::from_cli_args(cli_args(&[
+      "--transport",
+      "http",
+      "--bind=0.0.0.0",
+      "--port",
+      "9000",
+      "--auth-token",
+      "secret",
+    ]))
+    .unwrap();
+    assert_eq!(config.transport, TransportKind::Http);
+    assert_eq!(config.bind, "0.0.0.0");
+    assert_eq!(config.port, 9000);
+    assert_eq!(config.auth_token, Some("secret".to_string()));
+    assert_eq!(config.socket_addr().unwrap().to_string(), "0.0.0.0:9000");
+  }
+
+  /// 测试服务器命令行参数的错误处理
+  #[test]
+  fn test_server_config_invalid_cli_args() {
+    assert!(ServerConfig::from_cli_args(cli_args(&["--transport", "websocket"])).is_err());
+    assert!(ServerConfig::from_cli_args(cli_args(&["--port", "http"])).is_err());
+    assert!(ServerConfig::from_cli_args(cli_args(&["--port"])).is_err());
+    assert!(ServerConfig::from_cli_args(cli_args(&["--verbose"])).is_err());
+    assert!(ServerConfig::from_cli_args(cli_args(&["--allow-dir", "/nonexistent/dir"])).is_err());
+  }
+
+  /// 测试 --lang 参数
+  #[test]
+  fn test_server_config_lang() {
+    assert_eq!(ServerConfig::from_cli_args(cli_args(&["--lang", "en"])).unwrap().lang, Lang::En);
+    assert_eq!(ServerConfig::from_cli_args(cli_args(&["--lang=zh-CN"])).unwrap().lang, Lang::Zh);
+    let error_msg = ServerConfig::from_cli_args(cli_args(&["--lang", "fr"])).unwrap_err().to_string();
+    assert!(error_msg.contains("不支持的语言: fr"), "{}", error_msg);
+  }
+
+  /// 测试 --allow-dir 参数可多次指定，并解析为规范路径
+  #[test]
+  fn test_server_config_allow_dirs() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let dir = temp_dir.path().to_str().unwrap();
+    let config =
+      ServerConfig::from_cli_args(cli_args(&["--allow-dir", dir, &format!("--allow-dir={}/.", dir)])).unwrap();
+    let canonical = temp_dir.path().canonicalize().unwrap();
+    assert_eq!(config.allow_dirs, vec![canonical.clone(), canonical]);
+  }
 }
@@ -1,5 +1,16 @@
+//! 错误类型
+//!
+//! 每个错误都有稳定的错误码（[`MarkdownError::code`]），转换为 MCP 错误时与上下文一起放在 `data` 中，
+//! 客户端可以据此处理错误，而不必匹配（可能已翻译的）错误信息。
+
+use crate::image_localizer::format_size;
+use crate::tr;
+use rmcp::model::{CallToolResult, Content};
 use rmcp::ErrorData as McpError;
+use serde_json::{json, Value};
 use std::fmt;
+use std::io;
+use std::path::PathBuf;
 
 #[derive(Debug)]
 pub enum MarkdownError {
@@ -7,28 +18,183 @@ pub enum MarkdownError {
   FileError(String),
   ValidationError(String),
   ConfigError(String),
+  /// 文件或目录不存在
+  FileNotFound {
+    path: String,
+  },
+  /// 没有读写文件的权限
+  PermissionDenied {
+    path: String,
+  },
+  /// 文件不是 Markdown 格式（扩展名不是 .md 或 .markdown）
+  NotMarkdown {
+    path: String,
+  },
+  /// 文件不是有效的 UTF-8 文本
+  EncodingError {
+    path: String,
+  },
+  /// 其他文件读写错误
+  IoError {
+    path: String,
+    source: io::Error,
+  },
+  /// 网络请求失败，服务器返回了错误状态时带有 HTTP 状态码
+  NetworkError {
+    url: String,
+    status: Option<u16>,
+    message: String,
+  },
+  /// 内容不是有效的图片
+  InvalidImage {
+    reason: String,
+  },
+  /// 图片超过大小上限，流式下载中止时不知道实际大小
+  ImageTooLarge {
+    size: Option<u64>,
+    limit: u64,
+  },
+  /// 无效的 glob 模式
+  InvalidPattern {
+    pattern: String,
+    message: String,
+  },
+  /// 路径不在允许访问的根目录内
+  PathOutsideRoot {
+    path: String,
+    resolved: PathBuf,
+    roots: Vec<PathBuf>,
+  },
+  /// 操作被客户端取消
+  Cancelled,
+}
+
+impl MarkdownError {
+  /// 由 I/O 错误创建，按错误类型区分文件不存在、没有权限和编码错误
+  pub fn io(path: impl Into<String>, source: io::Error) -> Self {
+    let path = path.into();
+    match source.kind() {
+      io::ErrorKind::NotFound => MarkdownError::FileNotFound { path },
+      io::ErrorKind::PermissionDenied => MarkdownError::PermissionDenied { path },
+      io::ErrorKind::InvalidData => MarkdownError::EncodingError { path },
+      _ => MarkdownError::IoError { path, source },
+    }
+  }
+
+  /// 稳定的错误码
+  pub fn code(&self) -> &'static str {
+    match self {
+      MarkdownError::ParseError(_) => "parse_error",
+      MarkdownError::FileError(_) => "file_error",
+      MarkdownError::ValidationError(_) => "validation_error",
+      MarkdownError::ConfigError(_) => "config_error",
+      MarkdownError::FileNotFound { .. } => "file_not_found",
+      MarkdownError::PermissionDenied { .. } => "permission_denied",
+      MarkdownError::NotMarkdown { .. } => "not_markdown",
+      MarkdownError::EncodingError { .. } => "encoding_error",
+      MarkdownError::IoError { .. } => "io_error",
+      MarkdownError::NetworkError { .. } => "network_error",
+      MarkdownError::InvalidImage { .. } => "invalid_image",
+      MarkdownError::ImageTooLarge { .. } => "image_too_large",
+      MarkdownError::InvalidPattern { .. } => "invalid_pattern",
+      MarkdownError::PathOutsideRoot { .. } => "path_outside_root",
+      MarkdownError::Cancelled => "cancelled",
+    }
+  }
+
+  /// MCP 错误的 data：错误码和错误的上下文
+  pub fn data(&self) -> Value {
+    let mut data = match self {
+      MarkdownError::FileNotFound { path }
+      | MarkdownError::PermissionDenied { path }
+      | MarkdownError::NotMarkdown { path }
+      | MarkdownError::EncodingError { path }
+      | MarkdownError::IoError { path, .. } => json!({ "path": path }),
+      MarkdownError::NetworkError { url, status, .. } => json!({ "url": url, "status": status }),
+      MarkdownError::ImageTooLarge { size, limit } => json!({ "size": size, "limit": limit }),
+      MarkdownError::InvalidPattern { pattern, .. } => json!({ "pattern": pattern }),
+      MarkdownError::PathOutsideRoot { path, resolved, roots } => json!({
+        "path": path,
+        "resolved": resolved,
+        "roots": roots,
+      }),
+      _ => json!({}),
+    };
+    data["code"] = json!(self.code());
+    data
+  }
+
+  /// 转换为工具的错误结果：文本说明加上结构化的错误数据，便于客户端按错误码处理
+  pub fn to_tool_result(&self, message: String) -> CallToolResult {
+    CallToolResult {
+      content: vec![Content::text(message)],
+      structured_content: Some(self.data()),
+      is_error: Some(true),
+    }
+  }
 }
 
 impl fmt::Display for MarkdownError {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let message = match self {
+      MarkdownError::ParseError(msg) => tr!("解析错误: {}", msg),
+      MarkdownError::FileError(msg) => tr!("文件错误: {}", msg),
+      MarkdownError::ValidationError(msg) => tr!("验证错误: {}", msg),
+      MarkdownError::ConfigError(msg) => tr!("配置错误: {}", msg),
+      MarkdownError::FileNotFound { path } => tr!("文件不存在: {}", path),
+      MarkdownError::PermissionDenied { path } => tr!("没有权限访问: {}", path),
+      MarkdownError::NotMarkdown { path } => tr!("文件必须是Markdown格式 (.md 或 .markdown): {}", path),
+      MarkdownError::EncodingError { path } => tr!("文件不是有效的 UTF-8 文本: {}", path),
+      MarkdownError::IoError { path, source } => tr!("读写文件失败: {} - {}", path, source),
+      MarkdownError::NetworkError { status: Some(status), message, .. } => tr!("HTTP 错误: {} {}", status, message),
+      MarkdownError::NetworkError { status: None, message, .. } => tr!("请求失败: {}", message),
+      MarkdownError::InvalidImage { reason } => reason.clone(),
+      MarkdownError::ImageTooLarge { size: Some(size), limit } => {
+        tr!("图片大小 {} 超过上限 {}", format_size(*size), format_size(*limit))
+      }
+      MarkdownError::ImageTooLarge { size: None, limit } => tr!("图片大小超过上限 {}，已中止下载", format_size(*limit)),
+      MarkdownError::InvalidPattern { pattern, message } => tr!("无效的模式 {}: {}", pattern, message),
+      MarkdownError::PathOutsideRoot { path, resolved, roots } => {
+        let allowed = if roots.is_empty() {
+          tr!("无（客户端声明的根目录都不在 --allow-dir 允许的范围内）").to_string()
+        } else {
+          roots.iter().map(|root| root.display().to_string()).collect::<Vec<_>>().join(", ")
+        };
+        tr!(
+          "访问被拒绝: 路径 {} 不在允许访问的根目录内（解析为 {}），允许的根目录: {}",
+          path,
+          resolved.display(),
+          allowed
+        )
+      }
+      MarkdownError::Cancelled => tr!("操作已取消").to_string(),
+    };
+    f.write_str(&message)
+  }
+}
+
+impl std::error::Error for MarkdownError {
+  fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
     match self {
-      MarkdownError::ParseError(msg) => write!(f, "解析错误: {}", msg),
-      MarkdownError::FileError(msg) => write!(f, "文件错误: {}", msg),
-      MarkdownError::ValidationError(msg) => write!(f, "验证错误: {}", msg),
-      MarkdownError::ConfigError(msg) => write!(f, "配置错误: {}", msg),
+      MarkdownError::IoError { source, .. } => Some(source),
+      _ => None,
     }
   }
 }
 
-impl std::error::Error for MarkdownError {}
-
 impl From<MarkdownError> for McpError {
   fn from(err: MarkdownError) -> Self {
+    let data = Some(err.data());
     match err {
       MarkdownError::ParseError(msg) | MarkdownError::ValidationError(msg) | MarkdownError::ConfigError(msg) => {
-        McpError::invalid_params(msg, None)
+        McpError::invalid_params(msg, data)
+      }
+      MarkdownError::FileError(msg) => McpError::internal_error(msg, data),
+      // 服务器自身的读写、网络问题和取消属于内部错误，其余都是参数指向的文件或内容有问题
+      MarkdownError::IoError { .. } | MarkdownError::NetworkError { .. } | MarkdownError::Cancelled => {
+        McpError::internal_error(err.to_string(), data)
       }
-      MarkdownError::FileError(msg) => McpError::internal_error(msg, None),
+      _ => McpError::invalid_params(err.to_string(), data),
     }
   }
 }
@@ -54,6 +220,22 @@ mod tests {
 
     let config_error = MarkdownError::ConfigError("配置无效".to_string());
     assert_eq!(config_error.to_string(), "配置错误: 配置无效");
+
+    let outside_root = MarkdownError::PathOutsideRoot {
+      path: "/etc/hosts".to_string(),
+      resolved: PathBuf::from("/etc/hosts"),
+      roots: vec![PathBuf::from("/docs")],
+    };
+    assert_eq!(
+      outside_root.to_string(),
+      "访问被拒绝: 路径 /etc/hosts 不在允许访问的根目录内（解析为 /etc/hosts），允许的根目录: /docs"
+    );
+
+    let not_found = MarkdownError::FileNotFound { path: "/docs/a.md".to_string() };
+    assert_eq!(not_found.to_string(), "文件不存在: /docs/a.md");
+
+    let too_large = MarkdownError::ImageTooLarge { size: Some(2048), limit: 1024 };
+    assert_eq!(too_large.to_string(), "图片大小 2.0 KB 超过上限 1.0 KB");
   }
 
   /// 测试 MarkdownError 的 Debug 实现
@@ -85,7 +267,7 @@ mod tests {
 
     // 验证错误消息
     assert_eq!(mcp_error.message, "解析失败");
-    assert!(mcp_error.data.is_none());
+    assert_eq!(mcp_error.data, Some(serde_json::json!({"code": "parse_error"})));
   }
 
   /// 测试 ValidationError 转换为 McpError
@@ -95,7 +277,7 @@ mod tests {
     let mcp_error: McpError = markdown_error.into();
 
     assert_eq!(mcp_error.message, "验证失败");
-    assert!(mcp_error.data.is_none());
+    assert_eq!(mcp_error.data.unwrap()["code"], "validation_error");
   }
 
   /// 测试 ConfigError 转换为 McpError
@@ -105,7 +287,7 @@ mod tests {
     let mcp_error: McpError = markdown_error.into();
 
     assert_eq!(mcp_error.message, "配置错误");
-    assert!(mcp_error.data.is_none());
+    assert_eq!(mcp_error.data.unwrap()["code"], "config_error");
   }
 
   /// 测试 FileError 转换为 McpError
@@ -115,7 +297,68 @@ mod tests {
     let mcp_error: McpError = markdown_error.into();
 
     assert_eq!(mcp_error.message, "文件操作失败");
-    assert!(mcp_error.data.is_none());
+    assert_eq!(mcp_error.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+    assert_eq!(mcp_error.data.unwrap()["code"], "file_error");
+  }
+
+  /// 测试结构化错误转换为 McpError 时带有错误码和上下文
+  #[test]
+  fn test_structured_error_to_mcp_error() {
+    let mcp_error: McpError = MarkdownError::NotMarkdown { path: "/docs/a.txt".to_string() }.into();
+    assert_eq!(mcp_error.code, rmcp::model::ErrorCode::INVALID_PARAMS);
+    assert_eq!(mcp_error.message, "文件必须是Markdown格式 (.md 或 .markdown): /docs/a.txt");
+    assert_eq!(mcp_error.data, Some(serde_json::json!({"code": "not_markdown", "path": "/docs/a.txt"})));
+
+    let mcp_error: McpError = MarkdownError::NetworkError {
+      url: "https://example.com/a.png".to_string(),
+      status: Some(404),
+      message: "Not Found".to_string(),
+    }
+    .into();
+    assert_eq!(mcp_error.code, rmcp::model::ErrorCode::INTERNAL_ERROR);
+    assert_eq!(
+      mcp_error.data,
+      Some(serde_json::json!({"code": "network_error", "url": "https://example.com/a.png", "status": 404}))
+    );
+
+    let mcp_error: McpError = MarkdownError::PathOutsideRoot {
+      path: "../a.md".to_string(),
+      resolved: PathBuf::from("/a.md"),
+      roots: vec![PathBuf::from("/docs")],
+    }
+    .into();
+    let data = mcp_error.data.unwrap();
+    assert_eq!(data["code"], "path_outside_root");
+    assert_eq!(data["roots"], serde_json::json!(["/docs"]));
+  }
+
+  /// 测试按 I/O 错误类型区分错误
+  #[test]
+  fn test_io_error_classification() {
+    let error = MarkdownError::io("/a.md", io::Error::from(io::ErrorKind::NotFound));
+    assert_eq!(error.code(), "file_not_found");
+    let error = MarkdownError::io("/a.md", io::Error::from(io::ErrorKind::PermissionDenied));
+    assert_eq!(error.code(), "permission_denied");
+    let error =
+      MarkdownError::io("/a.md", io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8"));
+    assert_eq!(error.code(), "encoding_error");
+
+    let error = MarkdownError::io("/a.md", io::Error::other("磁盘已满"));
+    assert_eq!(error.code(), "io_error");
+    assert!(error.source().is_some());
+    assert_eq!(error.to_string(), "读写文件失败: /a.md - 磁盘已满");
+  }
+
+  /// 测试工具错误结果携带结构化的错误数据
+  #[test]
+  fn test_to_tool_result() {
+    let error = MarkdownError::ImageTooLarge { size: None, limit: 1024 };
+    let result = error.to_tool_result(format!("图片本地化失败: {}", error));
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(
+      result.structured_content,
+      Some(serde_json::json!({"code": "image_too_large", "size": null, "limit": 1024}))
+    );
   }
 
   /// 测试 Result 类型别名
@@ -123,15 +366,12 @@ mod tests {
   fn test_result_type_alias() {
     // 测试成功情况
     let success_result: Result<String> = Ok("成功".to_string());
-    assert!(success_result.is_ok());
-    assert_eq!(success_result.unwrap(), "成功");
+    assert!(matches!(success_result, Ok(ref value) if value == "成功"));
 
     // 测试错误情况
     let error_result: Result<String> = Err(MarkdownError::ParseError("失败".to_string()));
-    assert!(error_result.is_err());
-
-    match error_result.unwrap_err() {
-      MarkdownError::ParseError(msg) => assert_eq!(msg, "失败"),
+    match error_result {
+      Err(MarkdownError::ParseError(msg)) => assert_eq!(msg, "失败"),
       _ => panic!("期望 ParseError"),
     }
   }
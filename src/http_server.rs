@@ -0,0 +1,167 @@
+//! HTTP 传输模块
+//!
+//! 基于 rmcp 的 Streamable HTTP 服务端传输（响应以 SSE 流返回），
+//! 让多个客户端共享同一个服务器实例，可选 Bearer Token 认证
+
+use crate::tr;
+use bytes::Bytes;
+use http_body_util::{combinators::BoxBody, BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{AUTHORIZATION, WWW_AUTHENTICATE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rmcp::transport::streamable_http_server::session::local::LocalSessionManager;
+use rmcp::transport::streamable_http_server::{StreamableHttpServerConfig, StreamableHttpService};
+use rmcp::ServerHandler;
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// MCP 端点路径
+pub const MCP_ENDPOINT: &str = "/mcp";
+
+type HttpResponse = Response<BoxBody<Bytes, Infallible>>;
+
+/// 在已绑定的监听器上提供 MCP 服务，直到进程退出
+///
+/// 每个 MCP 会话使用 `handler` 的一个克隆；`auth_token` 不为空时，
+/// 请求必须携带 `Authorization: Bearer <token>`
+pub async fn serve_http<H>(listener: TcpListener, handler: H, auth_token: Option<String>) -> std::io::Result<()>
+where
+  H: ServerHandler + Clone + Send + Sync + 'static,
+{
+  let service = StreamableHttpService::new(
+    move || Ok(handler.clone()),
+    Arc::new(LocalSessionManager::default()),
+    StreamableHttpServerConfig::default(),
+  );
+  let auth_token = Arc::new(auth_token);
+
+  loop {
+    let (stream, _) = listener.accept().await?;
+    let service = service.clone();
+    let auth_token = auth_token.clone();
+
+    tokio::spawn(async move {
+      let handle_request = service_fn(move |request: Request<Incoming>| {
+        let service = service.clone();
+        let auth_token = auth_token.clone();
+        async move {
+          if request.uri().path() != MCP_ENDPOINT {
+            return Ok::<_, Infallible>(text_response(StatusCode::NOT_FOUND, "Not Found"));
+          }
+          if !is_authorized(&request, auth_token.as_deref()) {
+            let mut response = text_response(StatusCode::UNAUTHORIZED, "Unauthorized");
+            response.headers_mut().insert(WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+            return Ok(response);
+          }
+          Ok(service.handle(request).await)
+        }
+      });
+
+      if let Err(e) = http1::Builder::new().serve_connection(TokioIo::new(stream), handle_request).await {
+        eprintln!("{}", tr!("HTTP 连接处理失败: {}", e));
+      }
+    });
+  }
+}
+
+/// 校验 Bearer Token，未配置 Token 时允许所有请求
+fn is_authorized<B>(request: &Request<B>, auth_token: Option<&str>) -> bool {
+  let Some(expected) = auth_token else {
+    return true;
+  };
+
+  request
+    .headers()
+    .get(AUTHORIZATION)
+    .and_then(|value| value.to_str().ok())
+    .and_then(|value| value.strip_prefix("Bearer "))
+    .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), expected.as_bytes()))
+}
+
+/// 比较耗时与内容无关，避免通过响应时间猜测 Token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+  if a.len() != b.len() {
+    return false;
+  }
+  a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// 构造纯文本响应
+fn text_response(status: StatusCode, body: &'static str) -> HttpResponse {
+  let mut response = Response::new(Full::new(Bytes::from_static(body.as_bytes())).boxed());
+  *response.status_mut() = status;
+  response
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Clone)]
+  struct TestHandler;
+
+  impl ServerHandler for TestHandler {}
+
+  const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-03-26","capabilities":{},"clientInfo":{"name":"test","version":"0.1.0"}}}"#;
+
+  /// 在随机端口启动测试服务器，返回端点地址
+  async fn start_server(auth_token: Option<&str>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(serve_http(listener, TestHandler, auth_token.map(|t| t.to_string())));
+    format!("http://{}{}", addr, MCP_ENDPOINT)
+  }
+
+  async fn post_initialize(url: &str, token: Option<&str>) -> reqwest::Response {
+    let mut request = reqwest::Client::new()
+      .post(url)
+      .header("Content-Type", "application/json")
+      .header("Accept", "application/json, text/event-stream")
+      .body(INITIALIZE);
+    if let Some(token) = token {
+      request = request.bearer_auth(token);
+    }
+    request.send().await.unwrap()
+  }
+
+  /// 测试 Token 校验
+  #[test]
+  fn test_is_authorized() {
+    let request = Request::builder().header(AUTHORIZATION, "Bearer secret").body(()).unwrap();
+    assert!(is_authorized(&request, Some("secret")));
+    assert!(!is_authorized(&request, Some("other")));
+    assert!(is_authorized(&request, None));
+
+    let request = Request::builder().body(()).unwrap();
+    assert!(!is_authorized(&request, Some("secret")));
+    assert!(is_authorized(&request, None));
+  }
+
+  /// 测试通过 HTTP 完成 MCP 初始化，并拒绝未认证的请求
+  #[tokio::test]
+  async fn test_serve_http_with_bearer_token() {
+    let url = start_server(Some("secret")).await;
+
+    let response = post_initialize(&url, None).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = post_initialize(&url, Some("wrong")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = post_initialize(&url, Some("secret")).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().contains_key("mcp-session-id"));
+  }
+
+  /// 测试未知路径返回 404
+  #[tokio::test]
+  async fn test_serve_http_unknown_path() {
+    let url = start_server(None).await;
+    let response = post_initialize(&url.replace(MCP_ENDPOINT, "/other"), None).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+  }
+}
@@ -0,0 +1,931 @@
+//! 国际化模块
+//!
+//! 面向用户的信息以中文原文作为消息 ID（类似 gettext），通过 [`tr!`](crate::tr) 按当前语言翻译。
+//! 当前语言依次取自：
+//! - 工具调用时的 `lang` 参数（通过 [`with_lang`] 作用于本次调用）
+//! - 启动参数 `--lang` 或环境变量 `MCP_MARKDOWN_TOOLS_LANG`
+//! - 默认中文
+//!
+//! 英文目录中缺少的消息原样返回中文。
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+/// 未通过命令行指定时，从该环境变量读取语言
+pub const LANG_ENV: &str = "MCP_MARKDOWN_TOOLS_LANG";
+
+/// 工具调用中指定语言的参数名
+pub const LANG_ARG: &str = "lang";
+
+/// 支持的语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+  /// 中文
+  #[default]
+  Zh,
+  /// 英文
+  En,
+}
+
+impl Lang {
+  /// 解析语言代码，支持 zh、en 以及 zh-CN、en_US.UTF-8 等形式
+  pub fn parse(code: &str) -> Option<Self> {
+    let code = code.trim().to_ascii_lowercase();
+    match code.split(['-', '_', '.']).next() {
+      Some("zh") => Some(Lang::Zh),
+      Some("en") => Some(Lang::En),
+      _ => None,
+    }
+  }
+}
+
+static DEFAULT_LANG: AtomicU8 = AtomicU8::new(0);
+
+tokio::task_local! {
+  static CURRENT_LANG: Lang;
+}
+
+/// 设置服务器的默认语言
+pub fn set_default_lang(lang: Lang) {
+  DEFAULT_LANG.store(lang as u8, Ordering::Relaxed);
+}
+
+/// 当前语言：工具调用指定的语言，否则为服务器的默认语言
+pub fn current_lang() -> Lang {
+  CURRENT_LANG.try_with(|lang| *lang).unwrap_or_else(|_| match DEFAULT_LANG.load(Ordering::Relaxed) {
+    1 => Lang::En,
+    _ => Lang::Zh,
+  })
+}
+
+/// 在指定语言下执行异步任务，任务中生成的信息都使用该语言
+pub async fn with_lang<F: Future>(lang: Lang, future: F) -> F::Output {
+  CURRENT_LANG.scope(lang, future).await
+}
+
+/// 调用指定的语言和去掉 `lang` 后的其余参数
+pub type LangArgs = (Option<Lang>, Option<Map<String, Value>>);
+
+/// 从工具参数中取出 `lang` 参数，其余参数原样返回
+pub fn take_lang_arg(args: Option<&Map<String, Value>>) -> Result<LangArgs, String> {
+  let Some(args) = args else {
+    return Ok((None, None));
+  };
+  let mut args = args.clone();
+  let lang = match args.remove(LANG_ARG) {
+    None | Some(Value::Null) => None,
+    Some(Value::String(code)) if code.is_empty() => None,
+    Some(Value::String(code)) => Some(
+      Lang::parse(&code).ok_or_else(|| crate::tr!("参数 {} 的值 {} 无效，可选值为: {}", LANG_ARG, code, "zh, en"))?,
+    ),
+    Some(other) => return Err(crate::tr!("参数 {} 的类型应为 {}，实际为 {}", LANG_ARG, "string", other)),
+  };
+  Ok((lang, Some(args)))
+}
+
+/// 将中文消息翻译为当前语言
+pub fn translate(msgid: &'static str) -> &'static str {
+  match current_lang() {
+    Lang::Zh => msgid,
+    Lang::En => en_catalog().get(msgid).copied().unwrap_or(msgid),
+  }
+}
+
+/// 按当前语言格式化消息
+///
+/// 模板中的 `{}` 依次使用参数，`{0}`、`{1}` 按位置使用参数，其他花括号内容原样保留
+pub fn format_message(msgid: &'static str, args: &[&dyn Display]) -> String {
+  let template = translate(msgid);
+  let mut output = String::with_capacity(template.len());
+  let mut next = 0;
+  let mut rest = template;
+
+  while let Some(start) = rest.find('{') {
+    output.push_str(&rest[..start]);
+    let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+      rest = &rest[start..];
+      break;
+    };
+    let inner = &rest[start + 1..end];
+    let index = match inner {
+      "" => {
+        next += 1;
+        Some(next - 1)
+      }
+      _ => inner.parse::<usize>().ok(),
+    };
+    match index.and_then(|index| args.get(index)) {
+      Some(arg) => output.push_str(&arg.to_string()),
+      None => output.push_str(&rest[start..=end]),
+    }
+    rest = &rest[end + 1..];
+  }
+
+  output.push_str(rest);
+  output
+}
+
+/// 按当前语言翻译消息，有参数时返回格式化后的 String，否则返回 &'static str
+///
+/// ```ignore
+/// tr!("文件不存在: {}", path)
+/// ```
+#[macro_export]
+macro_rules! tr {
+  ($msgid:literal) => {
+    $crate::i18n::translate($msgid)
+  };
+  ($msgid:literal, $($arg:expr),+ $(,)?) => {
+    $crate::i18n::format_message($msgid, &[$(&$arg as &dyn ::std::fmt::Display),+])
+  };
+}
+
+/// 工具的说明，按当前语言返回
+pub fn tool_description(tool: &str) -> &'static str {
+  TOOL_DESCRIPTIONS
+    .iter()
+    .find(|(name, _, _)| *name == tool)
+    .map(|(_, zh, en)| if current_lang() == Lang::En { *en } else { *zh })
+    .unwrap_or_default()
+}
+
+/// 按当前语言翻译 JSON Schema 中的说明
+pub fn translate_schema_descriptions(schema: &mut Map<String, Value>) {
+  if current_lang() == Lang::Zh {
+    return;
+  }
+  for (key, item) in schema.iter_mut() {
+    match item {
+      Value::String(text) if key == "description" => {
+        if let Some(translated) = en_catalog().get(text.as_str()) {
+          *text = translated.to_string();
+        }
+      }
+      Value::Object(object) => translate_schema_descriptions(object),
+      Value::Array(items) => items.iter_mut().filter_map(Value::as_object_mut).for_each(translate_schema_descriptions),
+      _ => {}
+    }
+  }
+}
+
+fn en_catalog() -> &'static HashMap<&'static str, &'static str> {
+  static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+  CATALOG.get_or_init(|| EN_MESSAGES.iter().copied().collect())
+}
+
+/// 工具说明：(工具名, 中文, 英文)
+const TOOL_DESCRIPTIONS: &[(&str, &str, &str)] = &[
+  (
+    "check_heading",
+    r#"验证 Markdown 文档标题行的格式规范性和层级结构的正确性。
+
+验证规则：
+1. 标题格式规范：
+   - 标题行必须以1-6个#符号开头（称为标题符/Heading token）
+   - #符号前不能有空格或其他字符
+   - #符号后必须有且仅有一个空格，然后是标题内容
+   - #符号的数量决定标题级别（1-6级）
+
+2. 层级结构规范：
+   - 允许文档开头有非标题内容（如前言、说明等）
+   - 文档中第一个标题的级别决定了文档的起始级别
+   - 标题级别必须连续，不允许跳级：
+     * 允许：同级标题（H2→H2）、下一级标题（H2→H3）、回到任意上级标题（H3→H2、H3→H1）
+     * 不允许：跳级（H1→H3、H2→H4等，即跳过中间级别）
+
+3. 验证范围：
+   - 只验证标题行（以#开头的行），忽略其他内容行
+   - 支持文档开头有非标题内容
+
+返回结果：
+- 验证通过：返回成功信息和标题结构统计
+- 验证失败：返回详细的错误报告，包括：
+  * 错误类型（格式错误/层级跳级）
+  * 具体错误行号和内容"#,
+    r#"Validate the format and level structure of the heading lines in a Markdown document.
+
+Rules:
+1. Heading format:
+   - A heading line must start with 1-6 # characters (the heading token)
+   - There must be nothing before the # characters
+   - The # characters must be followed by exactly one space and then the heading text
+   - The number of # characters determines the heading level (1-6)
+
+2. Level structure:
+   - Non-heading content (such as a preface) is allowed at the start of the document
+   - The level of the first heading determines the starting level of the document
+   - Heading levels must be continuous and must not skip levels:
+     * Allowed: same level (H2→H2), one level deeper (H2→H3), back to any higher level (H3→H2, H3→H1)
+     * Not allowed: skipping levels (H1→H3, H2→H4, etc.)
+
+3. Scope:
+   - Only heading lines (lines starting with #) are checked, other lines are ignored
+   - Non-heading content at the start of the document is supported
+
+Result:
+- Passed: a success message and heading statistics
+- Failed: a detailed error report including:
+  * The error type (format error / skipped level)
+  * The line number and content of each error"#,
+  ),
+  (
+    "generate_chapter_number",
+    r#"为 Markdown 文档所有的标题行(Head line)创建编号。
+在创建之前，会将全文档的标题行检查一遍，清理掉已有的编号。
+为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
+    r#"Number all heading lines of a Markdown document.
+Existing numbers are removed from all heading lines before numbering.
+For speed, run this tool on the whole file directly instead of reading and processing the file in chunks."#,
+  ),
+  (
+    "remove_all_chapter_numbers",
+    r#"清除 Markdown 文档所有标题行(Head line)的编号，包括数字和中文编号。
+为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
+    r#"Remove the numbers from all heading lines of a Markdown document, including Arabic and Chinese numbers.
+For speed, run this tool on the whole file directly instead of reading and processing the file in chunks."#,
+  ),
+  (
+    "localize_images",
+    r#"将整个 Markdown 文档中引用的远程图片资源保存到本地，并且更改文档中的引用。
+支持行内图片 ![alt](url "title")、HTML <img> 标签，以及引用式图片 ![alt][label]（改写对应的 [label]: url 定义，所有引用共用一次下载）。
+下载的内容会通过文件头校验，非图片内容（如 HTML 错误页）和超过大小上限的图片会被跳过，并在结果中给出原因。
+可选地将 data URI 内嵌图片解码保存为文件（localize_data_uris），以及复制文档目录之外的本地图片（copy_local_images）。
+为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
+    r#"Save the remote images referenced by a whole Markdown document locally and update the references in the document.
+Supports inline images ![alt](url "title"), HTML <img> tags and reference-style images ![alt][label] (the matching [label]: url definition is rewritten and all references share one download).
+Downloaded content is validated by its file header; non-image content (such as HTML error pages) and images over the size limit are skipped, with the reason given in the result.
+Optionally decodes data URI images into files (localize_data_uris) and copies local images outside the document directory (copy_local_images).
+For speed, run this tool on the whole file directly instead of reading and processing the file in chunks."#,
+  ),
+  (
+    "publish_images",
+    r#"localize_images 的逆操作：处理 Markdown 文档中引用的本地图片，便于发布单个自包含的文档。
+mode=inline 时将本地图片以 base64 data URI 内嵌到文档中；mode=base_url 时按 path_mapping（最长前缀优先）或 base_url 将本地路径改写为远程 URL。
+支持行内图片、HTML <img> 标签和引用式图片，远程图片和 data URI 保持不变。"#,
+    r#"The inverse of localize_images: process the local images referenced by a Markdown document so that it can be published as a single self-contained document.
+mode=inline embeds local images into the document as base64 data URIs; mode=base_url rewrites local paths to remote URLs using path_mapping (longest prefix first) or base_url.
+Supports inline images, HTML <img> tags and reference-style images; remote images and data URIs are left unchanged."#,
+  ),
+  (
+    "clean_assets",
+    r#"扫描一个或多个 Markdown 文档引用的图片，与资源目录中的图片文件对比，报告未被任何文档引用的文件和指向不存在文件的图片引用。
+可选地删除未被引用的文件，或将其移动到回收目录。链接引用定义 [label]: path 也视为引用；只有图片文件会被当作清理对象。"#,
+    r#"Scan the images referenced by one or more Markdown documents, compare them with the image files in the assets directory, and report files not referenced by any document as well as image references pointing to missing files.
+Optionally deletes the unreferenced files or moves them to a trash directory. Link reference definitions [label]: path also count as references; only image files are considered for cleanup."#,
+  ),
+  (
+    "shift_heading_levels",
+    r#"将文档中所有标题（或某个锚点对应的标题及其下所有子标题）整体升级或降级 N 级，常用于把一篇文档嵌入另一篇文档之前调整层级。
+shift 为正数时降级（# 变为 ##），为负数时升级。调整后的级别超出 H1-H6 时默认拒绝，clamp=true 时限制在范围内。
+只改写标题行开头的 #，其余内容保持原样；renumber=true 且文档原有章节编号时，调整后按原有的编号风格重新生成编号。"#,
+    r#"Promote or demote every heading in a document (or the heading with a given anchor and all headings below it) by N levels, typically before embedding one document into another.
+A positive shift demotes (# becomes ##), a negative shift promotes. Levels that would fall outside H1-H6 are refused by default, or clamped when clamp=true.
+Only the leading # of heading lines are rewritten and everything else is left as is; with renumber=true and a numbered document, the numbering is regenerated in the same style after the shift."#,
+  ),
+  (
+    "extract_section",
+    r#"将一个章节（标题及其下所有内容）提取为独立的文档，标题级别调整为以 H1 开始。
+章节可以用锚点、以 / 分隔的标题路径（如 设计/存储）或标题所在行号指定，三者必须且只能提供一个。
+新文档默认保存在原文档同目录下、以章节锚点命名；replace_with_link=true 时，原文中保留章节标题，内容替换为指向新文档的链接。"#,
+    r#"Extract a section (a heading and everything below it) into a standalone document, with heading levels adjusted so the section starts at H1.
+The section is selected by its anchor, a /-separated heading path (such as Design/Storage) or the line number of its heading; exactly one of them must be given.
+The new document is saved next to the original and named after the section anchor by default; with replace_with_link=true the section heading is kept in the original and its content is replaced by a link to the new document."#,
+  ),
+  (
+    "split_document",
+    r#"按指定级别的标题将大文档拆分为多个文件，每个章节一个文件（如 01-introduction.md），保存在目标目录中，原文档保持不变。
+每个章节的标题级别调整为以 H1 开始；同时生成索引文件，保留第一个章节之前的内容，并将每个章节替换为指向其文件的链接。
+章节引用的本地图片可以随章节一起移动到目标目录（move_images=true），或保持原位并改写为新的相对路径。"#,
+    r#"Split a large document into one file per chapter at the given heading level (such as 01-introduction.md) in a target directory; the original document is left unchanged.
+Heading levels in each file are adjusted so the chapter starts at H1. An index file keeps the content before the first chapter and replaces every chapter with a link to its file.
+Local images referenced by a chapter are either moved into the target directory along with it (move_images=true) or kept in place with their relative paths rewritten."#,
+  ),
+  (
+    "merge_documents",
+    r#"按顺序将多个 Markdown 文档合并为一个文档，可以给出文档列表，或给出目录并按文件名自然排序。
+可以生成一个一级标题（title）或以第一个文档的标题（first_file_as_parent=true）作为上级标题，其余文档的标题级别调整到它之下。
+相对路径的图片和链接改写为从合并后文档出发仍然有效的路径，指向被合并文档的链接改写为文档内的锚点；同名标题的锚点依次追加 -1、-2，指向它们的链接同步更新。"#,
+    r#"Merge several Markdown documents into one, in order: either a list of documents, or a directory sorted naturally by file name.
+A generated top-level heading (title) or the heading of the first document (first_file_as_parent=true) can serve as the parent heading, with the headings of the other documents moved below it.
+Relative image and link paths are rewritten to stay valid from the merged document, and links to merged documents become anchors within it; duplicate heading anchors get -1, -2 suffixes and links to them are updated."#,
+  ),
+  (
+    "outline",
+    r#"文档大纲：返回标题树（级别、标题、编号、行范围、锚点）以及每个章节的统计信息：字数（每个汉字计为一个字，其他文字按单词计数，不含代码块）、图片数、代码块数和预计阅读时间。章节的统计包含其下所有子章节。
+
+format=text 时返回按章节层级缩进的文本，format=json 时返回带嵌套子章节的 JSON。max_level 限制列出的标题级别。适合在读取或修改章节之前了解文档结构和各章节的篇幅。"#,
+    r#"Document outline: return the heading tree (level, title, numbering, line range, anchor) with statistics for every section: word count (each CJK character counts as one word, other text is counted in words, code blocks excluded), images, code blocks and estimated reading time. Section statistics include all subsections.
+
+format=text returns text indented by section depth, format=json returns JSON with nested subsections. max_level limits the listed heading levels. Useful for learning the structure of a document and the size of each section before reading or editing sections."#,
+  ),
+  (
+    "check_links",
+    r#"检查文档中失效的内部链接：行内链接和引用式链接中指向文档内标题的锚点（#anchor），以及指向本地文件的相对链接（包括 other.md#section 中目标文档的锚点）。锚点与 GitHub 生成的标题锚点一致，也可以是 HTML 元素的 id 或 name。
+
+返回失效链接的行号、列号和原因，并按相似度给出最接近的锚点、文件名或引用标签作为建议。其他协议（如 mailto:）和站点根路径（/docs/a.md）不检查，代码块和行内代码中的链接会被忽略。
+
+check_external=true 时还会通过网络检查 http(s) 外部链接：先发送 HEAD 请求，不支持时改用 GET；报告错误状态码、超时和重定向后的最终地址。可以设置超时时间（timeout_secs）、并发数（max_concurrency）和不检查的域名（ignore_domains）。"#,
+    r#"Check a document for broken internal links: anchors pointing to headings in the document (#anchor) and relative links to local files (including the anchor in the target document of other.md#section), in both inline and reference links. Anchors follow the heading anchors generated by GitHub and may also be the id or name of an HTML element.
+
+Broken links are reported with line, column and reason, plus the most similar anchor, file name or reference label as a suggestion. Other schemes (such as mailto:) and site-root paths (/docs/a.md) are not checked, and links inside code blocks and inline code are ignored.
+
+With check_external=true, http(s) external links are also checked over the network: a HEAD request is sent first, falling back to GET when unsupported; error status codes, timeouts and the final address of redirects are reported. The timeout (timeout_secs), concurrency (max_concurrency) and domains to skip (ignore_domains) are configurable."#,
+  ),
+  (
+    "read_section",
+    r#"读取章节：只返回一个标题及其下内容的 Markdown 原文，不必读取整个文档，节省 token。
+
+章节通过 anchor、title_path、number_path（例如 2.3）或 line_number 指定。include_children=false 时只返回第一个子章节之前的内容，并在末尾列出省略的子章节锚点；指定 max_chars 时按整行截断，并在末尾附加截断标记，说明未显示内容的起始行号。"#,
+    r#"Read a section: return only the raw Markdown of one heading and the content below it, without reading the whole document, to save tokens.
+
+The section is given by anchor, title_path, number_path (such as 2.3) or line_number. With include_children=false, only the content before the first subsection is returned, followed by the anchors of the omitted subsections. With max_chars, the text is cut at a line boundary and ends with a truncation marker naming the line where the omitted content starts."#,
+  ),
+  (
+    "replace_section",
+    r#"替换章节：用给出的 Markdown 替换一个章节的内容，文档的其余部分保持原样，无需重新输出整个文档。
+
+章节通过 anchor、title_path、number_path（例如 2.3）或 line_number 指定。默认保留章节标题，content 替换标题下的所有内容（包括子章节），其中的标题必须比章节标题低；replace_heading=true 时连同标题一起替换，content 必须以一个与章节标题同级的标题开始。标题级别不适合时默认拒绝替换，adjust_levels=true 时自动调整。
+
+renumber=true 且文档原有章节编号时，替换后按原有的编号风格重新生成编号。"#,
+    r#"Replace a section: swap the content of one section for the given Markdown, leaving the rest of the document unchanged, without re-emitting the whole document.
+
+The section is given by anchor, title_path, number_path (such as 2.3) or line_number. By default the section heading is kept and content replaces everything below it, subsections included; its headings must be below the section heading. With replace_heading=true the heading is replaced too, and content must start with a heading at the section's level. Headings that do not fit are refused unless adjust_levels=true, which adjusts them.
+
+With renumber=true, a numbered document is renumbered in its original style after the replacement."#,
+  ),
+  (
+    "move_section",
+    r#"移动章节：将一个标题及其下所有内容移动到另一个标题之前（before）、之后（after）或之内作为最后一个子章节（into）。
+
+要移动的章节通过 anchor、title_path 或 line_number 指定，目标标题通过 target_anchor、target_title_path 或 target_line_number 指定。章节的标题级别会自动调整以适应新的位置：before/after 与目标标题同级，into 比目标标题低一级。不能移动到章节自身或其子章节的位置。
+
+renumber=true 且文档原有章节编号时，移动后按原有的编号风格重新生成编号。"#,
+    r#"Move a section: move a heading and everything below it before another heading (before), after it (after), or inside it as its last subsection (into).
+
+The section to move is given by anchor, title_path or line_number; the target heading by target_anchor, target_title_path or target_line_number. Heading levels are adjusted to fit the new position: before/after puts the section at the target's level, into one level below it. A section cannot be moved relative to itself or one of its subsections.
+
+With renumber=true, a numbered document is renumbered in its original style after the move."#,
+  ),
+  (
+    "delete_section",
+    r#"删除章节：删除一个标题及其下所有内容（包括所有子章节）。
+
+章节通过 anchor、title_path 或 line_number 指定。renumber=true 且文档原有章节编号时，删除后按原有的编号风格重新生成编号。"#,
+    r#"Delete a section: remove a heading and everything below it, including all subsections.
+
+The section is given by anchor, title_path or line_number. With renumber=true, a numbered document is renumbered in its original style after the deletion."#,
+  ),
+  (
+    "show_effective_config",
+    r#"显示对某个文档生效的项目配置（.mdtools.toml），用于排查工具参数的来源。
+配置文件从文档所在目录逐级向上查找，包含编号风格、标题检查规则、图片设置以及按 glob 匹配的覆盖设置；调用工具时显式传入的参数优先于配置文件。"#,
+    r#"Show the project configuration (.mdtools.toml) in effect for a document, to find out where tool arguments come from.
+The config file is searched for from the document directory upwards and contains the numbering style, heading check rules, image settings and glob-matched overrides; arguments passed explicitly when calling a tool take precedence over the config file."#,
+  ),
+];
+
+/// 英文消息目录：(中文消息 ID, 英文翻译)
+const EN_MESSAGES: &[(&str, &str)] = &[
+  // 命令行帮助
+  (crate::config::SERVER_USAGE, r#"Usage: mcp-markdown-tools [options]
+
+Options:
+  --transport <stdio|http>  Transport, defaults to stdio
+  --bind <address>          Address the HTTP transport listens on, defaults to 127.0.0.1
+  --port <port>             Port the HTTP transport listens on, defaults to 8080
+  --auth-token <token>      Bearer token required by the HTTP transport, can also be set with the MCP_MARKDOWN_TOOLS_AUTH_TOKEN environment variable
+  --allow-dir <dir>         Only allow reading and writing files inside this directory, can be repeated; roots declared by the client can only narrow it further
+  --lang <zh|en>            Language of the returned messages, defaults to zh, can also be set with the MCP_MARKDOWN_TOOLS_LANG environment variable
+  -h, --help                Show this help"#),
+  // 工具参数说明
+  ("是否允许标题跳级（如 H1 直接到 H3）", "Whether headings may skip levels (such as H1 straight to H3)"),
+  ("Markdown 文档的文件路径，必须使用绝对路径", "Path of the Markdown document, must be absolute"),
+  ("返回信息使用的语言，默认使用服务器的语言设置", "Language of the returned messages, defaults to the server language"),
+  ("允许使用的最深标题级别，更深的标题会被报告", "Deepest heading level allowed, deeper headings are reported"),
+  ("是否忽略一级标题（# 标题）", "Whether to skip top-level headings (# headings)"),
+  (r#"新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
+
+默认与原文档同目录，默认文件名为：{original_file_name}_numed.md。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。"#, r#"New file name, must be absolute. Used when save_as_new_file=true.
+
+Defaults to {original_file_name}_numed.md in the directory of the original document. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}."#),
+  ("编辑后，是否另存为新文件，为false时将覆盖原文件。", "Whether to save the result as a new file; the original file is overwritten when false."),
+  (r#"一级以下编号是否使用独立的阿拉伯数字编号。use_chinese_number=true 时生效。
+为 true 时，只有一级编号使用中文，每个一级编号下的二级编号都是用从1开始的一级阿拉伯数字编号，三级编号都是用从1.1开始的二级阿拉伯数字编号，以此类推。
+
+示例一（ignore_h1=false, use_chinese_number=true, use_arabic_number_for_sublevel=true）：
+# 一、
+## 一、一、
+### 一、一、一、
+### 一、一、二、
+## 一、二、
+# 二、
+## 二、一、
+## 二、二、
+# 三、
+## 三、一、
+
+示例二（ignore_h1=true, use_chinese_number=true, use_arabic_number_for_sublevel=false）：
+## 一、
+### 1.
+#### 1.1.
+#### 1.2.
+### 2.
+## 二、
+### 1.
+### 2.
+## 三、
+### 1.
+"#, r#"Whether levels below the top level use separate Arabic numbering. Used when use_chinese_number=true.
+When true, only top-level numbers use Chinese numerals; second-level numbers under each top-level number restart from 1, third-level numbers restart from 1.1, and so on.
+
+Example 1 (ignore_h1=false, use_chinese_number=true, use_arabic_number_for_sublevel=true):
+# 一、
+## 一、一、
+### 一、一、一、
+### 一、一、二、
+## 一、二、
+# 二、
+## 二、一、
+## 二、二、
+# 三、
+## 三、一、
+
+Example 2 (ignore_h1=true, use_chinese_number=true, use_arabic_number_for_sublevel=false):
+## 一、
+### 1.
+#### 1.1.
+#### 1.2.
+### 2.
+## 二、
+### 1.
+### 2.
+## 三、
+### 1.
+"#),
+  ("是否使用中文编号（一、二、三...）", "Whether to use Chinese numerals (一、二、三...)"),
+  (r#"新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
+
+默认与原文档同目录，默认文件名为：{original_file_name}_unnumed.md。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。"#, r#"New file name, must be absolute. Used when save_as_new_file=true.
+
+Defaults to {original_file_name}_unnumed.md in the directory of the original document. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}."#),
+  ("编辑后，是否另存为新文件。为false时将覆盖原文件。", "Whether to save the result as a new file; the original file is overwritten when false."),
+  ("是否将文档目录之外的本地图片（如 ../shared/a.png）复制到图片目录并改写引用", "Whether to copy local images outside the document directory (such as ../shared/a.png) into the image directory and rewrite the references"),
+  ("图片保存的目录，默认为原文档同目录下的 assets 目录。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}", "Directory to save images to, defaults to the assets directory next to the original document. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}"),
+  (r#"保存到本地的图片文件名格式，不包含扩展名。支持的通配符有：
+
+- multilevel_num: 图片所在的多层级编号，例如：1.2.1.
+
+- index: 序号，从零开始
+
+- hash: 6位哈希字符"#, r#"File name pattern of saved images, without the extension. Supported wildcards:
+
+- multilevel_num: multi-level number of the section containing the image, e.g. 1.2.1.
+
+- index: sequence number, starting from zero
+
+- hash: 6-character hash"#),
+  ("是否将 data URI 内嵌图片解码保存为文件并改写引用", "Whether to decode data URI images into files and rewrite the references"),
+  ("单张图片允许下载的最大字节数，超过时中止下载并跳过该图片", "Maximum size in bytes of a single downloaded image; larger downloads are aborted and the image is skipped"),
+  ("新文件名，必须使用绝对路径。为空则覆盖原文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}。", "New file name, must be absolute. The original file is overwritten when empty. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}."),
+  ("mode=base_url 时，未匹配 path_mapping 的本地图片使用的 URL 前缀，与图片相对于文档的路径拼接", "URL prefix for local images not matched by path_mapping when mode=base_url, joined with the image path relative to the document"),
+  ("mode=inline 时单张图片允许内嵌的最大字节数", "Maximum size in bytes of a single inlined image when mode=inline"),
+  ("inline：内嵌为 base64 data URI；base_url：改写为远程 URL", "inline: embed as base64 data URIs; base_url: rewrite to remote URLs"),
+  ("以 base64 data URI 内嵌到文档中", "Embed into the document as base64 data URIs"),
+  ("按路径映射改写为远程 URL", "Rewrite to remote URLs using the path mapping"),
+  ("本地路径前缀（相对于文档目录）到 URL 前缀的映射，例如 {\"assets/\": \"https://cdn.example.com/docs/assets/\"}", "Mapping from local path prefixes (relative to the document directory) to URL prefixes, e.g. {\"assets/\": \"https://cdn.example.com/docs/assets/\"}"),
+  ("report：仅报告；delete：删除未被引用的文件；trash：移动到回收目录", "report: only report; delete: delete unreferenced files; trash: move them to the trash directory"),
+  ("仅报告", "Only report"),
+  ("直接删除", "Delete directly"),
+  ("移动到回收目录", "Move to the trash directory"),
+  ("资源目录，占位符以第一个文档为准。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}", "Assets directory; placeholders are resolved against the first document. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}"),
+  ("参与扫描的 Markdown 文档路径列表，必须使用绝对路径。所有引用该资源目录的文档都应包含在内", "Paths of the Markdown documents to scan, must be absolute. All documents referencing the assets directory should be included"),
+  ("action=trash 时的回收目录，默认为资源目录下的 .trash。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}", "Trash directory when action=trash, defaults to .trash inside the assets directory. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}"),
+  ("只显示该工具最终使用的参数，例如 generate_chapter_number；为空时显示全部项目设置", "Only show the arguments this tool ends up using, e.g. generate_chapter_number; shows all project settings when empty"),
+  ("调整的级别数，正数降级（# 变为 ##），负数升级（## 变为 #）", "Number of levels to shift; positive values demote (# becomes ##), negative values promote (## becomes #)"),
+  ("只调整该锚点对应的标题及其下所有子标题，为空时调整全部标题。锚点可从 markdown://outline/{path} 资源中获取", "Only shift the heading with this anchor and all headings below it; shifts every heading when empty. Anchors are listed by the markdown://outline/{path} resource"),
+  ("调整后的级别超出 H1-H6 时，是否限制在范围内；为 false 时拒绝调整", "Whether to clamp levels that would fall outside H1-H6; the shift is refused when false"),
+  ("要提取的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个", "Anchor of the heading of the section to extract, as listed by the markdown://outline/{path} resource. Exactly one of anchor, title_path and line_number must be given"),
+  ("以 / 分隔的标题路径，例如 设计/存储，每一级可以带编号或不带编号。anchor、title_path、line_number 必须且只能提供一个", "Heading path separated by /, such as Design/Storage; each segment may include or omit its numbering. Exactly one of anchor, title_path and line_number must be given"),
+  ("章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个", "Line number of the section heading (starting at 1). Exactly one of anchor, title_path and line_number must be given"),
+  ("新文档的路径，必须使用绝对路径，默认为原文档同目录下以章节锚点命名的 .md 文件。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}", "Path of the new document, must be absolute. Defaults to a .md file named after the section anchor in the directory of the original document. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}"),
+  ("新文档已存在时是否覆盖", "Whether to overwrite the new document if it already exists"),
+  ("是否将原文档中的章节内容替换为指向新文档的链接（保留章节标题）", "Whether to replace the section content in the original document with a link to the new document (the section heading is kept)"),
+  ("按该级别的标题拆分，每个该级别的标题及其下所有内容成为一个文件", "Heading level to split at; each heading of this level and everything below it becomes one file"),
+  ("拆分后文件的保存目录，必须使用绝对路径。支持占位符 {full_dir_of_original_file}、{original_file_name}、{ext}、{date}、{timestamp}", "Directory for the split files, must be absolute. Supports the placeholders {full_dir_of_original_file}, {original_file_name}, {ext}, {date} and {timestamp}"),
+  ("索引文件的文件名，保存在 output_dir 中，包含第一个章节之前的内容和指向各个文件的链接", "File name of the index, saved in output_dir; it keeps the content before the first chapter and links to every file"),
+  ("是否将章节引用的本地图片移动到 output_dir 中；为 false 时改写图片的相对路径，图片保持原位", "Whether to move local images referenced by the chapters into output_dir; when false, images stay in place and their relative paths are rewritten"),
+  ("拆分后的文件已存在时是否覆盖", "Whether to overwrite split files that already exist"),
+  ("按顺序合并的 Markdown 文档路径列表，必须使用绝对路径。full_file_paths 和 input_dir 必须且只能提供一个", "Paths of the Markdown documents to merge, in order; must be absolute. Exactly one of full_file_paths and input_dir must be given"),
+  ("合并该目录下的所有 Markdown 文档（不包括子目录），按文件名自然排序，如 2-a.md 排在 10-b.md 之前。full_file_paths 和 input_dir 必须且只能提供一个", "Merge every Markdown document in this directory (subdirectories excluded), in natural file name order such as 2-a.md before 10-b.md. Exactly one of full_file_paths and input_dir must be given"),
+  ("合并后文档的路径，必须使用绝对路径", "Path of the merged document, must be absolute"),
+  ("合并后文档的一级标题，所有文档的标题调整到该标题之下", "Top-level heading of the merged document; the headings of every document are moved below it"),
+  ("以第一个文档的最高级标题作为上级标题，其余文档的标题调整到该标题之下。不能与 title 同时使用", "Use the top heading of the first document as the parent heading and move the headings of the other documents below it. Cannot be combined with title"),
+  ("合并后的文档已存在时是否覆盖", "Whether to overwrite the merged document if it already exists"),
+  ("目标标题之前，与目标标题同级", "Before the target heading, at the same level"),
+  ("目标章节之后，与目标标题同级", "After the target section, at the same level as the target heading"),
+  ("目标章节之内，作为最后一个子章节", "Inside the target section, as its last subsection"),
+  ("要移动的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个", "Anchor of the heading of the section to move, as listed by the markdown://outline/{path} resource. Exactly one of anchor, title_path and line_number must be given"),
+  ("要移动的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、line_number 必须且只能提供一个", "Heading path of the section to move, separated by /, such as Design/Storage. Exactly one of anchor, title_path and line_number must be given"),
+  ("要移动的章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个", "Line number of the heading of the section to move (starting at 1). Exactly one of anchor, title_path and line_number must be given"),
+  ("目标标题的锚点。target_anchor、target_title_path、target_line_number 必须且只能提供一个", "Anchor of the target heading. Exactly one of target_anchor, target_title_path and target_line_number must be given"),
+  ("目标标题的标题路径，以 / 分隔。target_anchor、target_title_path、target_line_number 必须且只能提供一个", "Heading path of the target heading, separated by /. Exactly one of target_anchor, target_title_path and target_line_number must be given"),
+  ("目标标题所在的行号（从 1 开始）。target_anchor、target_title_path、target_line_number 必须且只能提供一个", "Line number of the target heading (starting at 1). Exactly one of target_anchor, target_title_path and target_line_number must be given"),
+  ("before：移动到目标标题之前；after：移动到目标章节之后；into：移动到目标章节之内，作为最后一个子章节", "before: move in front of the target heading; after: move behind the target section; into: move inside the target section as its last subsection"),
+  ("文档原有章节编号时，调整后是否按原有风格重新生成编号", "If the document is numbered, whether to regenerate the numbering in the same style after the shift"),
+  ("文档原有章节编号时，移动后是否按原有风格重新生成编号", "If the document is numbered, whether to regenerate the numbering in the same style after the move"),
+  ("要删除的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、line_number 必须且只能提供一个", "Anchor of the heading of the section to delete, as listed by the markdown://outline/{path} resource. Exactly one of anchor, title_path and line_number must be given"),
+  ("要删除的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、line_number 必须且只能提供一个", "Heading path of the section to delete, separated by /, such as Design/Storage. Exactly one of anchor, title_path and line_number must be given"),
+  ("要删除的章节标题所在的行号（从 1 开始）。anchor、title_path、line_number 必须且只能提供一个", "Line number of the heading of the section to delete (starting at 1). Exactly one of anchor, title_path and line_number must be given"),
+  ("文档原有章节编号时，删除后是否按原有风格重新生成编号", "If the document is numbered, whether to regenerate the numbering in the same style after the deletion"),
+  ("要读取的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、number_path、line_number 必须且只能提供一个", "Anchor of the heading of the section to read, as listed by the markdown://outline/{path} resource. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要读取的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、number_path、line_number 必须且只能提供一个", "Heading path of the section to read, separated by /, such as Design/Storage. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要读取的章节的编号路径，例如 2.3，优先匹配标题自带的编号，没有匹配时按章节的位置定位。anchor、title_path、number_path、line_number 必须且只能提供一个", "Number path of the section to read, such as 2.3. Matches the numbering in the headings first, and otherwise the position of the section. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要读取的章节标题所在的行号（从 1 开始）。anchor、title_path、number_path、line_number 必须且只能提供一个", "Line number of the heading of the section to read (starting at 1). Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("是否包含子章节，为 false 时只返回第一个子章节之前的内容", "Whether to include subsections; when false, only the content before the first subsection is returned"),
+  ("最多返回的字符数（一个汉字计为一个字符），超出时按整行截断并附加截断标记。为空时返回完整章节", "Maximum number of characters to return (a CJK character counts as one); longer sections are cut at a line boundary and end with a truncation marker. Returns the whole section when empty"),
+  ("要替换的章节标题的锚点，可从 markdown://outline/{path} 资源中获取。anchor、title_path、number_path、line_number 必须且只能提供一个", "Anchor of the heading of the section to replace, as listed by the markdown://outline/{path} resource. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要替换的章节的标题路径，以 / 分隔，例如 设计/存储。anchor、title_path、number_path、line_number 必须且只能提供一个", "Heading path of the section to replace, separated by /, such as Design/Storage. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要替换的章节的编号路径，例如 2.3。anchor、title_path、number_path、line_number 必须且只能提供一个", "Number path of the section to replace, such as 2.3. Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("要替换的章节标题所在的行号（从 1 开始）。anchor、title_path、number_path、line_number 必须且只能提供一个", "Line number of the heading of the section to replace (starting at 1). Exactly one of anchor, title_path, number_path and line_number must be given"),
+  ("新的章节内容（Markdown）。replace_heading=false 时不包含章节标题，替换标题下的所有内容（包括子章节）", "New section content (Markdown). With replace_heading=false it excludes the section heading and replaces everything below it, subsections included"),
+  ("是否连同章节标题一起替换，为 true 时 content 必须以一个与章节标题同级的标题开始", "Whether to replace the section heading too; content must then start with a heading at the same level as the section heading"),
+  ("content 中的标题级别不适合放在该章节时，是否自动调整级别；为 false 时拒绝替换", "Whether to adjust heading levels in content that do not fit the section; the replacement is refused when false"),
+  ("文档原有章节编号时，替换后是否按原有风格重新生成编号", "If the document is numbered, whether to regenerate the numbering in the same style after the replacement"),
+  ("按章节层级缩进的文本", "Text indented by section depth"),
+  ("带嵌套子章节的 JSON", "JSON with nested subsections"),
+  ("text：按章节层级缩进的文本；json：带嵌套子章节的 JSON", "text: text indented by section depth; json: JSON with nested subsections"),
+  ("只列出不超过该级别的标题，章节的统计信息仍包含更深的子章节", "Only list headings up to this level; section statistics still include deeper subsections"),
+  ("是否通过网络检查 http(s) 外部链接，默认只检查内部链接", "Whether to check http(s) external links over the network; by default only internal links are checked"),
+  ("每个外部链接请求的超时时间（秒）", "Timeout of each external link request (seconds)"),
+  ("同时检查的外部链接数量上限", "Maximum number of external links checked at the same time"),
+  ("不检查的域名，其子域名同样不检查，例如 example.com", "Domains not to check, including their subdomains, such as example.com"),
+  // asset_cleaner
+  ("✅ 已删除: {}", "✅ Deleted: {}"),
+  ("❌ 删除失败: {} - {}", "❌ Failed to delete: {} - {}"),
+  ("❌ 拒绝处理资源目录之外的文件: {}", "❌ Refusing to touch a file outside the assets directory: {}"),
+  ("✅ 已移动: {} -> {}", "✅ Moved: {} -> {}"),
+  ("❌ 移动失败: {} - {}", "❌ Failed to move: {} - {}"),
+  ("解析 Markdown 失败: {} - {}", "Failed to parse Markdown: {} - {}"),
+  ("无法获取 Markdown 文件目录", "Cannot determine the Markdown file directory"),
+  ("创建回收目录失败: {}", "Failed to create trash directory: {}"),
+  ("复制文件失败: {}", "Failed to copy file: {}"),
+  ("删除原文件失败: {}", "Failed to delete the original file: {}"),
+  // config
+  ("参数 {} 缺少取值", "Option {} requires a value"),
+  ("不支持的传输方式: {}，可选值为 stdio 或 http", "Unsupported transport: {}, expected stdio or http"),
+  ("无效的端口: {}", "Invalid port: {}"),
+  ("不支持的语言: {}，可选值为 zh 或 en", "Unsupported language: {}, expected zh or en"),
+  ("未知参数: {}\n\n{}", "Unknown option: {}\n\n{}"),
+  ("无效的监听地址 {}:{} - {}", "Invalid listen address {}:{} - {}"),
+  ("无效的监听地址 {}:{}", "Invalid listen address {}:{}"),
+  ("mode 为 base_url 时必须提供 base_url 或 path_mapping", "base_url or path_mapping is required when mode is base_url"),
+  ("缺少参数", "Missing arguments"),
+  ("参数错误: {}", "Invalid arguments: {}"),
+  ("参数 {} 的类型应为 {}，实际为 {}", "Argument {} should be of type {}, got {}"),
+  ("参数 {} 的值 {} 无效，可选值为: {}", "Invalid value {1} for argument {0}, expected one of: {2}"),
+  ("参数 {} 不能小于 {}", "Argument {} must not be less than {}"),
+  ("参数 {} 不能大于 {}", "Argument {} must not be greater than {}"),
+  ("参数 {} 至少需要包含 {} 个元素", "Argument {} must contain at least {} items"),
+  ("缺少 {} 参数", "Missing argument {}"),
+  ("未知参数: {}，可用的参数有: {}", "Unknown argument: {}, available arguments: {}"),
+  ("{0}anchor、{0}title_path、{0}line_number 必须且只能提供其中一个", "Exactly one of {0}anchor, {0}title_path and {0}line_number must be given"),
+  ("index_file_name 只能是文件名: {}", "index_file_name must be a plain file name: {}"),
+  ("full_file_paths 和 input_dir 必须且只能提供其中一个", "Exactly one of full_file_paths and input_dir must be given"),
+  ("title 和 first_file_as_parent 不能同时使用", "title and first_file_as_parent cannot be used together"),
+  ("anchor、title_path、number_path、line_number 必须且只能提供其中一个", "Exactly one of anchor, title_path, number_path and line_number must be given"),
+  // error
+  ("解析错误: {}", "Parse error: {}"),
+  ("文件错误: {}", "File error: {}"),
+  ("验证错误: {}", "Validation error: {}"),
+  ("配置错误: {}", "Configuration error: {}"),
+  ("没有权限访问: {}", "Permission denied: {}"),
+  ("文件必须是Markdown格式 (.md 或 .markdown): {}", "The file must be a Markdown file (.md or .markdown): {}"),
+  ("文件不是有效的 UTF-8 文本: {}", "The file is not valid UTF-8 text: {}"),
+  ("读写文件失败: {} - {}", "File I/O failed: {} - {}"),
+  ("HTTP 错误: {} {}", "HTTP error: {} {}"),
+  ("无效的模式 {}: {}", "Invalid pattern {}: {}"),
+  ("访问被拒绝: 路径 {} 不在允许访问的根目录内（解析为 {}），允许的根目录: {}", "Access denied: path {} is outside the allowed roots (resolved to {}), allowed roots: {}"),
+  ("操作已取消", "Operation cancelled"),
+  // http_server
+  ("HTTP 连接处理失败: {}", "HTTP connection failed: {}"),
+  // i18n
+  ("文件不存在: {}", "File does not exist: {}"),
+  // image_localizer
+  ("✅ {}: {} -> {}", "✅ {}: {} -> {}"),
+  ("❌ {}: {} - {}", "❌ {}: {} - {}"),
+  ("✅ {}: {} -> {}（引用 [{}]）", "✅ {}: {} -> {} (reference [{}])"),
+  ("❌ {}: {} - {}（引用 [{}]）", "❌ {}: {} - {} (reference [{}])"),
+  ("data URI 不是图片类型: {}", "data URI is not an image type: {}"),
+  ("内嵌数据不是有效的图片（声明类型: {}）", "Embedded data is not a valid image (declared type: {})"),
+  ("读取文件失败: {}", "Failed to read file: {}"),
+  ("无法识别图片格式: {}", "Unrecognized image format: {}"),
+  ("图片大小 {} 超过上限 {}", "Image size {} exceeds the limit of {}"),
+  ("请求失败: {}", "Request failed: {}"),
+  ("读取响应失败: {}", "Failed to read response: {}"),
+  ("图片大小超过上限 {}，已中止下载", "Image size exceeds the limit of {}, download aborted"),
+  ("解析 URL 失败: {}", "Failed to parse URL: {}"),
+  ("无法计算相对路径", "Cannot compute the relative path"),
+  ("路径包含无效字符", "Path contains invalid characters"),
+  ("❌ 处理图片失败: {} - {}", "❌ Failed to process image: {} - {}"),
+  ("成功下载", "Downloaded"),
+  ("下载失败", "Download failed"),
+  ("成功保存内嵌图片", "Saved embedded image"),
+  ("保存内嵌图片失败", "Failed to save embedded image"),
+  ("成功复制本地图片", "Copied local image"),
+  ("复制本地图片失败", "Failed to copy local image"),
+  ("无法解析本地路径: {}", "Cannot resolve local path: {}"),
+  ("不是 data URI", "Not a data URI"),
+  ("data URI 缺少数据部分", "data URI has no data part"),
+  ("base64 解码失败: {}", "base64 decoding failed: {}"),
+  ("响应内容为空", "the response body is empty"),
+  ("HTML 页面", "an HTML page"),
+  ("JSON 数据", "JSON data"),
+  ("无法识别的格式", "an unrecognized format"),
+  ("响应内容不是图片（检测到{}，Content-Type: {}）", "The response is not an image (detected {}, Content-Type: {})"),
+  ("未知", "unknown"),
+  // image_publisher
+  ("✅ {}: {} -> {}{}", "✅ {}: {} -> {}{}"),
+  ("❌ {}: {} - {}{}", "❌ {}: {} - {}{}"),
+  ("已内嵌", "Inlined"),
+  ("内嵌失败", "Failed to inline"),
+  ("已改写", "Rewritten"),
+  ("改写失败", "Failed to rewrite"),
+  ("（引用 [{}]）", " (reference [{}])"),
+  ("没有匹配的路径映射", "No matching path mapping"),
+  ("文档目录之外的图片只能通过 path_mapping 改写", "Images outside the document directory can only be rewritten through path_mapping"),
+  // links
+  ("链接引用定义 [{}] 不存在", "Link reference definition [{}] does not exist"),
+  ("文档中没有锚点 #{}", "No anchor #{} in the document"),
+  ("{} 中没有锚点 #{}", "No anchor #{1} in {0}"),
+  ("HTML 锚点正则表达式错误: {}", "HTML anchor regex error: {}"),
+  ("请求超时", "Request timed out"),
+  ("重定向超过 {} 次", "More than {} redirects"),
+  ("创建 HTTP 客户端失败: {}", "Failed to create the HTTP client: {}"),
+  // main
+  ("获取客户端根目录失败: {}", "Failed to list client roots: {}"),
+  ("一个 markdown 文档工具集", "A toolkit for Markdown documents"),
+  // outline
+  ("没有找到标题: {}", "Heading not found: {}"),
+  ("标题路径 {} 匹配到多个标题（第 {} 行），请使用锚点或行号", "Heading path {} matches several headings (lines {}), use an anchor or a line number instead"),
+  ("第{}行", "line {}"),
+  ("编号路径 {} 匹配到多个标题（第 {} 行），请使用锚点或行号", "Number path {} matches several headings (lines {}), use an anchor or a line number instead"),
+  // project_config
+  ("配置文件格式错误: {} - {}", "Invalid config file: {} - {}"),
+  ("配置文件 {}: {}", "Config file {}: {}"),
+  // prompts
+  ("Markdown 文件的绝对路径", "Absolute path of the Markdown file"),
+  ("规范化文档标题：检查标题层级和编号，修复问题后重新生成统一的章节编号", "Normalize document headings: check heading levels and numbering, fix the problems, then regenerate consistent chapter numbers"),
+  ("一级标题是否使用中文编号（true/false），默认 false", "Whether top-level headings use Chinese numerals (true/false), defaults to false"),
+  ("发布前准备：检查并修复标题、生成章节编号、本地化远程图片并插入目录", "Prepare for publishing: check and fix headings, generate chapter numbers, localize remote images and insert a table of contents"),
+  ("图片保存目录，默认 ./assets/", "Directory to save images to, defaults to ./assets/"),
+  ("总结文档中的某个章节，章节原文作为资源附在提示中", "Summarize a section of the document; the section text is attached to the prompt as a resource"),
+  ("章节标题的锚点，可从 markdown://outline/{path} 资源中获取", "Anchor of the section heading, available from the markdown://outline/{path} resource"),
+  ("未知的提示: {}", "Unknown prompt: {}"),
+  (r#"请规范化文档 {0} 的标题，当前标题大纲见附带的资源。按以下步骤处理：
+1. 调用 check_heading 检查标题层级和编号问题；
+2. 如有问题（如跳级、# 后缺少空格），直接修改文档修复；
+3. 调用 remove_all_chapter_numbers 移除已有编号（不另存为新文件）；
+4. 调用 generate_chapter_number 重新生成编号，use_chinese_number 为 {1}，不另存为新文件；
+5. 再次调用 check_heading 确认没有问题，最后简要说明修改了哪些内容。"#, r#"Please normalize the headings of {0}; the current heading outline is attached as a resource. Follow these steps:
+1. Call check_heading to find heading level and numbering problems;
+2. If there are problems (such as skipped levels or a missing space after #), fix them by editing the document directly;
+3. Call remove_all_chapter_numbers to remove existing numbers (do not save as a new file);
+4. Call generate_chapter_number to regenerate the numbers with use_chinese_number set to {1}, without saving as a new file;
+5. Call check_heading again to confirm there are no problems, then briefly describe what was changed."#),
+  ("规范化 {} 的标题", "Normalize the headings of {}"),
+  (r#"请为发布准备文档 {0}，按以下步骤处理：
+1. 调用 check_heading 检查标题，如有问题直接修改文档修复；
+2. 调用 remove_all_chapter_numbers 和 generate_chapter_number 生成统一的章节编号（均不另存为新文件）；
+3. 调用 localize_images 将远程图片下载到 {1}，new_full_file_path 留空以覆盖原文件，并检查失败的图片；
+4. 读取资源 {2}，在第一个标题之前插入目录（指向各标题锚点的嵌套列表），已有目录时更新它；
+5. 汇总每一步的结果以及需要人工处理的问题。"#, r#"Please prepare {0} for publishing. Follow these steps:
+1. Call check_heading to check the headings and fix any problems by editing the document directly;
+2. Call remove_all_chapter_numbers and generate_chapter_number to generate consistent chapter numbers (neither saving as a new file);
+3. Call localize_images to download remote images to {1}, leaving new_full_file_path empty to overwrite the original file, and check the images that failed;
+4. Read the resource {2} and insert a table of contents (a nested list linking to each heading anchor) before the first heading, updating it if one already exists;
+5. Summarize the result of each step and any problems that need manual attention."#),
+  ("为发布准备 {}", "Prepare {} for publishing"),
+  ("请总结文档 {} 中 #{} 章节的内容（原文见附带的资源）：先用一两句话概括要点，再列出关键信息，保持原文使用的语言。", "Please summarize the #{1} section of {0} (the text is attached as a resource): first give the gist in one or two sentences, then list the key information, keeping the language of the original text."),
+  ("总结 {} 的 #{} 章节", "Summarize the #{1} section of {0}"),
+  ("资源 {} 没有文本内容", "Resource {} has no text content"),
+  // resources
+  ("文档的标题大纲，包含每个标题的锚点和行号。path 为 Markdown 文件的绝对路径", "Heading outline of the document, with the anchor and line number of each heading. path is the absolute path of the Markdown file"),
+  ("按标题锚点截取的章节原文，包含其下所有子章节。锚点可从大纲资源中获取", "Text of the section under a heading anchor, including all of its subsections. Anchors are available from the outline resource"),
+  ("文档中所有图片的清单（JSON），包含行号、地址、来源以及本地图片是否存在", "List of all images in the document (JSON), with line number, URL, source and whether local images exist"),
+  ("创建解析器失败: {}", "Failed to create parser: {}"),
+  ("解析失败: {}", "Parsing failed: {}"),
+  ("{} 中没有锚点为 #{} 的标题", "No heading with anchor #{1} in {0}"),
+  ("资源路径不是有效的 UTF-8: {}", "Resource path is not valid UTF-8: {}"),
+  ("资源 URI 缺少文件路径: {}", "Resource URI has no file path: {}"),
+  ("章节资源 URI 缺少锚点: {}", "Section resource URI has no anchor: {}"),
+  ("未知的资源 URI: {}", "Unknown resource URI: {}"),
+  ("（文档中没有标题）", "(the document has no headings)"),
+  ("{}- {} (#{}, 第{}行)", "{}- {} (#{}, line {})"),
+  // sandbox
+  ("路径中的 .. 无法在已存在的目录之上解析", "A .. in the path cannot be resolved above an existing directory"),
+  ("无（客户端声明的根目录都不在 --allow-dir 允许的范围内）", "none (none of the client roots are inside the directories allowed by --allow-dir)"),
+  ("无法访问允许的目录 {}: {}", "Cannot access allowed directory {}: {}"),
+  ("允许的目录不是目录: {}", "Allowed directory is not a directory: {}"),
+  // sections
+  ("没有锚点为 #{} 的标题", "No heading with anchor #{}"),
+  ("第{}行的标题 {} 调整后为 H{}，超出 H1-H6 的范围；设置 clamp=true 可将级别限制在范围内", "The heading {1} on line {0} would become H{2}, outside H1-H6; set clamp=true to clamp levels to that range"),
+  ("文档中没有 H{} 标题，无法拆分", "The document has no H{} headings to split at"),
+  ("不能将章节 {} 移动到它自身或其子章节的位置", "Cannot move section {} relative to itself or one of its subsections"),
+  ("新内容必须以标题开始", "The new content must start with a heading"),
+  ("新内容只能包含一个章节，第{}行的标题 {} 不能与第一个标题同级或比它高", "The new content must be a single section; the heading {1} on line {0} cannot be at the level of the first heading or above it"),
+  ("新内容的标题 {} 为 H{}，与被替换的标题 H{} 不同；设置 adjust_levels=true 可自动调整级别", "The new heading {} is H{}, unlike the replaced H{} heading; set adjust_levels=true to adjust levels automatically"),
+  ("新内容第{}行的标题 {} 为 H{}，不能位于 H{} 标题 {} 之下；设置 adjust_levels=true 可自动调整级别", "The heading {1} on line {0} of the new content is H{2} and cannot go below the H{3} heading {4}; set adjust_levels=true to adjust levels automatically"),
+  // statistics
+  ("📑 {} 个标题（{}），共 {} 字，{} 张图片，{} 个代码块，预计阅读 {} 分钟", "📑 {} headings ({}), {} words, {} images, {} code blocks, about {} min to read"),
+  ("{}- {} (#{}, 第{}-{}行)：{} 字，{} 张图片，{} 个代码块，约 {} 分钟", "{}- {} (#{}, lines {}-{}): {} words, {} images, {} code blocks, about {} min"),
+  // tools
+  ("解析 Markdown 失败: {}", "Failed to parse Markdown: {}"),
+  ("成功为文件 {} 生成章节编号", "Generated chapter numbers for {}"),
+  ("成功清除文件 {} 的所有章节编号", "Removed all chapter numbers from {}"),
+  ("✅ 标题验证通过\n\n{}", "✅ Heading check passed\n\n{}"),
+  ("❌ 标题验证失败\n\n{}", "❌ Heading check failed\n\n{}"),
+  ("文档中没有标题行。", "The document has no headings."),
+  ("📊 标题统计：", "📊 Heading statistics:"),
+  ("  H{}: {} 个", "  H{}: {}"),
+  ("第{}行：标题级别 H{} 超过允许的最深级别 H{}", "Line {}: heading level H{} is deeper than the maximum allowed level H{}"),
+  ("第{}行：标题级别跳级，从 H{} 直接跳到 H{}（跳过了 H{}）", "Line {}: heading level skipped, from H{} straight to H{} (H{} skipped)"),
+  ("✅ 所有标题格式和层级结构都正确。", "✅ All heading formats and levels are correct."),
+  ("第{}行：标题格式错误，应该以 {} 开头", "Line {}: malformed heading, it should start with {}"),
+  ("第{}行：标题格式错误，# 符号前不能有空格或其他字符", "Line {}: malformed heading, there must be nothing before the # characters"),
+  ("第{}行：标题格式错误，{} 后面必须有一个空格", "Line {}: malformed heading, {} must be followed by a space"),
+  ("第{}行：标题格式错误，{} 后面只能有一个空格", "Line {}: malformed heading, {} must be followed by exactly one space"),
+  ("第{}行：标题格式错误，缺少标题内容", "Line {}: malformed heading, the heading text is missing"),
+  ("文件验证失败: {}", "File validation failed: {}"),
+  ("{}，文档未修改: {}", "{}, the document was not modified: {}"),
+  ("图片本地化失败: {}", "Image localization failed: {}"),
+  ("写入文件失败: {}", "Failed to write file: {}"),
+  ("✅ 处理完毕: {}", "✅ Done: {}"),
+  ("图片发布失败: {}", "Image publishing failed: {}"),
+  ("文档中没有需要处理的本地图片", "The document has no local images to process"),
+  ("未知的工具: {}，可选值为: {}", "Unknown tool: {}, expected one of: {}"),
+  ("配置文件: {}", "Config file: {}"),
+  ("未找到 {}，使用工具的默认参数", "{} not found, using the default tool arguments"),
+  ("匹配的覆盖设置: {}", "Matched overrides: {}"),
+  ("{} 使用的项目设置（调用时传入的参数优先）:", "Project settings used by {} (arguments passed in the call take precedence):"),
+  ("生效的项目设置（调用时传入的参数优先）:", "Effective project settings (arguments passed in the call take precedence):"),
+  ("扫描资源失败: {}", "Failed to scan assets: {}"),
+  ("📊 扫描了 {} 个文档，资源目录 {} 中共有 {} 个图片文件", "📊 Scanned {} documents, the assets directory {} contains {} image files"),
+  ("✅ 没有未被引用的文件", "✅ No unreferenced files"),
+  ("🗑️ 未被引用的文件（{} 个）：", "🗑️ Unreferenced files ({}):"),
+  ("✅ 没有失效的图片引用", "✅ No broken image references"),
+  ("❌ 失效的图片引用（{} 个）：", "❌ Broken image references ({}):"),
+  ("  - {} 第{}行: {}", "  - {} line {}: {}"),
+  ("调整标题级别失败: {}", "Failed to shift heading levels: {}"),
+  ("✅ 已将 {} 个标题的级别调整 {}，保存为: {}", "✅ Shifted {} headings by {}, saved as: {}"),
+  ("⚠️ {} 个标题超出 H1-H6 的范围，已限制在范围内", "⚠️ {} headings fell outside H1-H6 and were clamped"),
+  ("🔢 文档原有章节编号，已按原来的编号风格重新生成", "🔢 The document was numbered; chapter numbers were regenerated in the same style"),
+  ("提取章节失败: {}", "Failed to extract section: {}"),
+  ("新文档不能与原文档相同: {}", "The new document must not be the original document: {}"),
+  ("文件已存在: {}，设置 overwrite=true 可覆盖", "File already exists: {}, set overwrite=true to overwrite it"),
+  ("✅ 已将章节 {}（第{}行）提取到: {}", "✅ Extracted section {} (line {}) to: {}"),
+  ("🔗 原文中的章节内容已替换为指向新文档的链接", "🔗 Replaced the section content in the original document with a link to the new document"),
+  ("拆分文档失败: {}", "Failed to split document: {}"),
+  ("创建目录失败: {}", "Failed to create directory: {}"),
+  ("✅ 已将文档拆分为 {} 个文件，保存在: {}", "✅ Split the document into {} files in: {}"),
+  ("📑 索引文件: {}", "📑 Index file: {}"),
+  ("🖼️ 已移动图片: {} -> {}", "🖼️ Moved image: {} -> {}"),
+  ("❌ 移动图片失败: {} - {}", "❌ Failed to move image: {} - {}"),
+  ("目标位置已存在同名文件: {}", "A file with the same name already exists at the target: {}"),
+  ("合并文档失败: {}", "Failed to merge documents: {}"),
+  ("合并后的文档不能是要合并的文档之一: {}", "The merged document must not be one of the documents being merged: {}"),
+  ("✅ 已将 {} 个文档合并到: {}", "✅ Merged {} documents into: {}"),
+  ("🔗 {} 个指向被合并文档的链接已改写为文档内的锚点", "🔗 Rewrote {} links to merged documents as anchors within the document"),
+  ("⚓ {} 个标题的锚点因重名或重新编号而改变，指向它们的链接已同步更新", "⚓ The anchors of {} headings changed because of duplicate names or renumbering; links to them were updated"),
+  ("目录中没有 Markdown 文档: {}", "No Markdown documents in directory: {}"),
+  ("移动章节失败: {}", "Failed to move section: {}"),
+  ("✅ 已将章节 {} 移动到 {} 之前，保存为: {}", "✅ Moved section {} before {}, saved as: {}"),
+  ("✅ 已将章节 {} 移动到 {} 之后，保存为: {}", "✅ Moved section {} after {}, saved as: {}"),
+  ("✅ 已将章节 {} 移动到 {} 之内，保存为: {}", "✅ Moved section {} into {}, saved as: {}"),
+  ("📐 章节的标题级别调整 {}", "📐 Heading levels of the section shifted by {}"),
+  ("🔢 已按原来的编号风格重新生成章节编号", "🔢 Regenerated the chapter numbering in the original style"),
+  ("删除章节失败: {}", "Failed to delete section: {}"),
+  ("✅ 已删除章节 {}（第 {}-{} 行，包含 {} 个子章节），保存为: {}", "✅ Deleted section {} (lines {}-{}, {} subsections), saved as: {}"),
+  ("读取章节失败: {}", "Failed to read section: {}"),
+  ("已截断：显示了 {} / {} 个字符，第 {} 行及之后的内容未显示", "Truncated: showing {} of {} characters, content from line {} on is omitted"),
+  ("省略了 {} 个子章节：{}", "{} subsections omitted: {}"),
+  ("替换章节失败: {}", "Failed to replace section: {}"),
+  ("✅ 已替换章节 {}（原第 {}-{} 行，替换后 {} 行），保存为: {}", "✅ Replaced section {} (lines {}-{}, now {} lines), saved as: {}"),
+  ("📐 新内容的标题级别调整 {}", "📐 Heading levels of the new content shifted by {}"),
+  ("生成大纲失败: {}", "Failed to build the outline: {}"),
+  ("序列化大纲失败: {}", "Failed to serialize the outline: {}"),
+  ("检查链接失败: {}", "Failed to check links: {}"),
+  ("🔗 共检查 {} 个链接，跳过 {} 个外部链接和 {} 个无法检查的链接", "🔗 Checked {} links, skipped {} external links and {} links that cannot be checked"),
+  ("✅ 没有发现失效的链接", "✅ No broken links found"),
+  ("❌ 发现 {} 个失效的链接：", "❌ Found {} broken links:"),
+  ("- 第 {} 行第 {} 列 {}：{}", "- Line {}, column {}, {}: {}"),
+  ("  💡 是否应为 {}？", "  💡 Did you mean {}?"),
+  ("↪️ {} 个外部链接发生了重定向：", "↪️ {} external links were redirected:"),
+  ("- 第 {} 行第 {} 列 {}：{} 重定向到 {}", "- Line {}, column {}, {}: {} redirect to {}"),
+  // utils
+  ("{}, 新文件保存为: {}", "{}, saved as new file: {}"),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use std::collections::BTreeSet;
+
+  /// 消息模板使用的参数位置
+  fn placeholder_indexes(template: &str) -> BTreeSet<usize> {
+    let mut indexes = BTreeSet::new();
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+      let Some(end) = rest[start..].find('}').map(|end| start + end) else {
+        break;
+      };
+      match &rest[start + 1..end] {
+        "" => {
+          indexes.insert(next);
+          next += 1;
+        }
+        inner => {
+          if let Ok(index) = inner.parse() {
+            indexes.insert(index);
+          }
+        }
+      }
+      rest = &rest[end + 1..];
+    }
+    indexes
+  }
+
+  /// 测试语言代码解析
+  #[test]
+  fn test_lang_parse() {
+    assert_eq!(Lang::parse("en"), Some(Lang::En));
+    assert_eq!(Lang::parse("en_US.UTF-8"), Some(Lang::En));
+    assert_eq!(Lang::parse("zh-CN"), Some(Lang::Zh));
+    assert_eq!(Lang::parse(" ZH "), Some(Lang::Zh));
+    assert_eq!(Lang::parse("fr"), None);
+  }
+
+  /// 测试依次和按位置使用参数，其他花括号原样保留
+  #[test]
+  fn test_format_message() {
+    assert_eq!(format_message("{} 和 {}", &[&1, &"b"]), "1 和 b");
+    assert_eq!(format_message("{1} 在 {0} 之前", &[&"a", &"b"]), "b 在 a 之前");
+    assert_eq!(format_message("{path} 与 {}", &[&"x"]), "{path} 与 x");
+    assert_eq!(format_message("缺少 {} 和 {", &[&"a"]), "缺少 a 和 {");
+    assert_eq!(tr!("文件不存在: {}", "a.md"), "文件不存在: a.md");
+  }
+
+  /// 测试按调用指定的语言翻译
+  #[tokio::test]
+  async fn test_with_lang() {
+    let message = with_lang(Lang::En, async { tr!("文件不存在: {}", "a.md") }).await;
+    assert_eq!(message, "File does not exist: a.md");
+    let message =
+      with_lang(Lang::En, async { tr!("参数 {} 的值 {} 无效，可选值为: {}", "mode", "x", "a, b") }).await;
+    assert_eq!(message, "Invalid value x for argument mode, expected one of: a, b");
+    assert_eq!(
+      with_lang(Lang::En, async { tool_description("check_heading") }).await.lines().next(),
+      Some("Validate the format and level structure of the heading lines in a Markdown document.")
+    );
+
+    // 目录中没有的消息原样返回
+    assert_eq!(with_lang(Lang::En, async { tr!("没有翻译的消息") }).await, "没有翻译的消息");
+    assert_eq!(current_lang(), Lang::Zh);
+  }
+
+  /// 测试从工具参数中取出 lang 参数
+  #[test]
+  fn test_take_lang_arg() {
+    let args = json!({"full_file_path": "/a.md", "lang": "en"});
+    let (lang, rest) = take_lang_arg(args.as_object()).unwrap();
+    assert_eq!(lang, Some(Lang::En));
+    assert_eq!(Value::Object(rest.unwrap()), json!({"full_file_path": "/a.md"}));
+
+    let args = json!({"full_file_path": "/a.md"});
+    assert_eq!(take_lang_arg(args.as_object()).unwrap().0, None);
+    assert_eq!(take_lang_arg(None).unwrap(), (None, None));
+    assert!(take_lang_arg(json!({"lang": "fr"}).as_object()).unwrap_err().contains("可选值为: zh, en"));
+    assert!(take_lang_arg(json!({"lang": 1}).as_object()).is_err());
+  }
+
+  /// 测试英文目录的每条翻译都使用与中文原文相同的参数，且没有重复的消息 ID
+  #[test]
+  fn test_catalog_placeholders() {
+    let mut msgids = BTreeSet::new();
+    for (msgid, translation) in EN_MESSAGES {
+      assert!(msgids.insert(*msgid), "重复的消息 ID: {}", msgid);
+      assert_eq!(placeholder_indexes(msgid), placeholder_indexes(translation), "参数不一致: {}", msgid);
+    }
+    for (tool, zh, en) in TOOL_DESCRIPTIONS {
+      assert!(!zh.is_empty() && !en.is_empty(), "工具 {} 缺少说明", tool);
+    }
+  }
+}
@@ -3,126 +3,403 @@
 //! 负责下载远程图片并保存到本地
 
 use crate::config::LocalizeImagesConfig;
-use crate::mst::{ImageInfo, MSTNode};
+use crate::error::{MarkdownError, Result};
+use crate::mst::{ImageInfo, ImageSource, ImageType, LinkDefinition, MSTNode, NodeType};
+use crate::parser::{LinkDefinitions, MarkdownParser};
+use crate::renderer::MarkdownRenderer;
+use crate::sandbox::Sandbox;
+use crate::tr;
+use base64::Engine;
+use percent_encoding::percent_decode_str;
 use reqwest;
 use sha2::{Digest, Sha256};
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 use url::Url;
 
+/// 图片本地化进度
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalizeProgress {
+  /// 已处理的图片数量（包括失败的图片）
+  pub processed: usize,
+  /// 需要处理的图片总数
+  pub total: usize,
+  /// 刚处理完的图片地址
+  pub url: String,
+}
+
+/// 进度回调，每处理完一张图片调用一次
+pub type ProgressCallback = Box<dyn Fn(LocalizeProgress) + Send + Sync>;
+
 /// 图片本地化器
 pub struct ImageLocalizer {
   config: LocalizeImagesConfig,
   client: reqwest::Client,
+  parser: MarkdownParser,
+  renderer: MarkdownRenderer,
+  progress: Option<ProgressCallback>,
+  cancellation: CancellationToken,
+  sandbox: Sandbox,
+  total: AtomicUsize,
+  processed: AtomicUsize,
 }
 
 impl ImageLocalizer {
   /// 创建新的图片本地化器
   pub fn new(config: LocalizeImagesConfig) -> Self {
-    let client = reqwest::Client::builder()
-      .timeout(Duration::from_secs(10)) // 总超时时间
+    let client = http_client_builder(Duration::from_secs(10)) // 总超时时间
       .build()
       .unwrap();
-    Self { config, client }
+    Self {
+      config,
+      client,
+      parser: MarkdownParser::default(),
+      renderer: MarkdownRenderer::new(),
+      progress: None,
+      cancellation: CancellationToken::new(),
+      sandbox: Sandbox::default(),
+      total: AtomicUsize::new(0),
+      processed: AtomicUsize::new(0),
+    }
+  }
+
+  /// 设置进度回调
+  pub fn with_progress(mut self, progress: ProgressCallback) -> Self {
+    self.progress = Some(progress);
+    self
+  }
+
+  /// 设置取消令牌，取消后停止下载并返回错误，调用方不应再写回文档
+  pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+    self.cancellation = cancellation;
+    self
+  }
+
+  /// 设置沙箱，复制文档目录之外的本地图片时只允许读取根目录内的文件
+  pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+    self.sandbox = sandbox;
+    self
   }
 
-  /// 本地化 MST 中的所有图片
-  pub async fn localize_images(&self, mst: &mut MSTNode) -> Result<Vec<String>, String> {
+  /// 本地化 MST 中的所有图片，取消时返回 [`MarkdownError::Cancelled`]
+  pub async fn localize_images(&self, mst: &mut MSTNode) -> Result<Vec<String>> {
     let mut results = Vec::new();
 
     // 确保保存目录存在，使用处理占位符后的路径
     let save_dir = PathBuf::from(self.config.get_resolved_save_dir());
-    fs::create_dir_all(&save_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    fs::create_dir_all(&save_dir).map_err(|e| MarkdownError::io(save_dir.display().to_string(), e))?;
+
+    // 引用式图片的 URL 在链接引用定义中，只需改写被图片引用到的定义
+    let definitions = collect_link_definitions(mst);
+    let referenced_labels = collect_image_reference_labels(&self.parser, mst, &definitions);
+
+    if self.progress.is_some() {
+      self.total.store(self.count_pending_images(mst, &save_dir, &referenced_labels), Ordering::Relaxed);
+      self.processed.store(0, Ordering::Relaxed);
+    }
 
     // 递归处理所有图片节点
     let mut index = 0;
-    self.process_images_recursive(mst, &mut index, &save_dir, &mut results).await?;
+    self.process_images_recursive(mst, &mut index, &save_dir, &referenced_labels, &mut results).await?;
+
+    // 中途取消时 MST 可能只改写了一部分
+    self.check_cancelled()?;
 
     Ok(results)
   }
 
+  /// 统计需要处理的图片数量，作为进度的总数
+  fn count_pending_images(&self, mst: &MSTNode, save_dir: &Path, referenced_labels: &HashSet<String>) -> usize {
+    let mut urls: Vec<String> = collect_document_images(&self.parser, mst)
+      .iter()
+      .filter_map(|image| image.get_image_info())
+      .filter(|info| info.image_type != ImageType::Reference)
+      .map(|info| info.original_url.clone())
+      .collect();
+    mst.walk(&mut |node| {
+      let definition = node
+        .get_link_definition()
+        .filter(|definition| referenced_labels.contains(&LinkDefinition::normalize_label(&definition.label)));
+      if let Some(definition) = definition {
+        urls.push(definition.url.clone());
+      }
+    });
+
+    urls.iter().filter(|url| self.needs_localization(url, save_dir)).count()
+  }
+
+  /// 判断图片地址是否需要本地化，与 localize_url 的处理范围一致
+  fn needs_localization(&self, url: &str, save_dir: &Path) -> bool {
+    match ImageSource::from_url(url) {
+      ImageSource::Remote => true,
+      ImageSource::DataUri => self.config.localize_data_uris,
+      ImageSource::Local => {
+        self.config.copy_local_images && !matches!(self.resolve_out_of_tree_image(url, save_dir), Ok(None))
+      }
+    }
+  }
+
+  /// 已取消时返回错误
+  fn check_cancelled(&self) -> Result<()> {
+    if self.cancellation.is_cancelled() {
+      return Err(MarkdownError::Cancelled);
+    }
+    Ok(())
+  }
+
+  /// 报告一张图片处理完毕
+  fn report_progress(&self, url: &str) {
+    if let Some(progress) = &self.progress {
+      let processed = self.processed.fetch_add(1, Ordering::Relaxed) + 1;
+      let total = self.total.load(Ordering::Relaxed).max(processed);
+      progress(LocalizeProgress { processed, total, url: display_url(url) });
+    }
+  }
+
   /// 递归处理图片节点和包含图片的内容节点
   fn process_images_recursive<'a>(
     &'a self,
     node: &'a mut MSTNode,
     index: &'a mut usize,
     save_dir: &'a Path,
+    referenced_labels: &'a HashSet<String>,
     results: &'a mut Vec<String>,
-  ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<(), String>> + Send + 'a>> {
+  ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Result<()>> + Send + 'a>> {
     Box::pin(async move {
-      // 处理图片节点
-      if let Some(image_info) = node.get_image_info_mut() {
-        match self.download_and_save_image(image_info, *index, save_dir).await {
+      self.check_cancelled()?;
+
+      // 处理图片节点，引用式图片通过其链接引用定义处理
+      let image_info = node.get_image_info_mut().filter(|info| info.image_type != ImageType::Reference);
+      let outcome = match &image_info {
+        Some(info) => self.localize_url(&info.original_url, *index, save_dir).await,
+        None => None,
+      };
+      if let (Some(image_info), Some(outcome)) = (image_info, outcome) {
+        let (success, failure) = action_labels(image_info.source());
+        let url = display_url(&image_info.original_url);
+        match outcome {
           Ok(local_path) => {
-            results.push(format!("✅ 成功下载: {} -> {}", image_info.original_url, local_path));
+            results.push(tr!("✅ {}: {} -> {}", success, url, local_path));
             image_info.local_path = Some(local_path);
           }
           Err(e) => {
-            results.push(format!("❌ 下载失败: {} - {}", image_info.original_url, e));
+            results.push(tr!("❌ {}: {} - {}", failure, url, e));
           }
         }
         *index += 1;
       }
 
-      // 处理包含图片的内容节点
-      if node.is_content() {
-        if let crate::mst::NodeType::Content(content) = &mut node.node_type {
-          let updated_content = self.process_inline_images_in_content(content, index, save_dir, results).await?;
-          *content = updated_content;
+      // 处理被图片引用的链接引用定义，所有引用共用一次下载
+      if let Some(definition) = node.get_link_definition_mut() {
+        let is_referenced = referenced_labels.contains(&LinkDefinition::normalize_label(&definition.label));
+        let outcome = if is_referenced { self.localize_url(&definition.url, *index, save_dir).await } else { None };
+        if let Some(outcome) = outcome {
+          let (success, failure) = action_labels(ImageSource::from_url(&definition.url));
+          let url = display_url(&definition.url);
+          match outcome {
+            Ok(local_path) => {
+              results.push(tr!("✅ {}: {} -> {}（引用 [{}]）", success, url, local_path, definition.label));
+              definition.local_path = Some(local_path);
+            }
+            Err(e) => {
+              results.push(tr!("❌ {}: {} - {}（引用 [{}]）", failure, url, e, definition.label));
+            }
+          }
+          *index += 1;
         }
       }
 
+      // 处理包含图片的内容节点
+      if let NodeType::Content(content) = &mut node.node_type {
+        let updated_content = self.process_inline_images_in_content(content, index, save_dir, results).await?;
+        *content = updated_content;
+      }
+
       for child in &mut node.children {
-        self.process_images_recursive(child, index, save_dir, results).await?;
+        self.process_images_recursive(child, index, save_dir, referenced_labels, results).await?;
       }
 
       Ok(())
     })
   }
 
-  /// 下载并保存图片
-  async fn download_and_save_image(
-    &self,
-    image_info: &ImageInfo,
-    index: usize,
-    save_dir: &Path,
-  ) -> Result<String, String> {
-    // 下载图片
-    let response = self.client.get(&image_info.original_url).send().await.map_err(|e| format!("请求失败: {}", e))?;
+  /// 根据图片来源本地化图片地址，返回相对于 Markdown 文件的路径
+  ///
+  /// 远程图片总是下载；data URI 和文档目录之外的本地图片按配置处理，
+  /// 不需要处理的图片返回 None。
+  async fn localize_url(&self, url: &str, index: usize, save_dir: &Path) -> Option<Result<String>> {
+    let outcome = self.localize_url_by_source(url, index, save_dir).await;
+    if outcome.is_some() && !self.cancellation.is_cancelled() {
+      self.report_progress(url);
+    }
+    outcome
+  }
+
+  /// 按图片来源分别处理，远程下载可被取消
+  async fn localize_url_by_source(&self, url: &str, index: usize, save_dir: &Path) -> Option<Result<String>> {
+    match ImageSource::from_url(url) {
+      ImageSource::Remote => Some(tokio::select! {
+        outcome = self.download_and_save_url(url, index, save_dir) => outcome,
+        _ = self.cancellation.cancelled() => Err(MarkdownError::Cancelled),
+      }),
+      ImageSource::DataUri => self.config.localize_data_uris.then(|| self.save_data_uri(url, index, save_dir)),
+      ImageSource::Local => {
+        if !self.config.copy_local_images {
+          return None;
+        }
+        match self.resolve_out_of_tree_image(url, save_dir) {
+          Ok(Some(source_path)) => Some(self.copy_local_image(&source_path, index, save_dir)),
+          Ok(None) => None,
+          Err(e) => Some(Err(e)),
+        }
+      }
+    }
+  }
 
-    if !response.status().is_success() {
-      return Err(format!("HTTP 错误: {}", response.status()));
+  /// 解码 data URI 并保存为图片文件
+  fn save_data_uri(&self, url: &str, index: usize, save_dir: &Path) -> Result<String> {
+    let (mime, bytes) = decode_data_uri(url)?;
+    if !mime.starts_with("image/") {
+      return Err(MarkdownError::InvalidImage { reason: tr!("data URI 不是图片类型: {}", mime) });
+    }
+    self.check_size(bytes.len() as u64)?;
+
+    let extension = match detect_image_format(&bytes) {
+      Some(format) => format.extension().to_string(),
+      None => {
+        return Err(MarkdownError::InvalidImage {
+          reason: tr!("内嵌数据不是有效的图片（声明类型: {}）", mime)
+        })
+      }
+    };
+
+    self.save_image_bytes(&bytes, &extension, index, save_dir)
+  }
+
+  /// 解析本地图片路径，仅返回位于文档目录之外、需要复制的图片
+  fn resolve_out_of_tree_image(&self, url: &str, save_dir: &Path) -> Result<Option<PathBuf>> {
+    let md_dir = markdown_dir(&self.config.full_file_path)?;
+
+    let Some(path) = resolve_local_image_path(md_dir, url)? else {
+      return Ok(None);
+    };
+
+    let source = path.canonicalize().map_err(|e| MarkdownError::io(path.display().to_string(), e))?;
+    let md_dir = md_dir.canonicalize().map_err(|e| MarkdownError::io(md_dir.display().to_string(), e))?;
+    let save_dir = save_dir.canonicalize().map_err(|e| MarkdownError::io(save_dir.display().to_string(), e))?;
+
+    if source.starts_with(&md_dir) || source.starts_with(&save_dir) {
+      return Ok(None);
+    }
+    self.sandbox.check_path(&source.to_string_lossy())?;
+    Ok(Some(source))
+  }
+
+  /// 将文档目录之外的本地图片复制到图片目录
+  fn copy_local_image(&self, source: &Path, index: usize, save_dir: &Path) -> Result<String> {
+    let io_error = |e| MarkdownError::io(source.display().to_string(), e);
+    let size = fs::metadata(source).map_err(io_error)?.len();
+    self.check_size(size)?;
+
+    let bytes = fs::read(source).map_err(io_error)?;
+    let extension =
+      match detect_image_format(&bytes) {
+        Some(format) => format.extension().to_string(),
+        None => source.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_lowercase()).ok_or_else(|| {
+          MarkdownError::InvalidImage { reason: tr!("无法识别图片格式: {}", source.display()) }
+        })?,
+      };
+
+    self.save_image_bytes(&bytes, &extension, index, save_dir)
+  }
+
+  /// 检查图片大小是否超过上限
+  fn check_size(&self, size: u64) -> Result<()> {
+    if size > self.config.max_image_size {
+      return Err(MarkdownError::ImageTooLarge { size: Some(size), limit: self.config.max_image_size });
+    }
+    Ok(())
+  }
+
+  /// 按文件名模式保存图片内容，返回相对路径
+  fn save_image_bytes(&self, bytes: &[u8], extension: &str, index: usize, save_dir: &Path) -> Result<String> {
+    let filename = self.build_filename(extension, index, bytes);
+    let file_path = save_dir.join(&filename);
+    fs::write(&file_path, bytes).map_err(|e| MarkdownError::io(file_path.display().to_string(), e))?;
+    self.get_relative_path(&file_path)
+  }
+
+  /// 下载指定 URL 的图片并保存，返回相对于 Markdown 文件的路径
+  async fn download_and_save_url(&self, url: &str, index: usize, save_dir: &Path) -> Result<String> {
+    // 下载图片
+    let mut response = self.client.get(url).send().await.map_err(|e| MarkdownError::NetworkError {
+      url: url.to_string(),
+      status: None,
+      message: e.to_string(),
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+      return Err(MarkdownError::NetworkError {
+        url: url.to_string(),
+        status: Some(status.as_u16()),
+        message: status.canonical_reason().unwrap_or_default().to_string(),
+      });
     }
 
     let content_type =
       response.headers().get(reqwest::header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).map(|s| s.to_string());
 
-    let bytes = response.bytes().await.map_err(|e| format!("读取响应失败: {}", e))?;
+    let bytes = self.read_body_with_limit(&mut response).await?;
+
+    // 通过文件头确认内容确实是图片，扩展名取自识别出的真实格式，不采信 URL 和 Content-Type
+    let Some(format) = detect_image_format(&bytes) else {
+      return Err(MarkdownError::InvalidImage { reason: describe_non_image_payload(content_type.as_deref(), &bytes) });
+    };
 
     // 生成文件名
-    let filename = self.generate_filename(&image_info.original_url, index, content_type.as_deref(), &bytes)?;
+    let filename = self.build_filename(format.extension(), index, &bytes);
     let file_path = save_dir.join(&filename);
 
     // 保存文件
-    fs::write(&file_path, &bytes).map_err(|e| format!("保存文件失败: {}", e))?;
+    fs::write(&file_path, &bytes).map_err(|e| MarkdownError::io(file_path.display().to_string(), e))?;
 
     // 返回相对路径
     let relative_path = self.get_relative_path(&file_path)?;
     Ok(relative_path)
   }
 
-  /// 生成文件名
-  fn generate_filename(
-    &self,
-    url: &str,
-    index: usize,
-    content_type: Option<&str>,
-    bytes: &[u8],
-  ) -> Result<String, String> {
-    // 获取文件扩展名
-    let extension = self.get_file_extension(url, content_type)?;
+  /// 以流的方式读取响应体，超过大小上限时立即中止
+  async fn read_body_with_limit(&self, response: &mut reqwest::Response) -> Result<Vec<u8>> {
+    let max_size = self.config.max_image_size;
+
+    if let Some(length) = response.content_length() {
+      self.check_size(length)?;
+    }
+
+    let mut bytes = Vec::new();
+    let url = response.url().to_string();
+    let read_error = |e: reqwest::Error| MarkdownError::NetworkError {
+      url: url.clone(),
+      status: None,
+      message: tr!("读取响应失败: {}", e),
+    };
+    while let Some(chunk) = response.chunk().await.map_err(read_error)? {
+      if (bytes.len() + chunk.len()) as u64 > max_size {
+        return Err(MarkdownError::ImageTooLarge { size: None, limit: max_size });
+      }
+      bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+  }
 
+  /// 根据文件名模式、序号和内容哈希生成带扩展名的文件名
+  fn build_filename(&self, extension: &str, index: usize, bytes: &[u8]) -> String {
     // 生成哈希
     let mut hasher = Sha256::new();
     hasher.update(bytes);
@@ -137,31 +414,20 @@ impl ImageLocalizer {
     // NOTE: multilevel_num为图片所处的标题行层级路径，比如 1.2.1.3.
     filename = filename.replace("{multilevel_num}", &format!("{}", index + 1));
 
-    Ok(format!("{}.{}", filename, extension))
-  }
-
-  /// 获取文件扩展名
-  fn get_file_extension(&self, url: &str, content_type: Option<&str>) -> Result<String, String> {
-    let parsed_url = Url::parse(url).map_err(|e| format!("解析 URL 失败: {}", e))?;
-    let path = parsed_url.path();
-
-    if let Some(extension) = Path::new(path).extension() {
-      if let Some(ext_str) = extension.to_str() {
-        return Ok(ext_str.to_lowercase());
-      }
-    }
-
-    Ok(content_type.unwrap_or("image/jpg").replace("image/", "").to_string())
+    format!("{}.{}", filename, extension)
   }
 
   /// 获取相对于 Markdown 文件的相对路径
-  fn get_relative_path(&self, file_path: &Path) -> Result<String, String> {
-    let md_file_path = Path::new(&self.config.full_file_path);
-    let md_dir = md_file_path.parent().ok_or("无法获取 Markdown 文件目录")?;
+  fn get_relative_path(&self, file_path: &Path) -> Result<String> {
+    let md_dir = markdown_dir(&self.config.full_file_path)?;
 
-    let relative_path = file_path.strip_prefix(md_dir).map_err(|_| "无法计算相对路径")?;
+    let relative_path =
+      file_path.strip_prefix(md_dir).map_err(|_| MarkdownError::FileError(tr!("无法计算相对路径").to_string()))?;
 
-    relative_path.to_str().ok_or("路径包含无效字符".to_string()).map(|s| s.replace('\\', "/"))
+    relative_path
+      .to_str()
+      .ok_or_else(|| MarkdownError::FileError(tr!("路径包含无效字符").to_string()))
+      .map(|s| s.replace('\\', "/"))
     // 统一使用正斜杠
   }
 
@@ -172,10 +438,9 @@ impl ImageLocalizer {
     index: &mut usize,
     save_dir: &Path,
     results: &mut Vec<String>,
-  ) -> Result<String, String> {
-    // 复用解析器的图片解析逻辑
-    let parser = crate::parser::MarkdownParser::new().map_err(|e| format!("创建解析器失败: {}", e))?;
-    let image_nodes = parser.parse_images_in_line(content, 0);
+  ) -> Result<String> {
+    // 复用解析器的图片解析逻辑，引用式图片由链接引用定义统一处理
+    let image_nodes = self.parser.parse_images_in_line(content, 0);
 
     // 如果没有图片，直接返回原内容
     if image_nodes.is_empty() {
@@ -186,15 +451,17 @@ impl ImageLocalizer {
 
     // 处理每个图片节点
     for image_node in image_nodes {
+      self.check_cancelled()?;
       if let Some(image_info) = image_node.get_image_info() {
         // 使用通用的图片处理函数
-        match self.process_single_image_for_content(image_info, *index, save_dir, results).await {
-          Ok((original_text, replacement_text)) => {
-            updated_content = updated_content.replace(&original_text, &replacement_text);
+        match self.process_single_image_for_content(&image_node, image_info, *index, save_dir, results).await {
+          Ok(Some((original_text, replacement_text))) => {
+            updated_content = updated_content.replacen(&original_text, &replacement_text, 1);
             *index += 1;
           }
+          Ok(None) => {}
           Err(e) => {
-            results.push(format!("❌ 处理图片失败: {} - {}", image_info.original_url, e));
+            results.push(tr!("❌ 处理图片失败: {} - {}", display_url(&image_info.original_url), e));
           }
         }
       }
@@ -206,75 +473,288 @@ impl ImageLocalizer {
   /// 处理单个图片（通用函数）
   async fn process_single_image_for_content(
     &self,
+    image_node: &MSTNode,
     image_info: &ImageInfo,
     index: usize,
     save_dir: &Path,
     results: &mut Vec<String>,
-  ) -> Result<(String, String), String> {
-    // 下载并保存图片
-    let local_path = self.download_and_save_image(image_info, index, save_dir).await?;
+  ) -> Result<Option<(String, String)>> {
+    // 按图片来源本地化，不需要处理的图片保持原样
+    let Some(outcome) = self.localize_url(&image_info.original_url, index, save_dir).await else {
+      return Ok(None);
+    };
+    let local_path = outcome?;
 
-    results.push(format!("✅ 成功下载: {} -> {}", image_info.original_url, local_path));
+    let (success, _) = action_labels(image_info.source());
+    results.push(tr!("✅ {}: {} -> {}", success, display_url(&image_info.original_url), local_path));
 
-    // 构建原始文本和替换文本
-    let (original_text, replacement_text) = match image_info.image_type {
-      crate::mst::ImageType::Markdown => {
-        // 构建原始 Markdown 图片引用
-        let title_part = image_info.title.as_ref().map(|t| format!(" \"{}\"", t)).unwrap_or_default();
-        let original = format!("![{}]({}{})", image_info.alt_text, image_info.original_url, title_part);
+    // 原始文本即解析时匹配到的片段，替换文本复用渲染器的图片渲染逻辑
+    let mut localized_node = image_node.clone();
+    if let Some(info) = localized_node.get_image_info_mut() {
+      info.local_path = Some(local_path);
+    }
 
-        // 构建替换后的 Markdown 图片引用
-        let replacement = format!("![{}]({}{})", image_info.alt_text, local_path, title_part);
+    Ok(Some((image_node.raw.clone(), self.renderer.render_image(&localized_node))))
+  }
+}
 
-        (original, replacement)
-      }
-      crate::mst::ImageType::Html => {
-        // 构建原始 HTML img 标签
-        let mut original_tag = String::from("<img");
-        if let Some(attrs) = &image_info.html_attributes {
-          if !attrs.is_empty() {
-            original_tag.push(' ');
-            original_tag.push_str(attrs);
-          }
-        }
-        original_tag.push_str(&format!(" src=\"{}\"", image_info.original_url));
-        if !image_info.alt_text.is_empty() {
-          let attrs_str = image_info.html_attributes.as_deref().unwrap_or("");
-          if !attrs_str.contains("alt=") {
-            original_tag.push_str(&format!(" alt=\"{}\"", image_info.alt_text));
-          }
-        }
-        original_tag.push('>');
-
-        // 构建替换后的 HTML img 标签
-        let mut replacement_tag = String::from("<img");
-        if let Some(attrs) = &image_info.html_attributes {
-          if !attrs.is_empty() {
-            replacement_tag.push(' ');
-            replacement_tag.push_str(attrs);
-          }
-        }
-        replacement_tag.push_str(&format!(" src=\"{}\"", local_path));
-        if !image_info.alt_text.is_empty() {
-          let attrs_str = image_info.html_attributes.as_deref().unwrap_or("");
-          if !attrs_str.contains("alt=") {
-            replacement_tag.push_str(&format!(" alt=\"{}\"", image_info.alt_text));
-          }
-        }
-        replacement_tag.push('>');
+/// 收集 MST 中所有的链接引用定义，标签重复时以第一个为准
+pub(crate) fn collect_link_definitions(mst: &MSTNode) -> LinkDefinitions {
+  let mut definitions = LinkDefinitions::new();
+  mst.walk(&mut |node| {
+    if let Some(definition) = node.get_link_definition() {
+      definitions.entry(LinkDefinition::normalize_label(&definition.label)).or_insert_with(|| definition.clone());
+    }
+  });
+  definitions
+}
 
-        (original_tag, replacement_tag)
-      }
+/// 按文档顺序收集所有图片：独立成行的图片节点和内容行中的行内图片，引用式图片的地址取自其定义
+pub(crate) fn collect_document_images(parser: &MarkdownParser, mst: &MSTNode) -> Vec<MSTNode> {
+  let definitions = collect_link_definitions(mst);
+  let mut images = Vec::new();
+  mst.walk(&mut |node| match &node.node_type {
+    NodeType::Content(content) => {
+      images.extend(parser.parse_images_in_line_with_definitions(content, node.line_number, &definitions))
+    }
+    NodeType::Image(_) => images.push(node.clone()),
+    _ => {}
+  });
+  images
+}
+
+/// 收集被引用式图片使用的标签（规范化后）
+pub(crate) fn collect_image_reference_labels(
+  parser: &MarkdownParser,
+  mst: &MSTNode,
+  definitions: &LinkDefinitions,
+) -> HashSet<String> {
+  let mut labels = HashSet::new();
+  mst.walk(&mut |node| {
+    let inline_images = match &node.node_type {
+      NodeType::Content(content) => parser.parse_images_in_line_with_definitions(content, 0, definitions),
+      NodeType::Image(_) => vec![node.clone()],
+      _ => Vec::new(),
     };
+    for image in inline_images {
+      if let Some(label) = image.get_image_info().and_then(|info| info.reference_label.as_deref()) {
+        labels.insert(LinkDefinition::normalize_label(label));
+      }
+    }
+  });
+  labels
+}
+
+/// Markdown 文件所在目录
+pub(crate) fn markdown_dir(full_file_path: &str) -> Result<&Path> {
+  Path::new(full_file_path)
+    .parent()
+    .ok_or_else(|| MarkdownError::FileError(tr!("无法获取 Markdown 文件目录").to_string()))
+}
+
+/// 不同来源图片的成功/失败描述
+fn action_labels(source: ImageSource) -> (&'static str, &'static str) {
+  match source {
+    ImageSource::Remote => (tr!("成功下载"), tr!("下载失败")),
+    ImageSource::DataUri => (tr!("成功保存内嵌图片"), tr!("保存内嵌图片失败")),
+    ImageSource::Local => (tr!("成功复制本地图片"), tr!("复制本地图片失败")),
+  }
+}
+
+/// 创建 HTTP 客户端的构建器，图片下载和外部链接检查共用相同的超时和 User-Agent 设置
+pub(crate) fn http_client_builder(timeout: Duration) -> reqwest::ClientBuilder {
+  reqwest::Client::builder().timeout(timeout).user_agent(concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION")
+  ))
+}
+
+/// 将文档中的本地图片地址解析为文件路径，相对路径以文档目录为基准
+///
+/// 支持 `file://` URL 和百分号编码的路径，其他协议的地址返回 None
+pub(crate) fn resolve_local_image_path(md_dir: &Path, url: &str) -> Result<Option<PathBuf>> {
+  match Url::parse(url) {
+    Ok(parsed) if parsed.scheme() == "file" => {
+      parsed.to_file_path().map(Some).map_err(|_| MarkdownError::ParseError(tr!("无法解析本地路径: {}", url)))
+    }
+    // 其他协议（如 ftp://）不属于本地图片；单字母协议视为 Windows 盘符
+    Ok(parsed) if parsed.scheme().len() > 1 => Ok(None),
+    _ => {
+      let path_part = url.split(['?', '#']).next().unwrap_or(url);
+      let decoded = percent_decode_str(path_part).decode_utf8_lossy().into_owned();
+      Ok(Some(md_dir.join(decoded)))
+    }
+  }
+}
+
+/// 结果中显示的图片地址，过长的 data URI 只保留开头部分
+pub(crate) fn display_url(url: &str) -> String {
+  const MAX_DATA_URI_DISPLAY: usize = 48;
+  if ImageSource::from_url(url) == ImageSource::DataUri && url.chars().count() > MAX_DATA_URI_DISPLAY {
+    let head: String = url.chars().take(MAX_DATA_URI_DISPLAY).collect();
+    return format!("{}…", head);
+  }
+  url.to_string()
+}
+
+/// 解码 data URI，返回 MIME 类型和内容
+///
+/// 格式：`data:[<mediatype>][;base64],<data>`，未声明类型时按 `text/plain` 处理
+fn decode_data_uri(url: &str) -> Result<(String, Vec<u8>)> {
+  let invalid = |reason: String| MarkdownError::InvalidImage { reason };
+  let rest = url.strip_prefix("data:").ok_or_else(|| invalid(tr!("不是 data URI").to_string()))?;
+  let (meta, data) = rest.split_once(',').ok_or_else(|| invalid(tr!("data URI 缺少数据部分").to_string()))?;
+
+  let mut params = meta.split(';');
+  let mime = params.next().map(|m| m.trim().to_lowercase()).filter(|m| !m.is_empty());
+  let is_base64 = params.any(|param| param.trim().eq_ignore_ascii_case("base64"));
+
+  let decoded: Vec<u8> = percent_decode_str(data).collect();
+  let bytes = if is_base64 {
+    let compact: Vec<u8> = decoded.into_iter().filter(|b| !b.is_ascii_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD.decode(compact).map_err(|e| invalid(tr!("base64 解码失败: {}", e)))?
+  } else {
+    decoded
+  };
+
+  Ok((mime.unwrap_or_else(|| "text/plain".to_string()), bytes))
+}
+
+/// 通过文件头（magic bytes）识别出的图片格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+  Png,
+  Jpeg,
+  Gif,
+  Webp,
+  Bmp,
+  Ico,
+  Tiff,
+  Avif,
+  Heic,
+  Svg,
+}
 
-    Ok((original_text, replacement_text))
+impl ImageFormat {
+  /// 保存文件时使用的扩展名
+  pub fn extension(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "png",
+      ImageFormat::Jpeg => "jpg",
+      ImageFormat::Gif => "gif",
+      ImageFormat::Webp => "webp",
+      ImageFormat::Bmp => "bmp",
+      ImageFormat::Ico => "ico",
+      ImageFormat::Tiff => "tiff",
+      ImageFormat::Avif => "avif",
+      ImageFormat::Heic => "heic",
+      ImageFormat::Svg => "svg",
+    }
+  }
+
+  /// 对应的 MIME 类型
+  pub fn mime_type(&self) -> &'static str {
+    match self {
+      ImageFormat::Png => "image/png",
+      ImageFormat::Jpeg => "image/jpeg",
+      ImageFormat::Gif => "image/gif",
+      ImageFormat::Webp => "image/webp",
+      ImageFormat::Bmp => "image/bmp",
+      ImageFormat::Ico => "image/x-icon",
+      ImageFormat::Tiff => "image/tiff",
+      ImageFormat::Avif => "image/avif",
+      ImageFormat::Heic => "image/heic",
+      ImageFormat::Svg => "image/svg+xml",
+    }
+  }
+}
+
+/// 根据文件头识别图片格式，无法识别时返回 None
+pub fn detect_image_format(bytes: &[u8]) -> Option<ImageFormat> {
+  if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+    return Some(ImageFormat::Png);
+  }
+  if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+    return Some(ImageFormat::Jpeg);
+  }
+  if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+    return Some(ImageFormat::Gif);
+  }
+  if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+    return Some(ImageFormat::Webp);
+  }
+  if bytes.starts_with(b"BM") && bytes.len() >= 14 {
+    return Some(ImageFormat::Bmp);
+  }
+  if bytes.starts_with(&[0x00, 0x00, 0x01, 0x00]) {
+    return Some(ImageFormat::Ico);
+  }
+  if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+    return Some(ImageFormat::Tiff);
+  }
+  if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+    match &bytes[8..12] {
+      b"avif" | b"avis" => return Some(ImageFormat::Avif),
+      b"heic" | b"heix" | b"hevc" | b"hevx" => return Some(ImageFormat::Heic),
+      _ => {}
+    }
+  }
+  if looks_like_svg(bytes) {
+    return Some(ImageFormat::Svg);
+  }
+  None
+}
+
+/// SVG 是文本格式，只检查开头部分是否有 <svg 根元素
+fn looks_like_svg(bytes: &[u8]) -> bool {
+  let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_lowercase();
+  let head = head.trim_start_matches('\u{feff}').trim_start();
+  if !head.starts_with('<') || head.contains("<html") {
+    return false;
+  }
+  head.contains("<svg")
+}
+
+/// 为非图片的响应内容生成可读的错误原因
+fn describe_non_image_payload(content_type: Option<&str>, bytes: &[u8]) -> String {
+  if bytes.is_empty() {
+    return tr!("响应内容为空").to_string();
+  }
+
+  let head = String::from_utf8_lossy(&bytes[..bytes.len().min(512)]).to_lowercase();
+  let head = head.trim_start();
+  let detected = if head.starts_with("<!doctype html") || head.contains("<html") {
+    tr!("HTML 页面")
+  } else if head.starts_with('{') || head.starts_with('[') {
+    tr!("JSON 数据")
+  } else {
+    tr!("无法识别的格式")
+  };
+
+  tr!("响应内容不是图片（检测到{}，Content-Type: {}）", detected, content_type.unwrap_or(tr!("未知")))
+}
+
+/// 将字节数格式化为便于阅读的大小
+pub(crate) fn format_size(size: u64) -> String {
+  const KB: f64 = 1024.0;
+  const MB: f64 = KB * 1024.0;
+
+  let size = size as f64;
+  if size >= MB {
+    format!("{:.1} MB", size / MB)
+  } else if size >= KB {
+    format!("{:.1} KB", size / KB)
+  } else {
+    format!("{} B", size)
   }
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::mst::{ImageInfo, ImageType, MSTNode, NodeType};
+  use crate::config::DEFAULT_MAX_IMAGE_SIZE;
+  use crate::mst::{ImageInfo, ImageType, MSTNode, NodeType, TitleDelimiter};
   use std::fs;
   use tempfile::{NamedTempFile, TempDir};
 
@@ -285,6 +765,9 @@ mod tests {
       image_file_name_pattern: "{index}-{hash}".to_string(),
       image_dir: save_dir.to_string(),
       new_full_file_path: None,
+      max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+      localize_data_uris: false,
+      copy_local_images: false,
     }
   }
 
@@ -297,6 +780,9 @@ mod tests {
       local_path: None,
       image_type: ImageType::Markdown,
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
 
     MSTNode {
@@ -319,6 +805,9 @@ mod tests {
       local_path: None,
       image_type: ImageType::Html,
       html_attributes: attributes,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
 
     let raw = if let Some(attrs) = &image_info.html_attributes {
@@ -346,16 +835,13 @@ mod tests {
 
     let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
 
+    // 字段是私有的，这里只测试创建不会 panic
     let _localizer = ImageLocalizer::new(config.clone());
-
-    // 验证配置被正确设置（通过间接方式，因为字段是私有的）
-    // 这里我们主要测试创建不会 panic
-    assert!(true);
   }
 
   /// 测试文件名生成 - 基本情况
   #[test]
-  fn test_generate_filename_basic() {
+  fn test_build_filename_basic() {
     let temp_file = NamedTempFile::new().unwrap();
     let temp_dir = TempDir::new().unwrap();
 
@@ -364,81 +850,18 @@ mod tests {
     let localizer = ImageLocalizer::new(config);
 
     // 测试数据
-    let url = "https://example.com/image.svg";
     let index = 0;
     let bytes = b"fake image data";
 
-    let result = localizer.generate_filename(url, index, None, bytes);
+    let file_name = localizer.build_filename("svg", index, bytes);
 
     let re = regex::Regex::new(r"\d+-\w{6}.svg").unwrap();
-    match result {
-      Ok(file_name) => assert!(re.is_match(file_name.as_str()), "file_name ({}) is not match", file_name),
-      Err(_) => assert!(false, "generate_filename failed"),
-    }
-  }
-
-  /// 测试文件名生成 - 扩展名来自 URL
-  #[test]
-  fn test_generate_filename_extension_from_url() {
-    let temp_file = NamedTempFile::new().unwrap();
-    let temp_dir = TempDir::new().unwrap();
-
-    let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
-
-    let localizer = ImageLocalizer::new(config);
-    let bytes = b"test";
-
-    // 测试不同的扩展名
-    let test_cases = vec![
-      ("https://example.com/image.png", Some("image/png"), "png"),
-      ("https://example.com/image.gif", Some("image/gif"), "gif"),
-      ("https://example.com/image.svg", Some("image/svg"), "svg"),
-      ("https://example.com/image.webp", Some("image/webp"), "webp"),
-    ];
-
-    for (url, content_type, expected_ext) in test_cases {
-      let filename = localizer.generate_filename(url, 0, content_type, bytes).unwrap();
-      assert!(filename.ends_with(&format!(".{}", expected_ext)));
-    }
-  }
-
-  /// 测试文件名生成 - 扩展名来自 content-type
-  #[test]
-  fn test_generate_filename_extension_from_content_type() {
-    let temp_file = NamedTempFile::new().unwrap();
-    let temp_dir = TempDir::new().unwrap();
-
-    let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
-
-    let localizer = ImageLocalizer::new(config);
-
-    let url = "https://example.com/image";
-    let filename = localizer.generate_filename(url, 0, Some("image/svg"), b"test").unwrap();
-
-    // 应该使用默认扩展名 jpg
-    assert!(filename.ends_with(".svg"));
-  }
-
-  /// 测试文件名生成 - 默认扩展名
-  #[test]
-  fn test_generate_filename_no_extension() {
-    let temp_file = NamedTempFile::new().unwrap();
-    let temp_dir = TempDir::new().unwrap();
-
-    let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
-
-    let localizer = ImageLocalizer::new(config);
-
-    let url = "https://example.com/image";
-    let filename = localizer.generate_filename(url, 0, None, b"test").unwrap();
-
-    // 应该使用默认扩展名 jpg
-    assert!(filename.ends_with(".jpg"));
+    assert!(re.is_match(file_name.as_str()), "file_name ({}) is not match", file_name);
   }
 
   /// 测试文件名生成 - 自定义模式
   #[test]
-  fn test_generate_filename_custom_pattern() {
+  fn test_build_filename_custom_pattern() {
     let temp_file = NamedTempFile::new().unwrap();
     let temp_dir = TempDir::new().unwrap();
 
@@ -447,30 +870,12 @@ mod tests {
 
     let localizer = ImageLocalizer::new(config);
 
-    let filename = localizer.generate_filename("https://example.com/test.png", 5, Some("image/png"), b"data").unwrap();
+    let filename = localizer.build_filename("png", 5, b"data");
 
     assert!(filename.starts_with("img_5_"));
     assert!(filename.ends_with(".png"));
   }
 
-  /// 测试获取文件扩展名
-  #[test]
-  fn test_get_file_extension() {
-    let temp_file = NamedTempFile::new().unwrap();
-    let temp_dir = TempDir::new().unwrap();
-
-    let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
-
-    let localizer = ImageLocalizer::new(config);
-
-    // 测试各种 URL 格式
-    assert_eq!(localizer.get_file_extension("https://example.com/image.JPG", None).unwrap(), "jpg");
-    assert_eq!(localizer.get_file_extension("https://example.com/path/image.PNG", None).unwrap(), "png");
-    assert_eq!(localizer.get_file_extension("https://example.com/image.gif?param=1", None).unwrap(), "gif");
-    assert_eq!(localizer.get_file_extension("https://example.com/noext", None).unwrap(), "jpg");
-    // 默认
-  }
-
   /// 测试获取相对路径
   #[test]
   fn test_get_relative_path() {
@@ -567,7 +972,7 @@ mod tests {
       assert_eq!(image_info.original_url, "https://example.com/image.png");
       assert!(image_info.html_attributes.is_none());
     } else {
-      assert!(false, "期望图片节点");
+      panic!("期望图片节点");
     }
   }
 
@@ -585,6 +990,9 @@ mod tests {
       image_file_name_pattern: "{multilevel_num}-{index}".to_string(),
       image_dir: "{full_dir_of_original_file}/assets/".to_string(),
       new_full_file_path: None,
+      max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+      localize_data_uris: false,
+      copy_local_images: false,
     };
 
     let resolved_dir = config.get_resolved_save_dir();
@@ -593,21 +1001,6 @@ mod tests {
     assert_eq!(resolved_dir, expected_dir);
   }
 
-  /// 测试无效 URL 的处理
-  #[test]
-  fn test_invalid_url_handling() {
-    let temp_file = NamedTempFile::new().unwrap();
-    let temp_dir = TempDir::new().unwrap();
-
-    let config = create_test_config(temp_file.path().to_str().unwrap(), temp_dir.path().to_str().unwrap());
-
-    let localizer = ImageLocalizer::new(config);
-
-    // 测试无效 URL
-    let result = localizer.get_file_extension("not-a-url", None);
-    assert!(result.is_err());
-  }
-
   /// 测试哈希生成的一致性
   #[test]
   fn test_hash_consistency() {
@@ -619,11 +1012,10 @@ mod tests {
     let localizer = ImageLocalizer::new(config);
 
     let data = b"test image data";
-    let url = "https://example.com/image.jpg";
 
     // 多次生成应该产生相同的哈希
-    let filename1 = localizer.generate_filename(url, 0, Some("image/jpg"), data).unwrap();
-    let filename2 = localizer.generate_filename(url, 0, Some("image/jpg"), data).unwrap();
+    let filename1 = localizer.build_filename("jpg", 0, data);
+    let filename2 = localizer.build_filename("jpg", 0, data);
 
     assert_eq!(filename1, filename2);
   }
@@ -638,10 +1030,8 @@ mod tests {
 
     let localizer = ImageLocalizer::new(config);
 
-    let url = "https://example.com/image.jpg";
-
-    let filename1 = localizer.generate_filename(url, 0, Some("image/jpg"), b"data1").unwrap();
-    let filename2 = localizer.generate_filename(url, 0, Some("image/jpg"), b"data2").unwrap();
+    let filename1 = localizer.build_filename("jpg", 0, b"data1");
+    let filename2 = localizer.build_filename("jpg", 0, b"data2");
 
     assert_ne!(filename1, filename2);
   }
@@ -656,12 +1046,204 @@ mod tests {
 
     let localizer = ImageLocalizer::new(config);
 
-    let result = localizer.generate_filename("https://example.com/image.jpg", 0, Some("image/jpg"), b"");
+    let file_name = localizer.build_filename("jpg", 0, b"");
 
     let re = regex::Regex::new(r"\d+-\w{6}.jpg").unwrap();
-    match result {
-      Ok(file_name) => assert!(re.is_match(file_name.as_str()), "file_name ({}) is not match", file_name),
-      Err(_) => assert!(false, "generate_filename failed"),
-    }
+    assert!(re.is_match(file_name.as_str()), "file_name ({}) is not match", file_name);
+  }
+
+  /// 测试文件头识别常见图片格式
+  #[test]
+  fn test_detect_image_format() {
+    assert_eq!(detect_image_format(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]), Some(ImageFormat::Png));
+    assert_eq!(detect_image_format(&[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10]), Some(ImageFormat::Jpeg));
+    assert_eq!(detect_image_format(b"GIF89a\x01\x00"), Some(ImageFormat::Gif));
+    assert_eq!(detect_image_format(b"RIFF\0\0\0\0WEBPVP8 "), Some(ImageFormat::Webp));
+    assert_eq!(detect_image_format(b"\0\0\0\x1cftypavif\0\0\0\0"), Some(ImageFormat::Avif));
+    assert_eq!(
+      detect_image_format(br#"<?xml version="1.0"?><svg xmlns="http://www.w3.org/2000/svg"></svg>"#),
+      Some(ImageFormat::Svg)
+    );
+  }
+
+  /// 测试非图片内容不会被识别为图片
+  #[test]
+  fn test_detect_image_format_rejects_non_images() {
+    assert_eq!(detect_image_format(b""), None);
+    assert_eq!(detect_image_format(b"<!DOCTYPE html><html><body>404</body></html>"), None);
+    assert_eq!(detect_image_format(b"<html><body><svg></svg></body></html>"), None);
+    assert_eq!(detect_image_format(br#"{"error": "not found"}"#), None);
+    assert_eq!(detect_image_format(b"fake image data"), None);
+  }
+
+  /// 测试非图片内容的错误原因
+  #[test]
+  fn test_describe_non_image_payload() {
+    let reason = describe_non_image_payload(Some("text/html"), b"<!DOCTYPE html><html></html>");
+    assert!(reason.contains("HTML 页面"));
+    assert!(reason.contains("text/html"));
+
+    let reason = describe_non_image_payload(Some("application/json"), br#"{"code": 404}"#);
+    assert!(reason.contains("JSON 数据"));
+
+    assert_eq!(describe_non_image_payload(None, b""), "响应内容为空");
+  }
+
+  /// 测试大小格式化
+  #[test]
+  fn test_format_size() {
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(2048), "2.0 KB");
+    assert_eq!(format_size(DEFAULT_MAX_IMAGE_SIZE), "10.0 MB");
+  }
+  /// 测试 data URI 解码
+  #[test]
+  fn test_decode_data_uri() {
+    let (mime, bytes) = decode_data_uri("data:image/gif;base64,R0lGODlh").unwrap();
+    assert_eq!(mime, "image/gif");
+    assert_eq!(bytes, b"GIF89a");
+
+    // 非 base64 数据按百分号编码解码
+    let (mime, bytes) = decode_data_uri("data:image/svg+xml,%3Csvg%3E%3C/svg%3E").unwrap();
+    assert_eq!(mime, "image/svg+xml");
+    assert_eq!(bytes, b"<svg></svg>");
+
+    // 未声明类型时默认为 text/plain
+    let (mime, _) = decode_data_uri("data:,hello").unwrap();
+    assert_eq!(mime, "text/plain");
+
+    assert!(decode_data_uri("data:image/png;base64").is_err());
+    assert!(decode_data_uri("data:image/png;base64,@@@").is_err());
+  }
+
+  /// 测试结果中 data URI 的截断显示
+  #[test]
+  fn test_display_url() {
+    assert_eq!(display_url("https://example.com/a.png"), "https://example.com/a.png");
+
+    let data_uri = format!("data:image/png;base64,{}", "A".repeat(100));
+    let displayed = display_url(&data_uri);
+    assert!(displayed.ends_with('…'));
+    assert!(displayed.len() < data_uri.len());
+  }
+
+  /// 测试 data URI 和文档目录之外的本地图片的本地化
+  #[tokio::test]
+  async fn test_localize_data_uri_and_out_of_tree_images() {
+    let temp_dir = TempDir::new().unwrap();
+    let doc_dir = temp_dir.path().join("docs");
+    let shared_dir = temp_dir.path().join("shared");
+    fs::create_dir_all(&doc_dir).unwrap();
+    fs::create_dir_all(&shared_dir).unwrap();
+
+    let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+    fs::write(shared_dir.join("logo.png"), png).unwrap();
+    fs::write(doc_dir.join("inside.png"), png).unwrap();
+
+    let md_file = doc_dir.join("test.md");
+    let assets_dir = doc_dir.join("assets");
+    let mut config = create_test_config(md_file.to_str().unwrap(), assets_dir.to_str().unwrap());
+    config.localize_data_uris = true;
+    config.copy_local_images = true;
+    let localizer = ImageLocalizer::new(config);
+
+    let mut root = MSTNode::new_root();
+    root.add_child(create_test_image_node("data:image/gif;base64,R0lGODlhAQABAAAAACw=", "内嵌"));
+    root.add_child(create_test_image_node("../shared/logo.png", "共享"));
+    root.add_child(create_test_image_node("inside.png", "目录内"));
+    root.add_child(create_test_html_image_node("data:image/png;base64,iVBORw0KGgoA", "HTML", None));
+
+    let results = localizer.localize_images(&mut root).await.unwrap();
+    assert_eq!(results.len(), 3, "{:?}", results);
+    assert!(results.iter().all(|r| r.starts_with("✅")), "{:?}", results);
+
+    let local_paths: Vec<Option<String>> =
+      root.children.iter().map(|child| child.get_image_info().unwrap().local_path.clone()).collect();
+    assert!(local_paths[0].as_deref().unwrap().ends_with(".gif"));
+    assert!(local_paths[1].as_deref().unwrap().ends_with(".png"));
+    // 文档目录内的图片保持原样
+    assert!(local_paths[2].is_none());
+    assert!(local_paths[3].as_deref().unwrap().starts_with("assets/"));
+
+    assert_eq!(fs::read_dir(&assets_dir).unwrap().count(), 3);
+  }
+
+  /// 测试默认配置下不处理 data URI 和本地图片
+  #[tokio::test]
+  async fn test_data_uri_and_local_images_skipped_by_default() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("docs").join("test.md");
+    let assets_dir = temp_dir.path().join("docs").join("assets");
+
+    let config = create_test_config(md_file.to_str().unwrap(), assets_dir.to_str().unwrap());
+    let localizer = ImageLocalizer::new(config);
+
+    let mut root = MSTNode::new_root();
+    root.add_child(create_test_image_node("data:image/gif;base64,R0lGODlhAQABAAAAACw=", "内嵌"));
+    root.add_child(create_test_image_node("../shared/logo.png", "共享"));
+
+    let results = localizer.localize_images(&mut root).await.unwrap();
+    assert!(results.is_empty());
+    assert!(root.children.iter().all(|child| child.get_image_info().unwrap().local_path.is_none()));
+  }
+
+  /// 测试非图片的 data URI 被拒绝
+  #[test]
+  fn test_save_data_uri_rejects_non_images() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("test.md");
+    let config = create_test_config(md_file.to_str().unwrap(), temp_dir.path().to_str().unwrap());
+    let localizer = ImageLocalizer::new(config);
+
+    let err = localizer.save_data_uri("data:text/plain,hello", 0, temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("不是图片类型"));
+    assert_eq!(err.code(), "invalid_image");
+
+    let err = localizer.save_data_uri("data:image/png;base64,aGVsbG8=", 0, temp_dir.path()).unwrap_err();
+    assert!(err.to_string().contains("不是有效的图片"));
+  }
+
+  /// 测试每处理完一张图片报告一次进度
+  #[tokio::test]
+  async fn test_localize_images_reports_progress() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("test.md");
+    let assets_dir = temp_dir.path().join("assets");
+    let mut config = create_test_config(md_file.to_str().unwrap(), assets_dir.to_str().unwrap());
+    config.localize_data_uris = true;
+
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let localizer = ImageLocalizer::new(config)
+      .with_progress(Box::new(move |progress| recorded.lock().unwrap().push((progress.processed, progress.total))));
+
+    let mut root = MSTNode::new_root();
+    root.add_child(create_test_image_node("data:image/gif;base64,R0lGODlhAQABAAAAACw=", "内嵌"));
+    root.add_child(create_test_image_node("assets/local.png", "本地"));
+    root.add_child(create_test_html_image_node("data:image/png;base64,iVBORw0KGgoA", "HTML", None));
+
+    localizer.localize_images(&mut root).await.unwrap();
+    assert_eq!(*events.lock().unwrap(), vec![(1, 2), (2, 2)]);
+  }
+
+  /// 测试取消后不再处理图片并返回错误
+  #[tokio::test]
+  async fn test_localize_images_cancelled() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("test.md");
+    let assets_dir = temp_dir.path().join("assets");
+    let mut config = create_test_config(md_file.to_str().unwrap(), assets_dir.to_str().unwrap());
+    config.localize_data_uris = true;
+
+    let cancellation = CancellationToken::new();
+    cancellation.cancel();
+    let localizer = ImageLocalizer::new(config).with_cancellation(cancellation);
+
+    let mut root = MSTNode::new_root();
+    root.add_child(create_test_image_node("data:image/gif;base64,R0lGODlhAQABAAAAACw=", "内嵌"));
+
+    assert!(matches!(localizer.localize_images(&mut root).await.unwrap_err(), MarkdownError::Cancelled));
+    assert!(root.children[0].get_image_info().unwrap().local_path.is_none());
+    assert_eq!(fs::read_dir(&assets_dir).unwrap().count(), 0);
   }
 }
@@ -0,0 +1,299 @@
+//! 图片发布模块
+//!
+//! 图片本地化的逆操作：将文档引用的本地图片内嵌为 base64 data URI，
+//! 或按路径映射改写为远程 URL，便于发布单个自包含的文档
+
+use crate::config::{PublishImagesConfig, PublishImagesMode};
+use crate::error::{MarkdownError, Result};
+use crate::image_localizer::{
+  collect_image_reference_labels, collect_link_definitions, detect_image_format, display_url, markdown_dir,
+  resolve_local_image_path,
+};
+use crate::mst::{ImageSource, ImageType, LinkDefinition, MSTNode, NodeType};
+use crate::parser::MarkdownParser;
+use crate::renderer::MarkdownRenderer;
+use crate::sandbox::Sandbox;
+use crate::tr;
+use base64::Engine;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+/// 图片发布器
+pub struct ImagePublisher {
+  config: PublishImagesConfig,
+  parser: MarkdownParser,
+  renderer: MarkdownRenderer,
+  sandbox: Sandbox,
+}
+
+impl ImagePublisher {
+  /// 创建新的图片发布器
+  pub fn new(config: PublishImagesConfig) -> Self {
+    Self { config, parser: MarkdownParser::default(), renderer: MarkdownRenderer::new(), sandbox: Sandbox::default() }
+  }
+
+  /// 设置沙箱，内嵌图片时只允许读取根目录内的文件
+  pub fn with_sandbox(mut self, sandbox: Sandbox) -> Self {
+    self.sandbox = sandbox;
+    self
+  }
+
+  /// 发布 MST 中引用的所有本地图片
+  pub fn publish_images(&self, mst: &mut MSTNode) -> Result<Vec<String>> {
+    let md_dir = markdown_dir(&self.config.full_file_path)?.to_path_buf();
+
+    let definitions = collect_link_definitions(mst);
+    let referenced_labels = collect_image_reference_labels(&self.parser, mst, &definitions);
+
+    let mut results = Vec::new();
+    self.publish_recursive(mst, &md_dir, &referenced_labels, &mut results);
+    Ok(results)
+  }
+
+  /// 递归处理图片节点、链接引用定义和包含图片的内容节点
+  fn publish_recursive(
+    &self,
+    node: &mut MSTNode,
+    md_dir: &Path,
+    referenced_labels: &HashSet<String>,
+    results: &mut Vec<String>,
+  ) {
+    // 本地路径取自 local_path（已本地化时）或 original_url，发布后的地址写回 original_url
+    if let Some(image_info) = node.get_image_info_mut().filter(|info| info.image_type != ImageType::Reference) {
+      let local_url = image_info.current_url().to_string();
+      if let Some(published) = self.report(&local_url, self.publish_url(&local_url, md_dir), None, results) {
+        image_info.original_url = published;
+        image_info.local_path = None;
+      }
+    }
+
+    // 只改写被图片引用的链接引用定义
+    let definition = node
+      .get_link_definition_mut()
+      .filter(|definition| referenced_labels.contains(&LinkDefinition::normalize_label(&definition.label)));
+    if let Some(definition) = definition {
+      let local_url = definition.current_url().to_string();
+      let outcome = self.publish_url(&local_url, md_dir);
+      if let Some(published) = self.report(&local_url, outcome, Some(&definition.label), results) {
+        definition.url = published;
+        definition.local_path = None;
+      }
+    }
+
+    if let NodeType::Content(content) = &mut node.node_type {
+      *content = self.publish_inline_images_in_content(content, md_dir, results);
+    }
+
+    for child in &mut node.children {
+      self.publish_recursive(child, md_dir, referenced_labels, results);
+    }
+  }
+
+  /// 处理内容中的行内图片，复用解析器的图片解析和渲染器的图片渲染逻辑
+  fn publish_inline_images_in_content(&self, content: &str, md_dir: &Path, results: &mut Vec<String>) -> String {
+    let mut updated_content = content.to_string();
+
+    for mut image_node in self.parser.parse_images_in_line(content, 0) {
+      let original_text = image_node.raw.clone();
+      let Some(image_info) = image_node.get_image_info_mut() else {
+        continue;
+      };
+
+      let local_url = image_info.current_url().to_string();
+      if let Some(published) = self.report(&local_url, self.publish_url(&local_url, md_dir), None, results) {
+        image_info.original_url = published;
+        image_info.local_path = None;
+        updated_content = updated_content.replacen(&original_text, &self.renderer.render_image(&image_node), 1);
+      }
+    }
+
+    updated_content
+  }
+
+  /// 记录处理结果，成功时返回发布后的地址
+  fn report(
+    &self,
+    local_url: &str,
+    outcome: Option<Result<String>>,
+    label: Option<&str>,
+    results: &mut Vec<String>,
+  ) -> Option<String> {
+    let (success, failure) = match self.config.mode {
+      PublishImagesMode::Inline => (tr!("已内嵌"), tr!("内嵌失败")),
+      PublishImagesMode::BaseUrl => (tr!("已改写"), tr!("改写失败")),
+    };
+    let reference = label.map(|label| tr!("（引用 [{}]）", label)).unwrap_or_default();
+
+    match outcome? {
+      Ok(published) => {
+        results.push(tr!("✅ {}: {} -> {}{}", success, local_url, display_url(&published), reference));
+        Some(published)
+      }
+      Err(e) => {
+        results.push(tr!("❌ {}: {} - {}{}", failure, local_url, e, reference));
+        None
+      }
+    }
+  }
+
+  /// 发布单个本地图片地址，远程图片和 data URI 返回 None
+  fn publish_url(&self, url: &str, md_dir: &Path) -> Option<Result<String>> {
+    if ImageSource::from_url(url) != ImageSource::Local {
+      return None;
+    }
+
+    match self.config.mode {
+      PublishImagesMode::Inline => match resolve_local_image_path(md_dir, url) {
+        Ok(Some(path)) => Some(self.inline_image(&path)),
+        Ok(None) => None,
+        Err(e) => Some(Err(e)),
+      },
+      PublishImagesMode::BaseUrl => Some(self.map_to_url(url)),
+    }
+  }
+
+  /// 读取本地图片并编码为 base64 data URI
+  fn inline_image(&self, path: &Path) -> Result<String> {
+    let path = &self.sandbox.check_path(&path.to_string_lossy())?;
+    let io_error = |e| MarkdownError::io(path.display().to_string(), e);
+    let size = fs::metadata(path).map_err(io_error)?.len();
+    if size > self.config.max_image_size {
+      return Err(MarkdownError::ImageTooLarge { size: Some(size), limit: self.config.max_image_size });
+    }
+
+    let bytes = fs::read(path).map_err(io_error)?;
+    let format = detect_image_format(&bytes)
+      .ok_or_else(|| MarkdownError::InvalidImage { reason: tr!("无法识别图片格式: {}", path.display()) })?;
+
+    let encoded = base64::engine::general_purpose::STANDARD.encode(&bytes);
+    Ok(format!("data:{};base64,{}", format.mime_type(), encoded))
+  }
+
+  /// 按路径映射将本地路径改写为 URL，最长前缀优先；未匹配时使用 base_url
+  fn map_to_url(&self, url: &str) -> Result<String> {
+    let path = url.trim_start_matches("./");
+
+    for (local_prefix, url_prefix) in &self.config.path_mapping {
+      if let Some(rest) = path.strip_prefix(local_prefix.trim_start_matches("./")) {
+        return Ok(format!("{}{}", url_prefix, rest));
+      }
+    }
+
+    let base_url = self
+      .config
+      .base_url
+      .as_deref()
+      .ok_or_else(|| MarkdownError::ConfigError(tr!("没有匹配的路径映射").to_string()))?;
+    if path.starts_with('/') || path.starts_with("../") {
+      return Err(MarkdownError::ConfigError(tr!("文档目录之外的图片只能通过 path_mapping 改写").to_string()));
+    }
+    Ok(format!("{}/{}", base_url.trim_end_matches('/'), path))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::DEFAULT_MAX_IMAGE_SIZE;
+  use crate::parser::MarkdownParser;
+  use tempfile::TempDir;
+
+  const PNG: [u8; 9] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+
+  /// 创建测试用的 PublishImagesConfig
+  fn create_test_config(file_path: &Path, mode: PublishImagesMode) -> PublishImagesConfig {
+    PublishImagesConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      mode,
+      base_url: None,
+      path_mapping: Vec::new(),
+      new_full_file_path: None,
+      max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+    }
+  }
+
+  /// 测试内嵌本地图片为 data URI
+  #[test]
+  fn test_inline_local_images() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("assets")).unwrap();
+    fs::write(temp_dir.path().join("assets/a.png"), PNG).unwrap();
+    let md_file = temp_dir.path().join("doc.md");
+
+    let content = "# 标题\n\n![本地](assets/a.png \"标题\")\n\n行内 <img src=\"./assets/a.png\" alt=\"a\">\n\n![远程](https://example.com/b.png)\n\n![缺失](assets/missing.png)";
+    let mut mst = MarkdownParser::new().unwrap().parse(content).unwrap();
+
+    let publisher = ImagePublisher::new(create_test_config(&md_file, PublishImagesMode::Inline));
+    let results = publisher.publish_images(&mut mst).unwrap();
+
+    assert_eq!(results.iter().filter(|r| r.starts_with("✅")).count(), 2, "{:?}", results);
+    assert_eq!(results.iter().filter(|r| r.starts_with("❌")).count(), 1, "{:?}", results);
+
+    let rendered = MarkdownRenderer::new().render(&mst);
+    assert!(rendered.contains("![本地](data:image/png;base64,iVBORw0KGgoA \"标题\")"), "{}", rendered);
+    assert!(rendered.contains("src=\"data:image/png;base64,"), "{}", rendered);
+    assert!(rendered.contains("![远程](https://example.com/b.png)"));
+    assert!(rendered.contains("![缺失](assets/missing.png)"));
+  }
+
+  /// 测试沙箱之外的本地图片不会被内嵌
+  #[test]
+  fn test_inline_image_outside_sandbox() {
+    let doc_dir = TempDir::new().unwrap();
+    let shared_dir = TempDir::new().unwrap();
+    fs::write(doc_dir.path().join("a.png"), PNG).unwrap();
+    fs::write(shared_dir.path().join("b.png"), PNG).unwrap();
+
+    let content = format!("![内部](a.png)\n\n![外部]({}/b.png)", shared_dir.path().display());
+    let mut mst = MarkdownParser::new().unwrap().parse(&content).unwrap();
+
+    let sandbox = Sandbox::new(&[doc_dir.path().canonicalize().unwrap()], None);
+    let publisher = ImagePublisher::new(create_test_config(&doc_dir.path().join("doc.md"), PublishImagesMode::Inline))
+      .with_sandbox(sandbox);
+    let results = publisher.publish_images(&mut mst).unwrap();
+
+    assert!(results[0].starts_with("✅"), "{:?}", results);
+    assert!(results[1].starts_with("❌") && results[1].contains("不在允许访问的根目录内"), "{:?}", results);
+  }
+
+  /// 测试按路径映射改写为 URL
+  #[test]
+  fn test_map_to_url() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = create_test_config(&temp_dir.path().join("doc.md"), PublishImagesMode::BaseUrl);
+    config.path_mapping = vec![
+      ("assets/icons/".to_string(), "https://icons.example.com/".to_string()),
+      ("assets/".to_string(), "https://cdn.example.com/docs/".to_string()),
+    ];
+    let publisher = ImagePublisher::new(config.clone());
+
+    assert_eq!(publisher.map_to_url("./assets/icons/x.svg").unwrap(), "https://icons.example.com/x.svg");
+    assert_eq!(publisher.map_to_url("assets/a.png").unwrap(), "https://cdn.example.com/docs/a.png");
+    assert!(publisher.map_to_url("images/b.png").is_err());
+
+    config.base_url = Some("https://static.example.com/".to_string());
+    let publisher = ImagePublisher::new(config);
+    assert_eq!(publisher.map_to_url("images/b.png").unwrap(), "https://static.example.com/images/b.png");
+    assert!(publisher.map_to_url("../shared/c.png").is_err());
+  }
+
+  /// 测试引用式图片通过链接引用定义改写
+  #[test]
+  fn test_publish_reference_definitions() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = create_test_config(&temp_dir.path().join("doc.md"), PublishImagesMode::BaseUrl);
+    config.base_url = Some("https://cdn.example.com".to_string());
+
+    let content = "![Logo][logo]\n\n[logo]: assets/logo.png \"Logo\"\n[site]: docs/index.html";
+    let mut mst = MarkdownParser::new().unwrap().parse(content).unwrap();
+
+    let results = ImagePublisher::new(config).publish_images(&mut mst).unwrap();
+    assert_eq!(results.len(), 1, "{:?}", results);
+
+    let rendered = MarkdownRenderer::new().render(&mst);
+    assert!(rendered.contains("[logo]: https://cdn.example.com/assets/logo.png \"Logo\""), "{}", rendered);
+    // 未被图片引用的定义保持不变
+    assert!(rendered.contains("[site]: docs/index.html"));
+  }
+}
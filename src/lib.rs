@@ -2,17 +2,33 @@
 //!
 //! 提供 Markdown 文档处理功能，包括：
 //! - 章节编号生成和移除
-//! - 标题格式验证
-//! - 图片本地化
+//! - 标题格式验证，带字数和阅读时间统计的文档大纲
+//! - 失效的内部链接和锚点检查
+//! - 标题级别整体调整、章节读取、替换、移动、删除与提取、文档拆分与合并等章节操作
+//! - 图片本地化及其逆操作（内嵌或发布本地图片）
+//! - 未被引用的资源文件检测和清理
+//! - stdio 与 Streamable HTTP 两种 MCP 传输方式
 //! - MST (Markdown Structured Tree) 解析和渲染
 
+pub mod asset_cleaner;
 pub mod config;
 pub mod error;
+pub mod http_server;
+pub mod i18n;
 pub mod image_localizer;
+pub mod image_publisher;
+pub mod links;
 pub mod mst;
 pub mod numbering;
+pub mod outline;
 pub mod parser;
+pub mod project_config;
+pub mod prompts;
 pub mod renderer;
+pub mod resources;
+pub mod sandbox;
+pub mod sections;
+pub mod statistics;
 pub mod tools;
 pub mod utils;
 
@@ -0,0 +1,703 @@
+//! 链接检查模块
+//!
+//! 收集文档中的行内链接和引用式链接，检查指向文档内标题的锚点链接和指向本地文件的相对链接是否有效。
+//! 失效的链接按编辑距离给出最接近的锚点、文件名或引用标签作为建议；代码块和行内代码中的链接不检查。
+//! http(s) 外部链接由 [`ExternalLinkChecker`] 另行检查
+
+use crate::error::{MarkdownError, Result};
+use crate::image_localizer::{http_client_builder, resolve_local_image_path};
+use crate::outline::{build_outline, heading_anchor};
+use crate::parser::MarkdownParser;
+use crate::statistics::{fence_marker, fenced_lines};
+use crate::tr;
+use crate::utils::{normalize_path, path_to_url};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use reqwest::{header, redirect, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use url::Url;
+
+/// 检查外部链接时最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 文档中的一个链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentLink {
+  /// 链接文本
+  pub text: String,
+  /// 链接地址，引用的标签未定义时为 None
+  pub url: Option<String>,
+  /// 引用式链接的标签，行内链接为 None
+  pub label: Option<String>,
+  /// 所在行号（从 1 开始）
+  pub line_number: usize,
+  /// 链接开头的 [ 所在的列（从 1 开始，按字符计）
+  pub column: usize,
+}
+
+impl fmt::Display for DocumentLink {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match &self.label {
+      Some(label) => write!(f, "[{}][{}]", self.text, label),
+      None => write!(f, "[{}]({})", self.text, self.url.as_deref().unwrap_or_default()),
+    }
+  }
+}
+
+/// 链接失效的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkProblem {
+  /// 引用的链接定义不存在
+  UndefinedReference(String),
+  /// 文档中没有该锚点
+  MissingAnchor(String),
+  /// 链接的本地文件不存在
+  MissingFile(PathBuf),
+  /// 链接的 Markdown 文档中没有该锚点
+  MissingAnchorInFile { path: PathBuf, anchor: String },
+  /// 外部链接返回了错误状态码
+  HttpStatus(u16),
+  /// 外部链接请求超时
+  Timeout,
+  /// 外部链接请求失败，如无法解析域名或连接被拒绝
+  RequestFailed(String),
+  /// 外部链接的重定向次数过多
+  TooManyRedirects,
+}
+
+impl fmt::Display for LinkProblem {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let message = match self {
+      LinkProblem::UndefinedReference(label) => tr!("链接引用定义 [{}] 不存在", label),
+      LinkProblem::MissingAnchor(anchor) => tr!("文档中没有锚点 #{}", anchor),
+      LinkProblem::MissingFile(path) => tr!("文件不存在: {}", path.display()),
+      LinkProblem::MissingAnchorInFile { path, anchor } => tr!("{} 中没有锚点 #{}", path.display(), anchor),
+      LinkProblem::HttpStatus(status) => {
+        let reason = StatusCode::from_u16(*status).ok().and_then(|s| s.canonical_reason()).unwrap_or_default();
+        tr!("HTTP 错误: {} {}", status, reason)
+      }
+      LinkProblem::Timeout => tr!("请求超时").to_string(),
+      LinkProblem::RequestFailed(message) => tr!("请求失败: {}", message),
+      LinkProblem::TooManyRedirects => tr!("重定向超过 {} 次", MAX_REDIRECTS),
+    };
+    write!(f, "{}", message)
+  }
+}
+
+/// 失效的链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrokenLink {
+  pub link: DocumentLink,
+  pub problem: LinkProblem,
+  /// 建议改用的地址或引用标签
+  pub suggestion: Option<String>,
+}
+
+/// 发生了重定向的外部链接
+#[derive(Debug, Clone, PartialEq)]
+pub struct RedirectedLink {
+  pub link: DocumentLink,
+  /// 第一次重定向的状态码，如 301
+  pub status: u16,
+  /// 跟随所有重定向后的最终地址
+  pub location: String,
+}
+
+/// 链接检查的结果
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LinkCheckReport {
+  /// 检查过的链接数量，包括失效的链接
+  pub checked: usize,
+  /// 尚未检查的 http(s) 外部链接，内部链接检查不处理它们
+  pub external: Vec<DocumentLink>,
+  /// 未检查的链接数量：其他协议（如 mailto:）、站点根路径、允许的目录之外的文件和忽略的域名
+  pub skipped: usize,
+  /// 失效的链接，按出现位置排序
+  pub broken: Vec<BrokenLink>,
+  /// 最终可以访问、但发生了重定向的外部链接
+  pub redirected: Vec<RedirectedLink>,
+}
+
+/// 链接地址指向的目标
+#[derive(Debug, Clone, PartialEq)]
+enum LinkTarget {
+  /// 当前文档中的锚点（已解码，不含 #）
+  Anchor(String),
+  /// 本地文件，可带锚点
+  File { path: PathBuf, fragment: Option<String> },
+  /// http(s) 外部链接
+  External,
+  /// 无法检查的地址
+  Unchecked,
+}
+
+/// 按文档顺序收集所有链接，跳过代码块、行内代码和链接引用定义行
+pub fn collect_links(parser: &MarkdownParser, content: &str) -> Vec<DocumentLink> {
+  let definitions = parser.parse_link_definitions(content);
+  let mut links = Vec::new();
+  let mut fence: Option<String> = None;
+
+  for (i, line) in content.lines().enumerate() {
+    match (&fence, fence_marker(line)) {
+      (Some(open), Some(marker))
+        if marker.starts_with(open.as_str()) && line.trim()[marker.len()..].trim().is_empty() =>
+      {
+        fence = None;
+        continue;
+      }
+      (Some(_), _) => continue,
+      (None, Some(marker)) => {
+        fence = Some(marker.to_string());
+        continue;
+      }
+      (None, None) => {}
+    }
+    if parser.parse_link_definition(line).is_some() {
+      continue;
+    }
+
+    let line = mask_code_spans(line);
+    let mut line_links: Vec<DocumentLink> = parser
+      .parse_links_in_line(&line, i + 1)
+      .into_iter()
+      .map(|link| DocumentLink {
+        text: link.text,
+        url: Some(link.url),
+        label: None,
+        line_number: link.line_number,
+        column: link.column,
+      })
+      .collect();
+    line_links.extend(parser.parse_reference_links_in_line(&line, i + 1, &definitions).into_iter().map(|link| {
+      DocumentLink {
+        text: link.text,
+        url: link.url,
+        label: Some(link.label),
+        line_number: link.line_number,
+        column: link.column,
+      }
+    }));
+    line_links.sort_by_key(|link| link.column);
+    links.extend(line_links);
+  }
+
+  links
+}
+
+/// 文档中可以链接到的锚点：标题锚点和 HTML 元素的 id 或 name 属性
+pub fn document_anchors(parser: &MarkdownParser, content: &str) -> Result<Vec<String>> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let mut anchors: Vec<String> =
+    build_outline(&mst, content.lines().count()).into_iter().map(|entry| entry.anchor).collect();
+
+  let html_anchor_regex = Regex::new(r#"<[a-zA-Z][^>]*?\s(?:id|name)\s*=\s*["']([^"']+)["']"#)
+    .map_err(|e| MarkdownError::ParseError(tr!("HTML 锚点正则表达式错误: {}", e)))?;
+  let fenced = fenced_lines(content);
+  for (line, _) in content.lines().zip(&fenced).filter(|(_, fenced)| !**fenced) {
+    anchors.extend(html_anchor_regex.captures_iter(line).map(|captures| captures[1].to_string()));
+  }
+  Ok(anchors)
+}
+
+/// 检查文档内的锚点链接和指向本地文件的相对链接
+///
+/// 相对路径以 `md_dir` 为基准；`allowed` 返回 false 的文件不检查，计入跳过的链接。
+/// 指向 Markdown 文档且带锚点的链接会读取目标文档，检查其中是否有该锚点
+pub fn check_internal_links<F>(
+  parser: &MarkdownParser,
+  content: &str,
+  md_dir: &Path,
+  mut allowed: F,
+) -> Result<LinkCheckReport>
+where
+  F: FnMut(&Path) -> bool,
+{
+  let anchors = document_anchors(parser, content)?;
+  let labels: Vec<String> = parser.parse_link_definitions(content).into_values().map(|d| d.label).collect();
+  // 目标文档的锚点，读取失败时为 None
+  let mut file_anchors: HashMap<PathBuf, Option<Vec<String>>> = HashMap::new();
+  let mut report = LinkCheckReport::default();
+
+  for link in collect_links(parser, content) {
+    let Some(url) = link.url.clone() else {
+      let label = link.label.clone().unwrap_or_default();
+      let suggestion = closest(&label.to_lowercase(), labels.iter().map(|l| (l.to_lowercase(), l.as_str())))
+        .map(|l| format!("[{}]", l));
+      report.checked += 1;
+      report.broken.push(BrokenLink { link, problem: LinkProblem::UndefinedReference(label), suggestion });
+      continue;
+    };
+
+    let (problem, suggestion) = match classify(&url, md_dir) {
+      LinkTarget::External => {
+        report.external.push(link);
+        continue;
+      }
+      LinkTarget::Unchecked => {
+        report.skipped += 1;
+        continue;
+      }
+      LinkTarget::File { path, .. } if !allowed(&path) => {
+        report.skipped += 1;
+        continue;
+      }
+      LinkTarget::Anchor(anchor) => match find_anchor(&anchors, &anchor) {
+        Ok(()) => (None, None),
+        Err(suggestion) => (Some(LinkProblem::MissingAnchor(anchor)), suggestion.map(|a| format!("#{}", a))),
+      },
+      LinkTarget::File { path, .. } if !path.exists() => {
+        let suggestion = closest_file_name(&path).map(|name| replace_file_name(&url, &name));
+        (Some(LinkProblem::MissingFile(path)), suggestion)
+      }
+      LinkTarget::File { path, fragment: Some(anchor) } if is_markdown(&path) => {
+        let target_anchors = file_anchors.entry(path.clone()).or_insert_with(|| {
+          std::fs::read_to_string(&path).ok().and_then(|target| document_anchors(parser, &target).ok())
+        });
+        match target_anchors.as_ref().map(|target_anchors| find_anchor(target_anchors, &anchor)) {
+          Some(Err(suggestion)) => {
+            let base = url.split('#').next().unwrap_or_default();
+            let suggestion = suggestion.map(|a| format!("{}#{}", base, a));
+            (Some(LinkProblem::MissingAnchorInFile { path, anchor }), suggestion)
+          }
+          _ => (None, None),
+        }
+      }
+      LinkTarget::File { .. } => (None, None),
+    };
+
+    report.checked += 1;
+    if let Some(problem) = problem {
+      report.broken.push(BrokenLink { link, problem, suggestion });
+    }
+  }
+
+  Ok(report)
+}
+
+/// 外部链接的检查结果
+#[derive(Debug, Clone, PartialEq)]
+enum UrlStatus {
+  Ok,
+  Redirected { status: u16, location: String },
+  Broken(LinkProblem),
+}
+
+/// http(s) 外部链接检查器
+///
+/// 先发送 HEAD 请求，服务器不支持或返回错误时改用 GET 请求；自行跟随重定向以便报告最终地址。
+/// 相同的地址只请求一次，同时进行的请求数量不超过 `max_concurrency`
+pub struct ExternalLinkChecker {
+  client: reqwest::Client,
+  max_concurrency: usize,
+  ignore_domains: Vec<String>,
+}
+
+impl ExternalLinkChecker {
+  /// 创建检查器，`timeout` 为单个请求的超时时间，`ignore_domains` 中的域名及其子域名不检查
+  pub fn new(timeout: Duration, max_concurrency: usize, ignore_domains: &[String]) -> Result<Self> {
+    let client = http_client_builder(timeout)
+      .redirect(redirect::Policy::none())
+      .build()
+      .map_err(|e| MarkdownError::ConfigError(tr!("创建 HTTP 客户端失败: {}", e)))?;
+    let ignore_domains = ignore_domains
+      .iter()
+      .map(|domain| domain.trim().trim_start_matches("*.").trim_end_matches('.').to_lowercase())
+      .filter(|domain| !domain.is_empty())
+      .collect();
+    Ok(Self { client, max_concurrency: max_concurrency.max(1), ignore_domains })
+  }
+
+  /// 检查报告中尚未检查的外部链接，结果并入报告：失效的链接加入 `broken`，重定向的链接加入 `redirected`
+  pub async fn check(&self, report: &mut LinkCheckReport) {
+    let (ignored, links): (Vec<DocumentLink>, Vec<DocumentLink>) =
+      std::mem::take(&mut report.external).into_iter().partition(|link| self.is_ignored(&request_url(link)));
+    report.skipped += ignored.len();
+
+    let mut urls: Vec<String> = links.iter().map(request_url).collect();
+    urls.sort();
+    urls.dedup();
+    let statuses = self.check_urls(urls).await;
+
+    for link in links {
+      report.checked += 1;
+      match statuses.get(&request_url(&link)) {
+        Some(UrlStatus::Broken(problem)) => {
+          report.broken.push(BrokenLink { link, problem: problem.clone(), suggestion: None })
+        }
+        Some(UrlStatus::Redirected { status, location }) => {
+          report.redirected.push(RedirectedLink { link, status: *status, location: location.clone() })
+        }
+        _ => {}
+      }
+    }
+    report.broken.sort_by_key(|broken| (broken.link.line_number, broken.link.column));
+  }
+
+  /// 地址的域名是否在忽略列表中
+  fn is_ignored(&self, url: &str) -> bool {
+    let Some(host) = Url::parse(url).ok().and_then(|url| url.host_str().map(str::to_lowercase)) else {
+      return false;
+    };
+    self.ignore_domains.iter().any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+  }
+
+  /// 并发检查多个地址
+  async fn check_urls(&self, urls: Vec<String>) -> HashMap<String, UrlStatus> {
+    let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+    let mut tasks = JoinSet::new();
+    for url in urls {
+      let client = self.client.clone();
+      let semaphore = semaphore.clone();
+      tasks.spawn(async move {
+        let _permit = semaphore.acquire_owned().await;
+        let status = check_url(&client, &url).await;
+        (url, status)
+      });
+    }
+
+    let mut statuses = HashMap::new();
+    while let Some(result) = tasks.join_next().await {
+      if let Ok((url, status)) = result {
+        statuses.insert(url, status);
+      }
+    }
+    statuses
+  }
+}
+
+/// 请求的地址：去掉锚点，协议相对地址（//host/path）按 https 处理
+fn request_url(link: &DocumentLink) -> String {
+  let url = link.url.as_deref().unwrap_or_default().trim();
+  let url = url.split('#').next().unwrap_or_default();
+  match url.starts_with("//") {
+    true => format!("https:{}", url),
+    false => url.to_string(),
+  }
+}
+
+/// 检查单个外部地址，跟随最多 [`MAX_REDIRECTS`] 次重定向
+async fn check_url(client: &reqwest::Client, url: &str) -> UrlStatus {
+  let mut current = match Url::parse(url) {
+    Ok(url) => url,
+    Err(e) => return UrlStatus::Broken(LinkProblem::RequestFailed(e.to_string())),
+  };
+  let mut first_redirect = None;
+
+  for _ in 0..=MAX_REDIRECTS {
+    let response = match send(client, &current).await {
+      Ok(response) => response,
+      Err(problem) => return UrlStatus::Broken(problem),
+    };
+    let status = response.status();
+    if status.is_redirection() {
+      let location = response.headers().get(header::LOCATION).and_then(|v| v.to_str().ok());
+      let Some(location) = location.and_then(|location| current.join(location).ok()) else {
+        return UrlStatus::Broken(LinkProblem::HttpStatus(status.as_u16()));
+      };
+      first_redirect.get_or_insert(status.as_u16());
+      current = location;
+      continue;
+    }
+    if !status.is_success() {
+      return UrlStatus::Broken(LinkProblem::HttpStatus(status.as_u16()));
+    }
+    return match first_redirect {
+      Some(status) => UrlStatus::Redirected { status, location: current.to_string() },
+      None => UrlStatus::Ok,
+    };
+  }
+
+  UrlStatus::Broken(LinkProblem::TooManyRedirects)
+}
+
+/// 先发送 HEAD 请求；服务器不支持 HEAD（如返回 405）或请求出错时改用 GET 请求，不读取响应体
+async fn send(client: &reqwest::Client, url: &Url) -> std::result::Result<reqwest::Response, LinkProblem> {
+  match client.head(url.clone()).send().await {
+    Ok(response) if !response.status().is_client_error() && !response.status().is_server_error() => {
+      return Ok(response);
+    }
+    Err(e) if e.is_timeout() => return Err(LinkProblem::Timeout),
+    _ => {}
+  }
+
+  client.get(url.clone()).send().await.map_err(|e| match e.is_timeout() {
+    true => LinkProblem::Timeout,
+    false => LinkProblem::RequestFailed(e.to_string()),
+  })
+}
+
+/// 判断链接地址指向的目标
+fn classify(url: &str, md_dir: &Path) -> LinkTarget {
+  let url = url.trim();
+  if let Some(fragment) = url.strip_prefix('#') {
+    return match fragment.is_empty() {
+      // 单独的 # 指向文档开头
+      true => LinkTarget::Unchecked,
+      false => LinkTarget::Anchor(percent_decode_str(fragment).decode_utf8_lossy().into_owned()),
+    };
+  }
+
+  let lower = url.to_lowercase();
+  if lower.starts_with("http://") || lower.starts_with("https://") || url.starts_with("//") {
+    return LinkTarget::External;
+  }
+  // 站点根路径依赖于文档的发布位置，无法在本地检查
+  if url.is_empty() || url.starts_with('/') {
+    return LinkTarget::Unchecked;
+  }
+
+  match resolve_local_image_path(md_dir, url) {
+    Ok(Some(path)) => {
+      let fragment = url
+        .split_once('#')
+        .map(|(_, fragment)| percent_decode_str(fragment).decode_utf8_lossy().into_owned())
+        .filter(|fragment| !fragment.is_empty());
+      LinkTarget::File { path: normalize_path(&path), fragment }
+    }
+    _ => LinkTarget::Unchecked,
+  }
+}
+
+/// 在锚点列表中查找锚点，找不到时返回最接近的锚点作为建议
+fn find_anchor(anchors: &[String], anchor: &str) -> std::result::Result<(), Option<String>> {
+  let lower = anchor.to_lowercase();
+  if anchors.iter().any(|a| a == anchor || *a == lower) {
+    return Ok(());
+  }
+  // 直接写了标题文本的链接，如 #Hello World
+  let normalized = heading_anchor(anchor);
+  if let Some(found) = anchors.iter().find(|a| **a == normalized) {
+    return Err(Some(found.clone()));
+  }
+  Err(closest(&lower, anchors.iter().map(|a| (a.to_lowercase(), a.as_str()))).map(str::to_string))
+}
+
+/// 在文件所在目录中查找与它的文件名最接近的文件，目录不存在时返回 None
+fn closest_file_name(path: &Path) -> Option<String> {
+  let name = path.file_name()?.to_string_lossy().to_lowercase();
+  let names: Vec<String> = std::fs::read_dir(path.parent()?)
+    .ok()?
+    .filter_map(|entry| entry.ok())
+    .map(|entry| entry.file_name().to_string_lossy().into_owned())
+    .collect();
+  closest(&name, names.iter().map(|n| (n.to_lowercase(), n.as_str()))).map(str::to_string)
+}
+
+/// 将链接地址中的文件名替换为 `name`，保留目录和锚点
+fn replace_file_name(url: &str, name: &str) -> String {
+  let (path, rest) = url.find(['?', '#']).map_or((url, ""), |i| url.split_at(i));
+  let dir = path.rsplit_once('/').map_or("", |(dir, _)| dir);
+  let name = path_to_url(Path::new(name));
+  match dir.is_empty() {
+    true => format!("{}{}", name, rest),
+    false => format!("{}/{}{}", dir, name, rest),
+  }
+}
+
+/// 是否为 Markdown 文档
+fn is_markdown(path: &Path) -> bool {
+  path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| ext == "md" || ext == "markdown")
+}
+
+/// 按编辑距离选出最接近 `target` 的候选项，差异超过目标长度的三分之一（至少 2）时不作为建议
+///
+/// 候选项为（用于比较的文本，返回的值）
+fn closest<'a, I>(target: &str, candidates: I) -> Option<&'a str>
+where
+  I: IntoIterator<Item = (String, &'a str)>,
+{
+  let max_distance = (target.chars().count() / 3).max(2);
+  candidates
+    .into_iter()
+    .map(|(key, value)| (edit_distance(target, &key), value))
+    .filter(|(distance, _)| *distance <= max_distance)
+    .min_by_key(|(distance, _)| *distance)
+    .map(|(_, value)| value)
+}
+
+/// 按字符计算的编辑距离（Levenshtein 距离）
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b: Vec<char> = b.chars().collect();
+  let mut previous: Vec<usize> = (0..=b.len()).collect();
+  for (i, ca) in a.chars().enumerate() {
+    let mut current = vec![i + 1; b.len() + 1];
+    for (j, cb) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(ca != *cb);
+      current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+    }
+    previous = current;
+  }
+  previous[b.len()]
+}
+
+/// 将行内代码替换为等长的空格，保持其他内容的字节位置不变
+fn mask_code_spans(line: &str) -> String {
+  let mut masked = String::with_capacity(line.len());
+  let mut rest = line;
+  while let Some(start) = rest.find('`') {
+    let ticks = rest[start..].len() - rest[start..].trim_start_matches('`').len();
+    let after = &rest[start + ticks..];
+    // 结束标记是长度相同的一串反引号
+    let end = after
+      .match_indices(&"`".repeat(ticks))
+      .map(|(i, _)| i)
+      .find(|&i| !after[i + ticks..].starts_with('`') && (i == 0 || !after[..i].ends_with('`')));
+    masked.push_str(&rest[..start]);
+    match end {
+      Some(end) => {
+        masked.push_str(&" ".repeat(ticks * 2 + end));
+        rest = &after[end + ticks..];
+      }
+      None => {
+        masked.push_str(&rest[start..start + ticks]);
+        rest = after;
+      }
+    }
+  }
+  masked.push_str(rest);
+  masked
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  fn check(dir: &Path, content: &str) -> LinkCheckReport {
+    check_internal_links(&MarkdownParser::new().unwrap(), content, dir, |_| true).unwrap()
+  }
+
+  /// 测试编辑距离和建议的阈值
+  #[test]
+  fn test_edit_distance_and_closest() {
+    assert_eq!(edit_distance("kitten", "sitting"), 3);
+    assert_eq!(edit_distance("安装说明", "安装"), 2);
+    assert_eq!(edit_distance("", "abc"), 3);
+
+    let candidates = ["installation", "usage", "license"];
+    let pairs = || candidates.iter().map(|c| (c.to_string(), *c));
+    assert_eq!(closest("instalation", pairs()), Some("installation"));
+    assert_eq!(closest("usage", pairs()), Some("usage"));
+    assert_eq!(closest("changelog", pairs()), None);
+  }
+
+  /// 测试外部链接的忽略域名和请求地址
+  #[test]
+  fn test_external_ignore_domains_and_request_url() {
+    let checker =
+      ExternalLinkChecker::new(Duration::from_secs(1), 4, &["Example.com".to_string(), "*.internal.".to_string()])
+        .unwrap();
+    assert!(checker.is_ignored("https://example.com/a"));
+    assert!(checker.is_ignored("http://docs.example.com/a"));
+    assert!(checker.is_ignored("https://wiki.internal/page"));
+    assert!(!checker.is_ignored("https://notexample.com/a"));
+    assert!(!checker.is_ignored("https://example.org/a"));
+
+    let link = |url: &str| DocumentLink {
+      text: String::new(),
+      url: Some(url.to_string()),
+      label: None,
+      line_number: 1,
+      column: 1,
+    };
+    assert_eq!(request_url(&link("https://example.com/a#part")), "https://example.com/a");
+    assert_eq!(request_url(&link("//cdn.example.com/x")), "https://cdn.example.com/x");
+  }
+
+  /// 测试行内代码的屏蔽
+  #[test]
+  fn test_mask_code_spans() {
+    assert_eq!(mask_code_spans("a `[x](y)` b"), "a          b");
+    assert_eq!(mask_code_spans("``a ` b`` [c](d)"), "          [c](d)");
+    assert_eq!(mask_code_spans("未闭合 `[x](y)"), "未闭合 `[x](y)");
+    assert_eq!(mask_code_spans("`中文`[x](y)").len(), "`中文`[x](y)".len());
+  }
+
+  /// 测试链接的收集：行内链接、引用式链接，跳过代码块和行内代码
+  #[test]
+  fn test_collect_links() {
+    let content =
+      "# 标题\n\n见 [A](a.md) 和 [B][b]，`[C](c.md)`\n\n```md\n[D](d.md)\n```\n\n[未定义][nope]\n\n[b]: b.md\n";
+    let links = collect_links(&MarkdownParser::new().unwrap(), content);
+
+    let summary: Vec<(usize, usize, Option<&str>)> =
+      links.iter().map(|link| (link.line_number, link.column, link.url.as_deref())).collect();
+    assert_eq!(summary, [(3, 3, Some("a.md")), (3, 15, Some("b.md")), (9, 1, None)]);
+    assert_eq!(links[1].to_string(), "[B][b]");
+    assert_eq!(links[0].to_string(), "[A](a.md)");
+  }
+
+  /// 测试文档内锚点链接的检查和建议
+  #[test]
+  fn test_check_anchor_links() {
+    let dir = TempDir::new().unwrap();
+    let content = "# Installation\n\n<a id=\"custom\"></a>\n\n## 使用 说明\n\n\
+      [ok](#installation) [ok](#%E4%BD%BF%E7%94%A8-%E8%AF%B4%E6%98%8E) [ok](#custom) [top](#)\n\
+      [typo](#instalation) [text](#Installation) [far](#changelog)\n";
+    let report = check(dir.path(), content);
+
+    assert_eq!(report.checked, 6);
+    assert_eq!(report.skipped, 1);
+    let broken: Vec<(usize, &str, Option<&str>)> =
+      report.broken.iter().map(|b| (b.link.column, b.link.text.as_str(), b.suggestion.as_deref())).collect();
+    assert_eq!(broken, [(1, "typo", Some("#installation")), (44, "far", None)]);
+    assert_eq!(report.broken[0].problem, LinkProblem::MissingAnchor("instalation".to_string()));
+  }
+
+  /// 测试代码块内形如标题的行和 HTML 锚点不是可链接的锚点
+  #[test]
+  fn test_document_anchors_skip_fenced_code() {
+    let content = "# 安装\n\n```bash\n# install deps\n<a id=\"sample\"></a>\n```\n\n<a name=\"top\"></a>\n";
+    let anchors = document_anchors(&MarkdownParser::new().unwrap(), content).unwrap();
+    assert_eq!(anchors, ["安装", "top"]);
+
+    let dir = TempDir::new().unwrap();
+    let report = check(dir.path(), &format!("{}\n[deps](#install-deps)\n", content));
+    assert_eq!(report.broken.len(), 1);
+  }
+
+  /// 测试本地文件链接的检查：文件不存在、目标文档中的锚点、外部链接和其他协议
+  #[test]
+  fn test_check_file_links() {
+    let dir = TempDir::new().unwrap();
+    std::fs::create_dir(dir.path().join("docs")).unwrap();
+    std::fs::write(dir.path().join("docs/guide.md"), "# Guide\n\n## 安装\n").unwrap();
+    std::fs::write(dir.path().join("logo.png"), b"png").unwrap();
+
+    let content = "[g](docs/guide.md#安装) [d](docs/) [img](logo.png#x)\n\
+      [miss](docs/gude.md#安装) [anchor](docs/guide.md#gude) [none](nothing/here.md)\n\
+      [web](https://example.com) [mail](mailto:a@b.c) [root](/docs/guide.md) [ref][web]\n\n\
+      [web]: HTTPS://example.com/ref\n";
+    let report = check(dir.path(), content);
+
+    assert_eq!(report.checked, 6);
+    assert_eq!(report.skipped, 2);
+    assert_eq!(report.external.len(), 2);
+    let broken: Vec<(&str, Option<&str>)> =
+      report.broken.iter().map(|b| (b.link.text.as_str(), b.suggestion.as_deref())).collect();
+    assert_eq!(broken, [("miss", Some("docs/guide.md#安装")), ("anchor", Some("docs/guide.md#guide")), ("none", None)]);
+    assert_eq!(report.broken[0].problem, LinkProblem::MissingFile(dir.path().join("docs/gude.md")));
+    assert!(
+      matches!(report.broken[1].problem, LinkProblem::MissingAnchorInFile { ref anchor, .. } if anchor == "gude")
+    );
+  }
+
+  /// 测试未定义的引用标签和不允许访问的文件
+  #[test]
+  fn test_check_undefined_references_and_disallowed_files() {
+    let dir = TempDir::new().unwrap();
+    let content = "[文档][guide-v2] [外部](../outside.md)\n\n[guide-v1]: guide.md\n";
+    let parser = MarkdownParser::new().unwrap();
+    let report = check_internal_links(&parser, content, dir.path(), |path| path.starts_with(dir.path())).unwrap();
+
+    assert_eq!((report.checked, report.skipped), (1, 1));
+    assert_eq!(report.broken[0].problem, LinkProblem::UndefinedReference("guide-v2".to_string()));
+    assert_eq!(report.broken[0].suggestion.as_deref(), Some("[guide-v1]"));
+  }
+}
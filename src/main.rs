@@ -5,27 +5,224 @@ use rmcp::{
   ErrorData as McpError, ServerHandler,
 };
 
+mod asset_cleaner;
 mod config;
 mod error;
+mod http_server;
+mod i18n;
 mod image_localizer;
+mod image_publisher;
+mod links;
 mod mst;
 mod numbering;
+mod outline;
 mod parser;
+mod project_config;
+mod prompts;
 mod renderer;
+mod resources;
+mod sandbox;
+mod sections;
+mod statistics;
 mod tools;
 mod utils;
 
-use config::{CheckHeadingConfig, GenerateChapterConfig, LocalizeImagesConfig, RemoveChapterConfig};
-use tools::MarkdownToolsImpl;
+use config::{
+  CheckHeadingConfig, CheckLinksConfig, CleanAssetsConfig, DeleteSectionConfig, ExtractSectionConfig,
+  GenerateChapterConfig, LocalizeImagesConfig, MergeDocumentsConfig, MoveSectionConfig, OutlineConfig,
+  PublishImagesConfig, ReadSectionConfig, RemoveChapterConfig, ReplaceSectionConfig, ServerConfig,
+  ShiftHeadingLevelsConfig, ShowEffectiveConfigConfig, SplitDocumentConfig, TransportKind, SERVER_USAGE, TOOLS,
+};
+use project_config::apply_project_config;
+use sandbox::Sandbox;
+use std::path::PathBuf;
+use std::sync::Arc;
+use tools::{LocalizeOptions, MarkdownToolsImpl, ProgressNotifier};
 
 #[derive(Clone)]
-pub struct MarkdownTools;
+pub struct MarkdownTools {
+  /// 启动参数 --allow-dir 指定的目录
+  allow_dirs: Arc<Vec<PathBuf>>,
+}
+
+impl MarkdownTools {
+  pub fn new(allow_dirs: Vec<PathBuf>) -> Self {
+    Self { allow_dirs: Arc::new(allow_dirs) }
+  }
+
+  /// 合并 --allow-dir 和客户端声明的 roots，得到本次请求的沙箱
+  ///
+  /// 每次请求都向客户端查询 roots，工作区变化后无需重新连接
+  async fn sandbox(&self, context: &RequestContext<RoleServer>) -> Result<Sandbox, McpError> {
+    let supports_roots = context.peer.peer_info().is_some_and(|info| info.capabilities.roots.is_some());
+    let client_roots = if supports_roots {
+      let result = context
+        .peer
+        .list_roots()
+        .await
+        .map_err(|e| McpError::internal_error(tr!("获取客户端根目录失败: {}", e), None))?;
+      Some(result.roots)
+    } else {
+      None
+    };
+    Ok(Sandbox::new(&self.allow_dirs, client_roots.as_deref()))
+  }
+
+  /// 按名称调用工具
+  ///
+  /// 项目配置文件中的设置作为默认值，调用时传入的参数优先
+  async fn dispatch_tool(
+    &self,
+    name: &str,
+    args: Option<&JsonObject>,
+    context: RequestContext<RoleServer>,
+  ) -> Result<CallToolResult, McpError> {
+    let sandbox = self.sandbox(&context).await?;
+
+    match name {
+      "check_heading" => {
+        let args = apply_project_config::<CheckHeadingConfig>(args, &sandbox)?;
+        let mut config = CheckHeadingConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::check_heading_impl(config).await
+      }
+      "outline" => {
+        let args = apply_project_config::<OutlineConfig>(args, &sandbox)?;
+        let mut config = OutlineConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::outline_impl(config).await
+      }
+      "check_links" => {
+        let args = apply_project_config::<CheckLinksConfig>(args, &sandbox)?;
+        let mut config = CheckLinksConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::check_links_impl(config, &sandbox).await
+      }
+      "generate_chapter_number" => {
+        let args = apply_project_config::<GenerateChapterConfig>(args, &sandbox)?;
+        let mut config = GenerateChapterConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::generate_chapter_number_impl(config, "numed").await
+      }
+      "remove_all_chapter_numbers" => {
+        let args = apply_project_config::<RemoveChapterConfig>(args, &sandbox)?;
+        let mut config = RemoveChapterConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::remove_all_chapter_numbers_impl(config, "unnumed").await
+      }
+      "localize_images" => {
+        let args = apply_project_config::<LocalizeImagesConfig>(args, &sandbox)?;
+        let mut config = LocalizeImagesConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        let notifier = ProgressNotifier::from_context(&context);
+        let progress = notifier.as_ref().map(ProgressNotifier::callback);
+        let options = LocalizeOptions { progress, cancellation: context.ct.clone(), sandbox };
+        let result = MarkdownToolsImpl::localize_images_impl(config, options).await;
+        if let Some(notifier) = notifier {
+          notifier.finish().await;
+        }
+        result
+      }
+      "publish_images" => {
+        let args = apply_project_config::<PublishImagesConfig>(args, &sandbox)?;
+        let mut config = PublishImagesConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::publish_images_impl(config, &sandbox).await
+      }
+      "clean_assets" => {
+        let args = apply_project_config::<CleanAssetsConfig>(args, &sandbox)?;
+        let mut config = CleanAssetsConfig::from_args(args.as_ref())?;
+        for path in &mut config.full_file_paths {
+          sandbox.confine(path)?;
+        }
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::clean_assets_impl(config).await
+      }
+      "shift_heading_levels" => {
+        let args = apply_project_config::<ShiftHeadingLevelsConfig>(args, &sandbox)?;
+        let mut config = ShiftHeadingLevelsConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::shift_heading_levels_impl(config).await
+      }
+      "read_section" => {
+        let args = apply_project_config::<ReadSectionConfig>(args, &sandbox)?;
+        let mut config = ReadSectionConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::read_section_impl(config).await
+      }
+      "replace_section" => {
+        let args = apply_project_config::<ReplaceSectionConfig>(args, &sandbox)?;
+        let mut config = ReplaceSectionConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::replace_section_impl(config).await
+      }
+      "move_section" => {
+        let args = apply_project_config::<MoveSectionConfig>(args, &sandbox)?;
+        let mut config = MoveSectionConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::move_section_impl(config).await
+      }
+      "delete_section" => {
+        let args = apply_project_config::<DeleteSectionConfig>(args, &sandbox)?;
+        let mut config = DeleteSectionConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::delete_section_impl(config).await
+      }
+      "extract_section" => {
+        let args = apply_project_config::<ExtractSectionConfig>(args, &sandbox)?;
+        let mut config = ExtractSectionConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::extract_section_impl(config, &sandbox).await
+      }
+      "split_document" => {
+        let args = apply_project_config::<SplitDocumentConfig>(args, &sandbox)?;
+        let mut config = SplitDocumentConfig::from_args(args.as_ref())?;
+        sandbox.confine(&mut config.full_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::split_document_impl(config, &sandbox).await
+      }
+      "merge_documents" => {
+        let args = apply_project_config::<MergeDocumentsConfig>(args, &sandbox)?;
+        let mut config = MergeDocumentsConfig::from_args(args.as_ref())?;
+        for path in &mut config.full_file_paths {
+          sandbox.confine(path)?;
+        }
+        if let Some(input_dir) = &mut config.input_dir {
+          sandbox.confine(input_dir)?;
+        }
+        sandbox.confine(&mut config.output_file_path)?;
+        sandbox.check_paths(config.accessed_paths())?;
+        MarkdownToolsImpl::merge_documents_impl(config, &sandbox).await
+      }
+      "show_effective_config" => {
+        let mut config = ShowEffectiveConfigConfig::from_args(args)?;
+        sandbox.confine(&mut config.full_file_path)?;
+        MarkdownToolsImpl::show_effective_config_impl(config, &sandbox).await
+      }
+      _ => Err(McpError::method_not_found::<CallToolRequestMethod>()),
+    }
+  }
+}
 
 impl ServerHandler for MarkdownTools {
   fn get_info(&self) -> ServerInfo {
     ServerInfo {
       server_info: Implementation { name: "mcp-markdown-tools".to_string(), version: "0.1.0".to_string() },
-      instructions: Some("一个 markdown 文档工具集".into()),
+      instructions: Some(tr!("一个 markdown 文档工具集").into()),
+      capabilities: ServerCapabilities::builder().enable_tools().enable_resources().enable_prompts().build(),
       ..Default::default()
     }
   }
@@ -35,194 +232,8 @@ impl ServerHandler for MarkdownTools {
     _request: Option<PaginatedRequestParam>,
     _context: RequestContext<RoleServer>,
   ) -> Result<ListToolsResult, McpError> {
-    let tools = vec![
-            Tool::new(
-                "check_heading",
-                r#"验证 Markdown 文档标题行的格式规范性和层级结构的正确性。
-
-验证规则：
-1. 标题格式规范：
-   - 标题行必须以1-6个#符号开头（称为标题符/Heading token）
-   - #符号前不能有空格或其他字符
-   - #符号后必须有且仅有一个空格，然后是标题内容
-   - #符号的数量决定标题级别（1-6级）
-
-2. 层级结构规范：
-   - 允许文档开头有非标题内容（如前言、说明等）
-   - 文档中第一个标题的级别决定了文档的起始级别
-   - 标题级别必须连续，不允许跳级：
-     * 允许：同级标题（H2→H2）、下一级标题（H2→H3）、回到任意上级标题（H3→H2、H3→H1）
-     * 不允许：跳级（H1→H3、H2→H4等，即跳过中间级别）
-
-3. 验证范围：
-   - 只验证标题行（以#开头的行），忽略其他内容行
-   - 支持文档开头有非标题内容
-
-返回结果：
-- 验证通过：返回成功信息和标题结构统计
-- 验证失败：返回详细的错误报告，包括：
-  * 错误类型（格式错误/层级跳级）
-  * 具体错误行号和内容"#,
-                std::sync::Arc::new(
-                    serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "full_file_path": {
-                                "type": "string",
-                                "description": "Markdown 文档的文件路径，必须使用绝对路径"
-                            }
-                        },
-                        "required": ["full_file_path"]
-                    })
-                    .as_object()
-                    .unwrap()
-                    .clone(),
-                ),
-            ),
-            Tool::new(
-                "generate_chapter_number",
-                r#"为 Markdown 文档所有的标题行(Head line)创建编号。
-在创建之前，会将全文档的标题行检查一遍，清理掉已有的编号。
-为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
-                std::sync::Arc::new(
-                    serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "full_file_path": {
-                                "type": "string",
-                                "description": "Markdown 文档的文件路径，必须使用绝对路径"
-                            },
-                            "ignore_h1": {
-                                "type": "boolean",
-                                "description": "是否忽略一级标题（# 标题）",
-                                "default": false
-                            },
-                            "use_chinese_number": {
-                                "type": "boolean",
-                                "description": "是否使用中文编号（一、二、三...）",
-                                "default": false
-                            },
-                            "use_arabic_number_for_sublevel": {
-                                "type": "boolean",
-                                "description": r#"一级以下编号是否使用独立的阿拉伯数字编号。use_chinese_number=true 时生效。
-为 true 时，只有一级编号使用中文，每个一级编号下的二级编号都是用从1开始的一级阿拉伯数字编号，三级编号都是用从1.1开始的二级阿拉伯数字编号，以此类推。
-
-示例一（ignore_h1=false, use_chinese_number=true, use_arabic_number_for_sublevel=true）：
-# 一、
-## 一、一、
-### 一、一、一、
-### 一、一、二、
-## 一、二、
-# 二、
-## 二、一、
-## 二、二、
-# 三、
-## 三、一、
-
-示例二（ignore_h1=true, use_chinese_number=true, use_arabic_number_for_sublevel=false）：
-## 一、
-### 1.
-#### 1.1.
-#### 1.2.
-### 2.
-## 二、
-### 1.
-### 2.
-## 三、
-### 1.
-"#,
-                                "default": true
-                            },
-                            "save_as_new_file": {
-                                "type": "boolean",
-                                "description": "编辑后，是否另存为新文件，为false时将覆盖原文件。",
-                                "default": false
-                            },
-                            "new_full_file_path": {
-                                "type": "string",
-                                "description": r#"新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
-默认与原文档同目录，默认文件名为：{original_file_name}_numed.md。"#,
-                                "default": "{full_dir_of_original_file}/{original_file_name}_numed.md"
-                            }
-                        },
-                        "required": ["full_file_path"]
-                    })
-                    .as_object()
-                    .unwrap()
-                    .clone(),
-                ),
-            ),
-            Tool::new(
-                "remove_all_chapter_numbers",
-                r#"清除 Markdown 文档所有标题行(Head line)的编号，包括数字和中文编号。
-为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
-                std::sync::Arc::new(
-                    serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "full_file_path": {
-                                "type": "string",
-                                "description": "Markdown 文档的文件路径，必须使用绝对路径"
-                            },
-                            "save_as_new_file": {
-                                "type": "boolean",
-                                "description": "编辑后，是否另存为新文件，文件名为：{原文件名}_unnumed.md。为false时将覆盖原文件。",
-                                "default": false
-                            },
-                            "new_full_file_path": {
-                                "type": "string",
-                                "description": r#"新文件名，必须使用绝对路径。save_as_new_file=true 时生效。
-默认与原文档同目录，默认文件名为：{original_file_name}_unnumed.md。"#,
-                                "default": "{full_dir_of_original_file}/{original_file_name}_unnumed.md"
-                            }
-                        },
-                        "required": ["full_file_path"]
-                    })
-                    .as_object()
-                    .unwrap()
-                    .clone(),
-                ),
-            ),
-            Tool::new(
-                "localize_images",
-                r#"将整个 Markdown 文档中引用的远程图片资源保存到本地，并且更改文档中的引用。
-为了提高处理速度，你应该直接对整个文件执行该工具，而不是对原文件分段读取处理。"#,
-                std::sync::Arc::new(
-                    serde_json::json!({
-                        "type": "object",
-                        "properties": {
-                            "full_file_path": {
-                                "type": "string",
-                                "description": "Markdown 文档的文件路径，必须使用绝对路径"
-                            },
-                            "image_file_name_pattern": {
-                                "type": "string",
-                                "description": "保存到本地的图片文件名格式，不包含扩展名。
-支持的通配符有：
-- multilevel_num: 图片所在的多层级编号，例如：1.2.1.
-- index: 序号，从零开始
-- hash: 6位哈希字符",
-                                "default": "{multilevel_num}-{index}"
-                            },
-                            "image_dir": {
-                                "type": "string",
-                                "description": "图片保存的目录，默认为原文档同目录下的 assets 目录",
-                                "default": "{full_dir_of_original_file}/assets/"
-                            },
-                            "new_full_file_path": {
-                                "type": "string",
-                                "description": r#"新文件名，必须使用绝对路径。为空则覆盖原文件。"#,
-                                "default": ""
-                            }
-                        },
-                        "required": ["full_file_path"]
-                    })
-                    .as_object()
-                    .unwrap()
-                    .clone(),
-                ),
-            ),
-        ];
+    let tools =
+      TOOLS.iter().map(|tool| Tool::new(tool.name, i18n::tool_description(tool.name), tool.input_schema())).collect();
 
     Ok(ListToolsResult { next_cursor: None, tools })
   }
@@ -230,42 +241,99 @@ impl ServerHandler for MarkdownTools {
   async fn call_tool(
     &self,
     request: CallToolRequestParam,
-    _context: RequestContext<RoleServer>,
+    context: RequestContext<RoleServer>,
   ) -> Result<CallToolResult, McpError> {
-    match request.name.as_ref() {
-      "check_heading" => {
-        let config = CheckHeadingConfig::from_args(request.arguments.as_ref())?;
-        MarkdownToolsImpl::check_heading_impl(config).await
-      }
-      "generate_chapter_number" => {
-        let config = GenerateChapterConfig::from_args(request.arguments.as_ref())?;
-        MarkdownToolsImpl::generate_chapter_number_impl(config, "numed").await
-      }
-      "remove_all_chapter_numbers" => {
-        let config = RemoveChapterConfig::from_args(request.arguments.as_ref())?;
-        MarkdownToolsImpl::remove_all_chapter_numbers_impl(config, "unnumed").await
-      }
-      "localize_images" => {
-        let config = LocalizeImagesConfig::from_args(request.arguments.as_ref())?;
-        MarkdownToolsImpl::localize_images_impl(config).await
-      }
-      _ => Err(McpError::method_not_found::<CallToolRequestMethod>()),
-    }
+    // lang 参数只决定本次调用返回信息的语言，不传给具体工具
+    let (lang, args) =
+      i18n::take_lang_arg(request.arguments.as_ref()).map_err(|message| McpError::invalid_params(message, None))?;
+    let lang = lang.unwrap_or_else(i18n::current_lang);
+    i18n::with_lang(lang, self.dispatch_tool(request.name.as_ref(), args.as_ref(), context)).await
+  }
+
+  async fn list_prompts(
+    &self,
+    _request: Option<PaginatedRequestParam>,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<ListPromptsResult, McpError> {
+    Ok(ListPromptsResult { next_cursor: None, prompts: prompts::prompt_list() })
+  }
+
+  async fn get_prompt(
+    &self,
+    request: GetPromptRequestParam,
+    context: RequestContext<RoleServer>,
+  ) -> Result<GetPromptResult, McpError> {
+    let sandbox = self.sandbox(&context).await?;
+    prompts::get_markdown_prompt(&request.name, request.arguments.as_ref(), &sandbox)
+  }
+
+  async fn list_resource_templates(
+    &self,
+    _request: Option<PaginatedRequestParam>,
+    _context: RequestContext<RoleServer>,
+  ) -> Result<ListResourceTemplatesResult, McpError> {
+    Ok(ListResourceTemplatesResult { next_cursor: None, resource_templates: resources::resource_templates() })
+  }
+
+  async fn read_resource(
+    &self,
+    request: ReadResourceRequestParam,
+    context: RequestContext<RoleServer>,
+  ) -> Result<ReadResourceResult, McpError> {
+    let sandbox = self.sandbox(&context).await?;
+    resources::read_markdown_resource(&request.uri, &sandbox)
   }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-  let server = MarkdownTools;
-  let transport = stdio();
+  // 解析命令行之前先按环境变量设置语言，帮助和参数错误信息也能使用对应的语言
+  i18n::set_default_lang(config::lang_from_env().unwrap_or_default());
+  let args: Vec<String> = std::env::args().skip(1).collect();
+  if args.iter().any(|arg| arg == "-h" || arg == "--help") {
+    println!("{}", i18n::translate(SERVER_USAGE));
+    return Ok(());
+  }
+  let server_config = match ServerConfig::from_cli_args(args) {
+    Ok(server_config) => server_config,
+    Err(e) => {
+      eprintln!("{}", e);
+      std::process::exit(2);
+    }
+  };
+  i18n::set_default_lang(server_config.lang);
+
+  if !server_config.allow_dirs.is_empty() {
+    let dirs: Vec<String> = server_config.allow_dirs.iter().map(|dir| dir.display().to_string()).collect();
+    eprintln!("Allowed directories: {}", dirs.join(", "));
+  }
+  let server = MarkdownTools::new(server_config.allow_dirs.clone());
 
   eprintln!("Markdown tools MCP Server starting...");
 
-  // 使用ServiceExt trait的serve方法
-  let running_service = server.serve(transport).await?;
+  match server_config.transport {
+    TransportKind::Stdio => {
+      // 使用ServiceExt trait的serve方法
+      let running_service = server.serve(stdio()).await?;
 
-  eprintln!("Server started successfully");
-  running_service.waiting().await?;
+      eprintln!("Server started successfully");
+      running_service.waiting().await?;
+    }
+    TransportKind::Http => {
+      let listener = tokio::net::TcpListener::bind(server_config.socket_addr()?).await?;
+      eprintln!(
+        "Server listening on http://{}{}{}",
+        listener.local_addr()?,
+        http_server::MCP_ENDPOINT,
+        if server_config.auth_token.is_some() { " (bearer token required)" } else { "" }
+      );
+
+      tokio::select! {
+        result = http_server::serve_http(listener, server, server_config.auth_token) => result?,
+        _ = tokio::signal::ctrl_c() => eprintln!("Server shutting down"),
+      }
+    }
+  }
 
   Ok(())
 }
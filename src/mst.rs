@@ -4,6 +4,7 @@
 //! 将解析、处理和渲染逻辑分离，提高代码的可维护性和扩展性。
 
 use std::fmt;
+use std::ops::Range;
 
 /// Markdown 文档节点类型
 #[derive(Debug, Clone, PartialEq)]
@@ -16,6 +17,8 @@ pub enum NodeType {
   Content(String),
   /// 图片节点
   Image(ImageInfo),
+  /// 链接引用定义节点：[label]: url "title"
+  LinkDefinition(LinkDefinition),
 }
 
 /// 图片类型
@@ -25,6 +28,67 @@ pub enum ImageType {
   Markdown,
   /// HTML img 标签格式：<img src="url" ...>
   Html,
+  /// 引用式图片：![alt][label]、![label][] 或 ![label]，URL 来自链接引用定义
+  Reference,
+}
+
+/// 图片资源的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSource {
+  /// 远程图片：http:// 或 https://
+  Remote,
+  /// 内嵌图片：data:image/png;base64,...
+  DataUri,
+  /// 本地图片：相对或绝对路径
+  Local,
+}
+
+impl ImageSource {
+  /// 根据图片地址判断来源
+  pub fn from_url(url: &str) -> Self {
+    if url.starts_with("http://") || url.starts_with("https://") {
+      Self::Remote
+    } else if url.starts_with("data:") {
+      Self::DataUri
+    } else {
+      Self::Local
+    }
+  }
+}
+
+/// 标题（title）使用的定界符
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TitleDelimiter {
+  /// "title"
+  #[default]
+  DoubleQuote,
+  /// 'title'
+  SingleQuote,
+  /// (title)
+  Parentheses,
+}
+
+impl TitleDelimiter {
+  /// 从带定界符的原始标题中解析出定界符和标题文本
+  pub fn split(raw: &str) -> Option<(Self, String)> {
+    let delimiter = match raw.chars().next()? {
+      '"' => Self::DoubleQuote,
+      '\'' => Self::SingleQuote,
+      '(' => Self::Parentheses,
+      _ => return None,
+    };
+    let inner = raw.get(1..raw.len().checked_sub(1)?)?;
+    Some((delimiter, inner.to_string()))
+  }
+
+  /// 用定界符包裹标题文本
+  pub fn wrap(&self, title: &str) -> String {
+    match self {
+      Self::DoubleQuote => format!("\"{}\"", title),
+      Self::SingleQuote => format!("'{}'", title),
+      Self::Parentheses => format!("({})", title),
+    }
+  }
 }
 
 /// 图片信息
@@ -42,6 +106,85 @@ pub struct ImageInfo {
   pub title: Option<String>,
   /// HTML 属性（仅对 HTML 类型有效）
   pub html_attributes: Option<String>,
+  /// 标题的定界符
+  pub title_delimiter: TitleDelimiter,
+  /// 引用标签（仅对 Reference 类型有效）
+  pub reference_label: Option<String>,
+  /// 地址在节点原始文本中的字节范围，渲染时原位替换地址；手工构造的节点为 None，按各字段重新生成
+  pub url_range: Option<Range<usize>>,
+}
+
+/// 链接引用定义：[label]: url "title"
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkDefinition {
+  /// 原始标签文本（保留大小写）
+  pub label: String,
+  /// 定义中的原始 URL
+  pub url: String,
+  /// 本地化后的路径（如果已本地化）
+  pub local_path: Option<String>,
+  /// 标题（可选）
+  pub title: Option<String>,
+  /// 标题的定界符
+  pub title_delimiter: TitleDelimiter,
+  /// 定义所在行的原文
+  pub raw: String,
+  /// 地址在原文中的字节范围，渲染时原位替换地址；手工构造的定义为 None，按各字段重新生成
+  pub url_range: Option<Range<usize>>,
+}
+
+/// 行内链接：[text](url "title")
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineLink {
+  /// 链接文本
+  pub text: String,
+  /// 链接地址（不含尖括号）
+  pub url: String,
+  /// 链接地址在行内的字节范围，用于原位改写地址
+  pub url_range: Range<usize>,
+  /// 所在行号（从 1 开始）
+  pub line_number: usize,
+  /// 链接开头的 [ 所在的列（从 1 开始，按字符计）
+  pub column: usize,
+}
+
+/// 引用式链接：[text][label]、[label][] 或 [label]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceLink {
+  /// 链接文本
+  pub text: String,
+  /// 引用的标签
+  pub label: String,
+  /// 由链接引用定义解析出的地址，标签未定义时为 None
+  pub url: Option<String>,
+  /// 所在行号（从 1 开始）
+  pub line_number: usize,
+  /// 链接开头的 [ 所在的列（从 1 开始，按字符计）
+  pub column: usize,
+}
+
+impl ImageInfo {
+  /// 图片资源的来源
+  pub fn source(&self) -> ImageSource {
+    ImageSource::from_url(&self.original_url)
+  }
+
+  /// 渲染时使用的地址：已本地化时为本地路径，否则为原始 URL
+  pub fn current_url(&self) -> &str {
+    self.local_path.as_deref().unwrap_or(&self.original_url)
+  }
+}
+
+impl LinkDefinition {
+  /// 规范化标签，用于匹配引用（不区分大小写，合并空白）
+  pub fn normalize_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+  }
+
+  /// 渲染时使用的地址：已本地化时为本地路径，否则为原始 URL
+  pub fn current_url(&self) -> &str {
+    self.local_path.as_deref().unwrap_or(&self.url)
+  }
 }
 
 /// MST 节点
@@ -138,6 +281,18 @@ impl MSTNode {
     }
   }
 
+  /// 创建链接引用定义节点
+  pub fn new_link_definition(definition: LinkDefinition, raw: String, line_number: usize) -> Self {
+    Self {
+      node_type: NodeType::LinkDefinition(definition),
+      title: None,
+      raw,
+      line_number,
+      children: Vec::new(),
+      numbering: None,
+    }
+  }
+
   /// 获取标题级别
   pub fn header_level(&self) -> Option<usize> {
     match self.node_type {
@@ -161,6 +316,11 @@ impl MSTNode {
     matches!(self.node_type, NodeType::Image(_))
   }
 
+  /// 是否为链接引用定义节点
+  pub fn is_link_definition(&self) -> bool {
+    matches!(self.node_type, NodeType::LinkDefinition(_))
+  }
+
   pub fn is_empty(&self) -> bool {
     self.children.is_empty()
   }
@@ -181,6 +341,22 @@ impl MSTNode {
     }
   }
 
+  /// 获取链接引用定义（如果是链接引用定义节点）
+  pub fn get_link_definition(&self) -> Option<&LinkDefinition> {
+    match &self.node_type {
+      NodeType::LinkDefinition(definition) => Some(definition),
+      _ => None,
+    }
+  }
+
+  /// 获取链接引用定义（可变引用，如果是链接引用定义节点）
+  pub fn get_link_definition_mut(&mut self) -> Option<&mut LinkDefinition> {
+    match &mut self.node_type {
+      NodeType::LinkDefinition(definition) => Some(definition),
+      _ => None,
+    }
+  }
+
   /// 添加子节点
   pub fn add_child(&mut self, child: MSTNode) {
     self.children.push(child);
@@ -263,9 +439,13 @@ impl MSTNode {
         writeln!(f, "{}Content: {}", indent_str, preview)?;
       }
       NodeType::Image(image_info) => {
-        let url = image_info.local_path.as_ref().unwrap_or(&image_info.original_url);
+        let url = image_info.current_url();
         writeln!(f, "{}Image: {} (alt: {})", indent_str, url, image_info.alt_text)?;
       }
+      NodeType::LinkDefinition(definition) => {
+        let url = definition.current_url();
+        writeln!(f, "{}LinkDefinition: [{}] {}", indent_str, definition.label, url)?;
+      }
     }
 
     for child in &self.children {
@@ -335,6 +515,9 @@ mod tests {
       alt_text: "Test image".to_string(),
       title: Some("Image title".to_string()),
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
 
     let image_node = MSTNode::new_image(
@@ -364,6 +547,9 @@ mod tests {
       alt_text: "Test image".to_string(),
       title: None,
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
 
     let mut image_node = MSTNode::new_image(image_info, "![Test image](https://example.com/image.png)".to_string(), 1);
@@ -476,6 +662,9 @@ mod tests {
       alt_text: "alt1".to_string(),
       title: None,
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
     let image_info2 = ImageInfo {
       image_type: ImageType::Markdown,
@@ -484,6 +673,9 @@ mod tests {
       alt_text: "alt1".to_string(),
       title: None,
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
     assert_eq!(NodeType::Image(image_info1), NodeType::Image(image_info2));
   }
@@ -508,6 +700,9 @@ mod tests {
       alt_text: "Test image".to_string(),
       title: None,
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
     let image_node = MSTNode::new_image(image_info, "![Test image](./assets/image.png)".to_string(), 3);
 
@@ -553,6 +748,9 @@ mod tests {
       alt_text: "Diagram".to_string(),
       title: Some("Architecture Diagram".to_string()),
       html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
     };
     let image = MSTNode::new_image(image_info, "![Diagram](https://example.com/diagram.png)".to_string(), 4);
 
@@ -570,4 +768,48 @@ mod tests {
     assert!(root.children[2].is_content());
     assert!(root.children[3].is_image());
   }
+
+  #[test]
+  fn test_title_delimiter() {
+    assert_eq!(TitleDelimiter::split("\"title\""), Some((TitleDelimiter::DoubleQuote, "title".to_string())));
+    assert_eq!(TitleDelimiter::split("'it\"s'"), Some((TitleDelimiter::SingleQuote, "it\"s".to_string())));
+    assert_eq!(TitleDelimiter::split("(标题)"), Some((TitleDelimiter::Parentheses, "标题".to_string())));
+    assert_eq!(TitleDelimiter::split("title"), None);
+    assert_eq!(TitleDelimiter::split(""), None);
+
+    assert_eq!(TitleDelimiter::SingleQuote.wrap("a \"b\""), "'a \"b\"'");
+    assert_eq!(TitleDelimiter::Parentheses.wrap("c"), "(c)");
+  }
+
+  #[test]
+  fn test_link_definition_node() {
+    let definition = LinkDefinition {
+      label: "Logo Dark".to_string(),
+      url: "https://example.com/logo.png".to_string(),
+      local_path: None,
+      title: None,
+      title_delimiter: TitleDelimiter::default(),
+      raw: String::new(),
+      url_range: None,
+    };
+    let mut node = MSTNode::new_link_definition(definition, "[Logo Dark]: https://example.com/logo.png".to_string(), 3);
+
+    assert!(node.is_link_definition());
+    assert!(!node.is_content());
+    node.get_link_definition_mut().unwrap().local_path = Some("assets/1.png".to_string());
+    assert_eq!(node.get_link_definition().unwrap().local_path, Some("assets/1.png".to_string()));
+    assert!(format!("{}", node).contains("LinkDefinition: [Logo Dark] assets/1.png"));
+
+    assert_eq!(LinkDefinition::normalize_label("  Logo \t Dark "), "logo dark");
+  }
+
+  #[test]
+  fn test_image_source() {
+    assert_eq!(ImageSource::from_url("https://example.com/a.png"), ImageSource::Remote);
+    assert_eq!(ImageSource::from_url("http://example.com/a.png"), ImageSource::Remote);
+    assert_eq!(ImageSource::from_url("data:image/png;base64,iVBORw0KGgo="), ImageSource::DataUri);
+    assert_eq!(ImageSource::from_url("./assets/a.png"), ImageSource::Local);
+    assert_eq!(ImageSource::from_url("../images/a.png"), ImageSource::Local);
+    assert_eq!(ImageSource::from_url("/abs/a.png"), ImageSource::Local);
+  }
 }
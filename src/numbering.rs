@@ -91,6 +91,33 @@ impl NumberingGenerator {
   }
 }
 
+/// 根据文档中已有的章节编号推断编号配置，文档没有编号时返回 None
+///
+/// 解析器会从标题文本中去掉编号，标题行原文与标题文本之间的差异即为编号
+pub fn detect_numbering_config(mst: &MSTNode) -> Option<NumberingConfig> {
+  let headers: Vec<(usize, String)> = mst
+    .get_headers()
+    .iter()
+    .filter_map(|header| {
+      let text = header.raw.trim_start_matches('#').trim();
+      let prefix = text.strip_suffix(header.title.as_deref()?)?.trim();
+      Some((header.header_level()?, prefix.to_string()))
+    })
+    .collect();
+
+  let numbered: Vec<&(usize, String)> = headers.iter().filter(|(_, prefix)| !prefix.is_empty()).collect();
+  if numbered.is_empty() {
+    return None;
+  }
+
+  let use_chinese_number = numbered.iter().any(|(_, prefix)| prefix.contains('、'));
+  Some(NumberingConfig {
+    ignore_h1: headers.iter().any(|(level, _)| *level == 1) && numbered.iter().all(|(level, _)| *level > 1),
+    use_chinese_number,
+    use_arabic_number_for_sublevel: !use_chinese_number || numbered.iter().any(|(_, prefix)| !prefix.contains('、')),
+  })
+}
+
 /// 将阿拉伯数字转换为中文数字
 fn to_chinese_number(num: usize) -> String {
   let chinese_digits = ["", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
@@ -129,6 +156,7 @@ fn to_chinese_number(num: usize) -> String {
 mod tests {
   use super::*;
   use crate::mst::MSTNode;
+  use crate::parser::MarkdownParser;
 
   fn create_test_mst() -> MSTNode {
     let mut root = MSTNode::new_root();
@@ -150,6 +178,27 @@ mod tests {
     root
   }
 
+  /// 测试从已有编号推断编号配置
+  #[test]
+  fn test_detect_numbering_config() {
+    let parser = MarkdownParser::new().unwrap();
+    let detect = |content: &str| detect_numbering_config(&parser.parse(content).unwrap());
+
+    assert!(detect("# 标题\n\n## 子标题\n").is_none());
+
+    let config = detect("# 1. 标题\n\n## 1.1. 子标题\n").unwrap();
+    assert!(!config.ignore_h1 && !config.use_chinese_number && config.use_arabic_number_for_sublevel);
+
+    let config = detect("# 一、标题\n\n## 1. 子标题\n").unwrap();
+    assert!(!config.ignore_h1 && config.use_chinese_number && config.use_arabic_number_for_sublevel);
+
+    let config = detect("# 一、标题\n\n## 一、一、子标题\n").unwrap();
+    assert!(config.use_chinese_number && !config.use_arabic_number_for_sublevel);
+
+    let config = detect("# 文档标题\n\n## 1. 子标题\n").unwrap();
+    assert!(config.ignore_h1 && !config.use_chinese_number);
+  }
+
   #[test]
   fn test_to_chinese_number() {
     assert_eq!(to_chinese_number(0), "零");
@@ -0,0 +1,343 @@
+//! 文档大纲模块
+//!
+//! 从 MST 中提取标题大纲，生成与 GitHub 一致的标题锚点，并按锚点、标题路径或行号定位章节在原文中的行范围
+
+use crate::error::{MarkdownError, Result};
+use crate::mst::MSTNode;
+use crate::tr;
+use std::collections::HashMap;
+use std::fmt;
+
+/// 大纲条目，对应文档中的一个标题
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineEntry {
+  /// 标题级别（1-6）
+  pub level: usize,
+  /// 标题文本（保留编号，去掉 # 符号）
+  pub text: String,
+  /// 去掉编号后的标题文本
+  pub title: String,
+  /// 标题锚点（不含 #），同名标题依次追加 -1、-2
+  pub anchor: String,
+  /// 标题所在行号（从 1 开始）
+  pub line_number: usize,
+  /// 章节最后一行的行号，章节包含下一个同级或更高级标题之前的所有内容
+  pub end_line: usize,
+}
+
+/// 从 MST 构建大纲，`total_lines` 为原文的总行数
+pub fn build_outline(mst: &MSTNode, total_lines: usize) -> Vec<OutlineEntry> {
+  let headers = mst.get_headers();
+  let mut anchor_counts: HashMap<String, usize> = HashMap::new();
+
+  let mut entries: Vec<OutlineEntry> = headers
+    .iter()
+    .map(|header| {
+      let text = heading_text(&header.raw);
+      let base_anchor = heading_anchor(&text);
+      let count = anchor_counts.entry(base_anchor.clone()).or_insert(0);
+      let anchor = if *count == 0 { base_anchor } else { format!("{}-{}", base_anchor, count) };
+      *count += 1;
+
+      OutlineEntry {
+        level: header.header_level().unwrap_or(1),
+        text,
+        title: header.title.clone().unwrap_or_default(),
+        anchor,
+        line_number: header.line_number,
+        end_line: total_lines,
+      }
+    })
+    .collect();
+
+  // 章节在下一个同级或更高级标题之前结束
+  for i in 0..entries.len() {
+    let level = entries[i].level;
+    if let Some(next) = entries[i + 1..].iter().find(|entry| entry.level <= level) {
+      entries[i].end_line = next.line_number - 1;
+    }
+  }
+
+  entries
+}
+
+/// 按锚点查找大纲条目，锚点可以带前导 #，不区分大小写
+pub fn find_section<'a>(outline: &'a [OutlineEntry], anchor: &str) -> Option<&'a OutlineEntry> {
+  let anchor = anchor.trim_start_matches('#').to_lowercase();
+  outline.iter().find(|entry| entry.anchor == anchor)
+}
+
+/// 定位标题的方式
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadingSelector {
+  /// 标题锚点，可以带前导 #，不区分大小写
+  Anchor(String),
+  /// 以 / 分隔的标题路径，如 `设计/存储`，每一级都位于上一级的章节内；
+  /// 每一级可以写带编号或去掉编号的标题文本，不区分大小写
+  TitlePath(String),
+  /// 以 . 分隔的编号路径，如 `2.3`，优先匹配标题自带的章节编号（`、` 视同 `.`）；
+  /// 没有标题带有该编号时，按章节的位置定位，即第 2 个顶层章节下的第 3 个子章节
+  NumberPath(String),
+  /// 标题所在的行号（从 1 开始）
+  Line(usize),
+}
+
+impl HeadingSelector {
+  /// 在大纲中查找选定的标题，找不到或标题路径匹配到多个标题时返回错误
+  pub fn find<'a>(&self, outline: &'a [OutlineEntry]) -> Result<&'a OutlineEntry> {
+    let found = match self {
+      HeadingSelector::Anchor(anchor) => find_section(outline, anchor),
+      HeadingSelector::TitlePath(path) => {
+        let matches = find_by_title_path(outline, path);
+        if matches.len() > 1 {
+          let lines: Vec<String> = matches.iter().map(|entry| entry.line_number.to_string()).collect();
+          return Err(MarkdownError::ValidationError(tr!(
+            "标题路径 {} 匹配到多个标题（第 {} 行），请使用锚点或行号",
+            path,
+            lines.join("、")
+          )));
+        }
+        matches.into_iter().next()
+      }
+      HeadingSelector::NumberPath(path) => {
+        let matches = find_by_number_path(outline, path);
+        if matches.len() > 1 {
+          let lines: Vec<String> = matches.iter().map(|entry| entry.line_number.to_string()).collect();
+          return Err(MarkdownError::ValidationError(tr!(
+            "编号路径 {} 匹配到多个标题（第 {} 行），请使用锚点或行号",
+            path,
+            lines.join("、")
+          )));
+        }
+        matches.into_iter().next()
+      }
+      HeadingSelector::Line(line_number) => outline.iter().find(|entry| entry.line_number == *line_number),
+    };
+    found.ok_or_else(|| MarkdownError::ValidationError(tr!("没有找到标题: {}", self)))
+  }
+}
+
+impl fmt::Display for HeadingSelector {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      HeadingSelector::Anchor(anchor) => write!(f, "#{}", anchor.trim_start_matches('#')),
+      HeadingSelector::TitlePath(path) | HeadingSelector::NumberPath(path) => write!(f, "{}", path),
+      HeadingSelector::Line(line_number) => write!(f, "{}", tr!("第{}行", line_number)),
+    }
+  }
+}
+
+/// 按标题路径查找标题，返回所有匹配的标题
+fn find_by_title_path<'a>(outline: &'a [OutlineEntry], path: &str) -> Vec<&'a OutlineEntry> {
+  let matches_segment = |entry: &OutlineEntry, segment: &str| {
+    let segment = segment.to_lowercase();
+    entry.text.to_lowercase() == segment || entry.title.to_lowercase() == segment
+  };
+
+  let mut segments = path.split('/').map(str::trim).filter(|segment| !segment.is_empty());
+  let Some(first) = segments.next() else {
+    return Vec::new();
+  };
+
+  let mut candidates: Vec<&OutlineEntry> = outline.iter().filter(|entry| matches_segment(entry, first)).collect();
+  for segment in segments {
+    candidates = outline
+      .iter()
+      .filter(|entry| matches_segment(entry, segment))
+      .filter(|entry| {
+        candidates.iter().any(|parent| {
+          entry.level > parent.level && (parent.line_number + 1..=parent.end_line).contains(&entry.line_number)
+        })
+      })
+      .collect();
+  }
+  candidates
+}
+
+/// 按编号路径查找标题：先匹配标题自带的章节编号，没有匹配时按章节的位置查找
+fn find_by_number_path<'a>(outline: &'a [OutlineEntry], path: &str) -> Vec<&'a OutlineEntry> {
+  let wanted = normalize_numbering(path);
+  if wanted.is_empty() {
+    return Vec::new();
+  }
+
+  let numbered: Vec<&OutlineEntry> = outline
+    .iter()
+    .filter(|entry| {
+      entry.text.strip_suffix(entry.title.as_str()).is_some_and(|prefix| normalize_numbering(prefix) == wanted)
+    })
+    .collect();
+  if !numbered.is_empty() {
+    return numbered;
+  }
+
+  // 按位置查找：每一级编号是该章节在上一级章节的直接子章节中的序号（从 1 开始）
+  let mut found: Option<&OutlineEntry> = None;
+  for segment in wanted.split('.') {
+    let siblings = child_sections(outline, found);
+    let Some(entry) = segment.trim().parse::<usize>().ok().and_then(|n| n.checked_sub(1)).and_then(|i| siblings.get(i))
+    else {
+      return Vec::new();
+    };
+    found = Some(entry);
+  }
+  found.into_iter().collect()
+}
+
+/// 规范化章节编号：`、` 视同 `.`，去掉首尾的分隔符和空白
+fn normalize_numbering(numbering: &str) -> String {
+  numbering.replace('、', ".").trim().trim_matches('.').trim().to_string()
+}
+
+/// 获取章节的直接子章节，`parent` 为 `None` 时获取顶层章节
+pub fn child_sections<'a>(outline: &'a [OutlineEntry], parent: Option<&OutlineEntry>) -> Vec<&'a OutlineEntry> {
+  let inside = |entry: &OutlineEntry, parent: &OutlineEntry| {
+    entry.level > parent.level && (parent.line_number + 1..=parent.end_line).contains(&entry.line_number)
+  };
+  let candidates: Vec<&OutlineEntry> =
+    outline.iter().filter(|entry| parent.is_none_or(|parent| inside(entry, parent))).collect();
+  candidates.iter().filter(|entry| !candidates.iter().any(|other| inside(entry, other))).copied().collect()
+}
+
+/// 截取章节在原文中的文本，去掉末尾的空行
+pub fn section_text(content: &str, entry: &OutlineEntry) -> String {
+  let lines: Vec<&str> = content
+    .lines()
+    .skip(entry.line_number.saturating_sub(1))
+    .take(entry.end_line + 1 - entry.line_number.max(1))
+    .collect();
+  let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |i| i + 1);
+  lines[..end].join("\n")
+}
+
+/// 从标题行中提取标题文本：去掉开头的 # 和可选的结尾 #
+pub fn heading_text(raw: &str) -> String {
+  let text = raw.trim().trim_start_matches('#').trim();
+  // 结尾的 # 只有与正文以空格分隔时才是闭合符号
+  let without_closing = text.trim_end_matches('#');
+  if without_closing.len() < text.len() && (without_closing.is_empty() || without_closing.ends_with(' ')) {
+    without_closing.trim_end().to_string()
+  } else {
+    text.to_string()
+  }
+}
+
+/// 生成与 GitHub 一致的标题锚点：转小写，去掉标点，空格替换为 -
+pub fn heading_anchor(text: &str) -> String {
+  text
+    .trim()
+    .to_lowercase()
+    .chars()
+    .filter(|c| c.is_alphanumeric() || *c == '-' || *c == '_' || *c == ' ')
+    .map(|c| if c == ' ' { '-' } else { c })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::MarkdownParser;
+
+  const DOC: &str =
+    "# 1. 第一章 介绍\n\n前言\n\n## 背景\n\n内容\n\n## 背景\n\n### Hello, World! ###\n\n# 第二章\n\n结尾\n";
+
+  fn outline() -> Vec<OutlineEntry> {
+    outline_of(DOC)
+  }
+
+  fn outline_of(doc: &str) -> Vec<OutlineEntry> {
+    let mst = MarkdownParser::new().unwrap().parse(doc).unwrap();
+    build_outline(&mst, doc.lines().count())
+  }
+
+  /// 测试标题锚点生成
+  #[test]
+  fn test_heading_anchor() {
+    assert_eq!(heading_anchor("1. 第一章 介绍"), "1-第一章-介绍");
+    assert_eq!(heading_anchor("Hello, World!"), "hello-world");
+    assert_eq!(heading_anchor("API `v2` 参考"), "api-v2-参考");
+    assert_eq!(heading_anchor("snake_case - 说明"), "snake_case---说明");
+  }
+
+  /// 测试标题文本提取
+  #[test]
+  fn test_heading_text() {
+    assert_eq!(heading_text("## 背景"), "背景");
+    assert_eq!(heading_text("### Hello ###"), "Hello");
+    assert_eq!(heading_text("# C#"), "C#");
+  }
+
+  /// 测试大纲构建和重名锚点
+  #[test]
+  fn test_build_outline() {
+    let outline = outline();
+    let anchors: Vec<&str> = outline.iter().map(|entry| entry.anchor.as_str()).collect();
+    assert_eq!(anchors, vec!["1-第一章-介绍", "背景", "背景-1", "hello-world", "第二章"]);
+
+    assert_eq!((outline[0].line_number, outline[0].end_line), (1, 12));
+    assert_eq!((outline[1].line_number, outline[1].end_line), (5, 8));
+    assert_eq!((outline[2].line_number, outline[2].end_line), (9, 12));
+    assert_eq!(outline[4].end_line, DOC.lines().count());
+  }
+
+  /// 测试代码块内形如标题的行不计入大纲，也不会截断章节
+  #[test]
+  fn test_build_outline_skips_fenced_code() {
+    let doc = "# 安装\n\n```bash\n# install deps\nnpm install\n```\n\n~~~~\n```\n# 不是标题\n~~~~\n\n## 使用\n";
+    let outline = outline_of(doc);
+    let anchors: Vec<&str> = outline.iter().map(|entry| entry.anchor.as_str()).collect();
+    assert_eq!(anchors, vec!["安装", "使用"]);
+    assert_eq!((outline[0].line_number, outline[0].end_line), (1, 13));
+  }
+
+  /// 测试按锚点截取章节
+  #[test]
+  fn test_section_text() {
+    let outline = outline();
+
+    let section = find_section(&outline, "#背景").unwrap();
+    assert_eq!(section_text(DOC, section), "## 背景\n\n内容");
+
+    let section = find_section(&outline, "背景-1").unwrap();
+    assert_eq!(section_text(DOC, section), "## 背景\n\n### Hello, World! ###");
+
+    assert!(find_section(&outline, "不存在").is_none());
+  }
+
+  /// 测试按锚点、标题路径和行号定位标题
+  #[test]
+  fn test_heading_selector() {
+    let outline = outline();
+    let find = |selector: HeadingSelector| selector.find(&outline).map(|entry| entry.line_number);
+
+    assert_eq!(find(HeadingSelector::Anchor("#背景-1".to_string())).unwrap(), 9);
+    assert_eq!(find(HeadingSelector::TitlePath("第一章 介绍/背景/hello, world!".to_string())).unwrap(), 11);
+    assert_eq!(find(HeadingSelector::TitlePath("1. 第一章 介绍".to_string())).unwrap(), 1);
+    assert_eq!(find(HeadingSelector::Line(13)).unwrap(), 13);
+
+    let err = find(HeadingSelector::TitlePath("第一章 介绍/背景".to_string())).unwrap_err();
+    assert!(err.to_string().contains("第 5、9 行"), "{}", err);
+    assert!(find(HeadingSelector::TitlePath("第二章/背景".to_string())).is_err());
+    assert!(find(HeadingSelector::Line(3)).is_err());
+  }
+
+  /// 测试按编号路径定位标题：优先匹配标题自带的编号，否则按位置定位
+  #[test]
+  fn test_number_path_selector() {
+    let doc = "# 一、概述\n\n## 1. 背景\n\n# 二、设计\n\n## 1. 存储\n\n## 2. 接口\n";
+    let outline = build_outline(&MarkdownParser::new().unwrap().parse(doc).unwrap(), doc.lines().count());
+    let find = |path: &str| HeadingSelector::NumberPath(path.to_string()).find(&outline).map(|entry| entry.line_number);
+
+    assert_eq!(find("二、").unwrap(), 5);
+    assert_eq!(find("2").unwrap(), 9);
+    assert!(find("1").unwrap_err().to_string().contains("第 3、7 行"));
+
+    // 没有编号的文档按位置定位
+    let outline = outline_of(DOC);
+    let find = |path: &str| HeadingSelector::NumberPath(path.to_string()).find(&outline).map(|entry| entry.line_number);
+    assert_eq!(find("1.2.1").unwrap(), 11);
+    assert_eq!(find("2").unwrap(), 13);
+    assert!(find("1.3").is_err());
+    assert!(find("x").is_err());
+  }
+}
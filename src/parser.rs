@@ -2,26 +2,60 @@
 //!
 //! 将 Markdown 文本解析为 MST (Markdown Structured Tree) 结构
 
-use crate::mst::{ImageInfo, MSTNode, NodeType};
+use crate::mst::{ImageInfo, ImageType, InlineLink, LinkDefinition, MSTNode, NodeType, ReferenceLink, TitleDelimiter};
+use crate::statistics::fenced_lines;
 use regex::Regex;
+use std::collections::HashMap;
+
+/// 链接引用定义表：规范化后的标签 -> 定义
+pub type LinkDefinitions = HashMap<String, LinkDefinition>;
 
 /// Markdown 解析器
 pub struct MarkdownParser {
   header_regex: Regex,
   image_regex: Regex,
+  reference_image_regex: Regex,
+  shortcut_image_regex: Regex,
+  link_definition_regex: Regex,
   html_img_regex: Regex,
+  link_regex: Regex,
+  reference_link_regex: Regex,
+  shortcut_link_regex: Regex,
 }
 
 impl MarkdownParser {
   /// 创建新的解析器
   pub fn new() -> Result<Self, String> {
     let header_regex = Regex::new(r"^(#{1,6})\s+(.*)$").map_err(|e| format!("正则表达式错误: {}", e))?;
-    let image_regex =
-      Regex::new(r#"!\[([^\]]*)\]\(([^)]+?)(?:\s+"([^"]*)")?\)"#).map_err(|e| format!("图片正则表达式错误: {}", e))?;
+    let image_regex = Regex::new(r#"!\[([^\]]*)\]\(\s*([^\s)]+)(?:\s+("[^"]*"|'[^']*'|\([^)]*\)))?\s*\)"#)
+      .map_err(|e| format!("图片正则表达式错误: {}", e))?;
+    let reference_image_regex =
+      Regex::new(r"!\[([^\]]*)\]\[([^\]]*)\]").map_err(|e| format!("引用图片正则表达式错误: {}", e))?;
+    let shortcut_image_regex = Regex::new(r"!\[([^\]]+)\]").map_err(|e| format!("引用图片正则表达式错误: {}", e))?;
+    let link_definition_regex =
+      Regex::new(r#"^ {0,3}\[([^\]^][^\]]*)\]:\s*<?([^\s<>]+)>?(?:\s+("[^"]*"|'[^']*'|\([^)]*\)))?\s*$"#)
+        .map_err(|e| format!("链接引用定义正则表达式错误: {}", e))?;
     let html_img_regex = Regex::new(r#"<img\s*([^>]*?)src\s*=\s*["']([^"']+)["']([^>]*?)/?>"#)
       .map_err(|e| format!("HTML img 正则表达式错误: {}", e))?;
-
-    Ok(Self { header_regex, image_regex, html_img_regex })
+    // 链接文本中可以包含一层方括号，如 [![alt](img)](url)
+    let link_regex =
+      Regex::new(r#"\[((?:[^\[\]]|\[[^\]]*\])*)\]\(\s*(<[^>]*>|[^\s)]+)(?:\s+("[^"]*"|'[^']*'|\([^)]*\)))?\s*\)"#)
+        .map_err(|e| format!("链接正则表达式错误: {}", e))?;
+    let reference_link_regex =
+      Regex::new(r"\[((?:[^\[\]]|\[[^\]]*\])*)\]\[([^\]]*)\]").map_err(|e| format!("引用链接正则表达式错误: {}", e))?;
+    let shortcut_link_regex = Regex::new(r"\[([^\[\]]+)\]").map_err(|e| format!("引用链接正则表达式错误: {}", e))?;
+
+    Ok(Self {
+      header_regex,
+      image_regex,
+      reference_image_regex,
+      shortcut_image_regex,
+      link_definition_regex,
+      html_img_regex,
+      link_regex,
+      reference_link_regex,
+      shortcut_link_regex,
+    })
   }
 
   /// 解析 Markdown 文本为 MST
@@ -29,11 +63,17 @@ impl MarkdownParser {
     let mut root = MSTNode::new_root();
     let mut header_stack: Vec<(usize, usize)> = Vec::new(); // (level, node_index)
 
+    // 引用式图片可能出现在定义之前，先收集全文的链接引用定义
+    let definitions = self.parse_link_definitions(content);
+    // 代码块内以 # 开头的行不是标题
+    let fenced = fenced_lines(content);
+
     for (line_number, line) in content.lines().enumerate() {
+      let in_fence = fenced[line_number];
       let line_number = line_number + 1; // 从1开始计数
 
       // 标题行
-      if let Some(captures) = self.header_regex.captures(line) {
+      if let Some(captures) = self.header_regex.captures(line).filter(|_| !in_fence) {
         let hashes = captures.get(1).unwrap().as_str();
         let title = captures.get(2).unwrap().as_str();
         let level = hashes.len();
@@ -48,8 +88,19 @@ impl MarkdownParser {
         continue;
       }
 
+      // 链接引用定义行
+      if let Some(definition) = self.parse_link_definition(line) {
+        let definition_node = MSTNode::new_link_definition(definition, line.to_string(), line_number);
+        if let Some((_, parent_index)) = header_stack.last() {
+          self.add_content_to_node(&mut root, *parent_index, definition_node);
+        } else {
+          root.add_child(definition_node);
+        }
+        continue;
+      }
+
       // 检查是否包含图片（可能是行内图片或独立图片行）
-      let image_nodes = self.parse_images_in_line(line, line_number);
+      let image_nodes = self.parse_images_in_line_with_definitions(line, line_number, &definitions);
       if !image_nodes.is_empty() {
         // 如果整行只有一个图片且没有其他内容，作为独立图片节点
         if image_nodes.len() == 1 && line.trim() == image_nodes[0].raw.trim() {
@@ -190,70 +241,254 @@ impl MarkdownParser {
     cleaned.trim().to_string()
   }
 
+  /// 解析单行链接引用定义：[label]: url "title"
+  pub fn parse_link_definition(&self, line: &str) -> Option<LinkDefinition> {
+    let captures = self.link_definition_regex.captures(line)?;
+    let label = captures.get(1)?.as_str().to_string();
+    let url_match = captures.get(2)?;
+    let (title_delimiter, title) = match captures.get(3).and_then(|m| TitleDelimiter::split(m.as_str())) {
+      Some((delimiter, title)) => (delimiter, Some(title)),
+      None => (TitleDelimiter::default(), None),
+    };
+
+    Some(LinkDefinition {
+      label,
+      url: url_match.as_str().to_string(),
+      local_path: None,
+      title,
+      title_delimiter,
+      raw: line.to_string(),
+      url_range: Some(url_match.range()),
+    })
+  }
+
+  /// 收集文档中所有的链接引用定义，标签重复时以第一个为准
+  pub fn parse_link_definitions(&self, content: &str) -> LinkDefinitions {
+    let mut definitions = LinkDefinitions::new();
+    for line in content.lines() {
+      if let Some(definition) = self.parse_link_definition(line) {
+        definitions.entry(LinkDefinition::normalize_label(&definition.label)).or_insert(definition);
+      }
+    }
+    definitions
+  }
+
+  /// 解析一行中的行内链接 [text](url)，不包括图片
+  pub fn parse_links_in_line(&self, line: &str, line_number: usize) -> Vec<InlineLink> {
+    self
+      .link_regex
+      .captures_iter(line)
+      .filter_map(|captures| {
+        // 前面紧跟 ! 的是图片
+        let full_match = captures.get(0)?;
+        if line[..full_match.start()].ends_with('!') {
+          return None;
+        }
+        let text = captures.get(1)?.as_str().to_string();
+        let target = captures.get(2)?;
+        let url_range = match target.as_str().starts_with('<') {
+          true => target.start() + 1..target.end() - 1,
+          false => target.range(),
+        };
+        let column = line[..full_match.start()].chars().count() + 1;
+        Some(InlineLink { text, url: line[url_range.clone()].to_string(), url_range, line_number, column })
+      })
+      .collect()
+  }
+
+  /// 解析一行中的引用式链接，不包括图片，地址通过 definitions 解析
+  ///
+  /// [text][label] 和 [label][] 的标签未定义时仍然返回，地址为 None；
+  /// 简写的 [label] 只在标签已定义时才是链接，否则只是普通文本
+  pub fn parse_reference_links_in_line(
+    &self,
+    line: &str,
+    line_number: usize,
+    definitions: &LinkDefinitions,
+  ) -> Vec<ReferenceLink> {
+    // 行内链接的区间，其中的方括号不再按引用式链接解析
+    let mut matched_spans: Vec<_> = self.link_regex.find_iter(line).map(|m| m.range()).collect();
+    let overlaps = |spans: &[std::ops::Range<usize>], range: &std::ops::Range<usize>| {
+      spans.iter().any(|span| span.start < range.end && range.start < span.end)
+    };
+    let new_link = |text: &str, label: &str, start: usize| ReferenceLink {
+      text: text.to_string(),
+      label: label.to_string(),
+      url: definitions.get(&LinkDefinition::normalize_label(label)).map(|definition| definition.url.clone()),
+      line_number,
+      column: line[..start].chars().count() + 1,
+    };
+
+    let mut links = Vec::new();
+    for captures in self.reference_link_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
+      if line[..full_match.start()].ends_with('!') || overlaps(&matched_spans, &full_match.range()) {
+        continue;
+      }
+      matched_spans.push(full_match.range());
+
+      let text = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+      let label = captures.get(2).map(|m| m.as_str()).filter(|l| !l.trim().is_empty()).unwrap_or(text);
+      links.push(new_link(text, label, full_match.start()));
+    }
+
+    for captures in self.shortcut_link_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
+      let preceded = matches!(line[..full_match.start()].chars().last(), Some('!') | Some(']'));
+      let followed = matches!(line[full_match.end()..].chars().next(), Some('(') | Some('[') | Some(':'));
+      if preceded || followed || overlaps(&matched_spans, &full_match.range()) {
+        continue;
+      }
+
+      let label = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+      let link = new_link(label, label, full_match.start());
+      if link.url.is_some() {
+        links.push(link);
+      }
+    }
+
+    links.sort_by_key(|link| link.column);
+    links
+  }
+
   /// 解析一行中的所有图片（支持行内图片和多个图片）
   pub fn parse_images_in_line(&self, line: &str, line_number: usize) -> Vec<MSTNode> {
+    self.parse_images_in_line_with_definitions(line, line_number, &LinkDefinitions::new())
+  }
+
+  /// 解析一行中的所有图片，引用式图片通过 definitions 解析出 URL
+  pub fn parse_images_in_line_with_definitions(
+    &self,
+    line: &str,
+    line_number: usize,
+    definitions: &LinkDefinitions,
+  ) -> Vec<MSTNode> {
     let mut images = Vec::new();
+    // 已被识别为图片的区间，避免引用式图片的正则重复匹配
+    let mut matched_spans = Vec::new();
 
     // 解析 Markdown 格式的图片
     for captures in self.image_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
+      matched_spans.push(full_match.range());
+
       let alt_text = captures.get(1).map(|m| m.as_str()).unwrap_or("").to_string();
-      let url = captures.get(2).map(|m| m.as_str()).unwrap_or("").to_string();
-      let title = captures.get(3).map(|m| m.as_str().to_string());
-
-      // 检查是否是远程图片（http/https）
-      if url.starts_with("http://") || url.starts_with("https://") {
-        let image_info = ImageInfo {
-          image_type: crate::mst::ImageType::Markdown,
-          original_url: url,
-          local_path: None,
-          alt_text,
-          title,
-          html_attributes: None,
-        };
+      let url_match = captures.get(2).unwrap();
+      let url = url_match.as_str().to_string();
+      let (title_delimiter, title) = match captures.get(3).and_then(|m| TitleDelimiter::split(m.as_str())) {
+        Some((delimiter, title)) => (delimiter, Some(title)),
+        None => (TitleDelimiter::default(), None),
+      };
+
+      let image_info = ImageInfo {
+        image_type: ImageType::Markdown,
+        original_url: url,
+        local_path: None,
+        alt_text,
+        title,
+        html_attributes: None,
+        title_delimiter,
+        reference_label: None,
+        url_range: Some(url_match.start() - full_match.start()..url_match.end() - full_match.start()),
+      };
+
+      images.push(MSTNode::new_image(image_info, full_match.as_str().to_string(), line_number));
+    }
+
+    // 解析引用式图片：![alt][label] 和 ![label][]
+    for captures in self.reference_image_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
+      matched_spans.push(full_match.range());
 
-        let full_match = captures.get(0).unwrap().as_str();
-        images.push(MSTNode::new_image(image_info, full_match.to_string(), line_number));
+      let alt_text = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+      let label = captures.get(2).map(|m| m.as_str()).filter(|l| !l.trim().is_empty()).unwrap_or(alt_text);
+      if let Some(node) = self.new_reference_image(alt_text, label, full_match.as_str(), line_number, definitions) {
+        images.push(node);
+      }
+    }
+
+    // 解析简写的引用式图片：![label]
+    for captures in self.shortcut_image_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
+      let overlaps = matched_spans.iter().any(|span| span.start < full_match.end() && full_match.start() < span.end);
+      let followed_by_target = matches!(line[full_match.end()..].chars().next(), Some('(') | Some('['));
+      if overlaps || followed_by_target {
+        continue;
+      }
+
+      let label = captures.get(1).map(|m| m.as_str()).unwrap_or("");
+      if let Some(node) = self.new_reference_image(label, label, full_match.as_str(), line_number, definitions) {
+        images.push(node);
       }
     }
 
     // 解析 HTML img 标签
     for captures in self.html_img_regex.captures_iter(line) {
+      let full_match = captures.get(0).unwrap();
       let before_attrs = captures.get(1).map(|m| m.as_str()).unwrap_or("").trim();
-      let url = captures.get(2).map(|m| m.as_str()).unwrap_or("").to_string();
+      let url_match = captures.get(2).unwrap();
+      let url = url_match.as_str().to_string();
       let after_attrs = captures.get(3).map(|m| m.as_str()).unwrap_or("").trim();
 
-      // 检查是否是远程图片（http/https）
-      if url.starts_with("http://") || url.starts_with("https://") {
-        // 合并所有属性
-        let mut all_attrs = Vec::new();
-        if !before_attrs.is_empty() {
-          all_attrs.push(before_attrs);
-        }
-        if !after_attrs.is_empty() {
-          all_attrs.push(after_attrs);
-        }
-        let html_attributes = if all_attrs.is_empty() { None } else { Some(all_attrs.join(" ")) };
-
-        // 尝试从属性中提取 alt 文本
-        let alt_text = self.extract_alt_from_attributes(&format!("{} {}", before_attrs, after_attrs));
-
-        let image_info = ImageInfo {
-          image_type: crate::mst::ImageType::Html,
-          original_url: url,
-          local_path: None,
-          alt_text,
-          title: None, // HTML img 标签通常不使用 title 属性作为图片标题
-          html_attributes,
-        };
-
-        let full_match = captures.get(0).unwrap().as_str();
-        images.push(MSTNode::new_image(image_info, full_match.to_string(), line_number));
+      // 合并所有属性
+      let mut all_attrs = Vec::new();
+      if !before_attrs.is_empty() {
+        all_attrs.push(before_attrs);
+      }
+      if !after_attrs.is_empty() {
+        all_attrs.push(after_attrs);
       }
+      let html_attributes = if all_attrs.is_empty() { None } else { Some(all_attrs.join(" ")) };
+
+      // 尝试从属性中提取 alt 文本
+      let alt_text = self.extract_alt_from_attributes(&format!("{} {}", before_attrs, after_attrs));
+
+      let image_info = ImageInfo {
+        image_type: ImageType::Html,
+        original_url: url,
+        local_path: None,
+        alt_text,
+        title: None, // HTML img 标签通常不使用 title 属性作为图片标题
+        html_attributes,
+        title_delimiter: TitleDelimiter::default(),
+        reference_label: None,
+        url_range: Some(url_match.start() - full_match.start()..url_match.end() - full_match.start()),
+      };
+
+      images.push(MSTNode::new_image(image_info, full_match.as_str().to_string(), line_number));
     }
 
+    // 按出现位置排序，保证处理顺序与文档顺序一致
+    images.sort_by_key(|node| line.find(&node.raw).unwrap_or(usize::MAX));
     images
   }
 
+  /// 创建引用式图片节点，标签未定义时返回 None
+  fn new_reference_image(
+    &self,
+    alt_text: &str,
+    label: &str,
+    raw: &str,
+    line_number: usize,
+    definitions: &LinkDefinitions,
+  ) -> Option<MSTNode> {
+    let definition = definitions.get(&LinkDefinition::normalize_label(label))?;
+
+    let image_info = ImageInfo {
+      image_type: ImageType::Reference,
+      original_url: definition.url.clone(),
+      local_path: None,
+      alt_text: alt_text.to_string(),
+      title: definition.title.clone(),
+      html_attributes: None,
+      title_delimiter: definition.title_delimiter,
+      reference_label: Some(label.to_string()),
+      url_range: None,
+    };
+
+    Some(MSTNode::new_image(image_info, raw.to_string(), line_number))
+  }
+
   /// 从 HTML 属性中提取 alt 文本
   fn extract_alt_from_attributes(&self, attrs: &str) -> String {
     // 简单的 alt 属性提取
@@ -364,6 +599,19 @@ mod tests {
     assert_eq!(mst.children.len(), 4); // 应该有内容节点
   }
 
+  /// 测试代码块内以 # 开头的行解析为内容而不是标题
+  #[test]
+  fn test_parse_fenced_code_is_not_header() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 安装\n\n```bash\n# install deps\n```\n\n~~~\n## 示例\n~~~\n";
+
+    let mst = parser.parse(content).unwrap();
+    let headers = mst.get_headers();
+
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers[0].line_number, 1);
+  }
+
   #[test]
   fn test_parse_images() {
     let parser = MarkdownParser::new().unwrap();
@@ -389,7 +637,7 @@ mod tests {
       }
     });
 
-    assert_eq!(image_count, 2); // 只有远程图片会被解析为图片节点
+    assert_eq!(image_count, 3); // 远程图片和本地图片都会被解析为图片节点
 
     // 验证第一个图片节点
     let mut found_first_image = false;
@@ -571,7 +819,7 @@ mod tests {
       }
     });
 
-    assert_eq!(image_count, 4); // 只有远程图片被解析
+    assert_eq!(image_count, 6); // 远程、本地图片都被解析
   }
 
   #[test]
@@ -678,4 +926,133 @@ mod tests {
     assert!(lines[0].contains("前面有文字"), "应该包含前面的文字");
     assert!(lines[0].contains("后面有文字"), "应该包含后面的文字");
   }
+
+  /// 测试解析行内链接，图片不算链接
+  #[test]
+  fn test_parse_links_in_line() {
+    let parser = MarkdownParser::new().unwrap();
+    let line = r#"见 [安装](install.md#依赖 "标题")、![图](a.png) 和 [![logo](logo.png)](<my doc.md>)"#;
+    let links = parser.parse_links_in_line(line, 3);
+
+    let urls: Vec<&str> = links.iter().map(|link| link.url.as_str()).collect();
+    assert_eq!(urls, ["install.md#依赖", "my doc.md"]);
+    assert_eq!(links[0].text, "安装");
+    assert_eq!(&line[links[1].url_range.clone()], "my doc.md");
+    assert_eq!(links[1].line_number, 3);
+    assert_eq!((links[0].column, links[1].column), (3, 42));
+  }
+
+  /// 测试引用式链接（完整、折叠、简写三种形式）
+  #[test]
+  fn test_parse_reference_links_in_line() {
+    let parser = MarkdownParser::new().unwrap();
+    let definitions = parser.parse_link_definitions("[guide]: guide.md#安装\n[API]: <api.md>\n");
+
+    let line = "见 [指南][guide]、[api][]、[Guide] 和 [缺失][nope]，![图][guide] 与 [x](a.md) 不算，[普通文本] 也不算";
+    let links = parser.parse_reference_links_in_line(line, 2, &definitions);
+
+    let labels: Vec<&str> = links.iter().map(|link| link.label.as_str()).collect();
+    assert_eq!(labels, ["guide", "api", "Guide", "nope"]);
+    assert_eq!(links[0].text, "指南");
+    assert_eq!(links[0].url.as_deref(), Some("guide.md#安装"));
+    assert_eq!(links[1].url.as_deref(), Some("api.md"));
+    assert_eq!(links[2].url.as_deref(), Some("guide.md#安装"));
+    assert_eq!(links[3].url, None);
+    assert_eq!((links[0].line_number, links[0].column, links[1].column), (2, 3, 15));
+
+    // 链接引用定义本身和行内链接中的方括号不是引用式链接
+    assert!(parser.parse_reference_links_in_line("[guide]: other.md", 1, &definitions).is_empty());
+    assert!(parser.parse_reference_links_in_line("[[guide]](a.md)", 1, &definitions).is_empty());
+  }
+
+  /// 测试链接引用定义的解析
+  #[test]
+  fn test_parse_link_definition() {
+    let parser = MarkdownParser::new().unwrap();
+
+    let definition = parser.parse_link_definition("[Logo]: https://example.com/logo.png 'Logo (dark)'").unwrap();
+    assert_eq!(definition.label, "Logo");
+    assert_eq!(definition.url, "https://example.com/logo.png");
+    assert_eq!(definition.title, Some("Logo (dark)".to_string()));
+    assert_eq!(definition.title_delimiter, TitleDelimiter::SingleQuote);
+
+    let definition = parser.parse_link_definition("[a b]: <https://example.com/a.png> (标题)").unwrap();
+    assert_eq!(definition.url, "https://example.com/a.png");
+    assert_eq!(definition.title_delimiter, TitleDelimiter::Parentheses);
+
+    // 脚注和普通文本不是链接引用定义
+    assert!(parser.parse_link_definition("[^1]: https://example.com/note").is_none());
+    assert!(parser.parse_link_definition("[label]: url with trailing words").is_none());
+    assert!(parser.parse_link_definition("text [label]: https://example.com").is_none());
+  }
+
+  /// 测试引用式图片（完整、折叠、简写三种形式）
+  #[test]
+  fn test_parse_reference_images() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = r#"# 引用图片
+
+![Logo][logo]
+
+文字 ![logo][] 和 ![LOGO] 都引用同一个定义，![未定义][missing] 不是图片。
+
+[logo]: https://example.com/logo.png "Logo"
+"#;
+
+    let mst = parser.parse(content).unwrap();
+
+    let mut definitions = Vec::new();
+    let mut reference_images = Vec::new();
+    mst.walk(&mut |node| {
+      if let Some(definition) = node.get_link_definition() {
+        definitions.push(definition.clone());
+      }
+      if let Some(image_info) = node.get_image_info() {
+        reference_images.push(image_info.clone());
+      }
+    });
+
+    assert_eq!(definitions.len(), 1);
+    assert_eq!(reference_images.len(), 1);
+    assert_eq!(reference_images[0].image_type, ImageType::Reference);
+    assert_eq!(reference_images[0].original_url, "https://example.com/logo.png");
+    assert_eq!(reference_images[0].reference_label, Some("logo".to_string()));
+
+    let definitions = parser.parse_link_definitions(content);
+    let line = "文字 ![logo][] 和 ![LOGO] 都引用同一个定义，![未定义][missing] 不是图片。";
+    let inline_images = parser.parse_images_in_line_with_definitions(line, 5, &definitions);
+    assert_eq!(inline_images.len(), 2);
+    assert_eq!(inline_images[0].raw, "![logo][]");
+    assert_eq!(inline_images[1].raw, "![LOGO]");
+
+    // 渲染时引用式图片和定义都保持原样
+    let rendered = MarkdownRenderer::new().render(&mst);
+    assert_eq!(rendered, content);
+  }
+
+  /// 测试带单引号和括号标题的图片以及链接中的图片
+  #[test]
+  fn test_parse_image_titles_and_image_in_link() {
+    let parser = MarkdownParser::new().unwrap();
+
+    let images = parser.parse_images_in_line("![a](https://example.com/a.png 'It\"s a title')", 1);
+    let info = images[0].get_image_info().unwrap();
+    assert_eq!(info.original_url, "https://example.com/a.png");
+    assert_eq!(info.title, Some("It\"s a title".to_string()));
+    assert_eq!(info.title_delimiter, TitleDelimiter::SingleQuote);
+
+    let images = parser.parse_images_in_line("![b](https://example.com/b.png (括号标题))", 1);
+    let info = images[0].get_image_info().unwrap();
+    assert_eq!(info.original_url, "https://example.com/b.png");
+    assert_eq!(info.title, Some("括号标题".to_string()));
+    assert_eq!(info.title_delimiter, TitleDelimiter::Parentheses);
+
+    let line = "[![badge](https://img.example.com/badge.svg)](https://github.com/example/repo)";
+    let images = parser.parse_images_in_line(line, 1);
+    assert_eq!(images.len(), 1);
+    assert_eq!(images[0].raw, "![badge](https://img.example.com/badge.svg)");
+
+    let mst = parser.parse(line).unwrap();
+    assert_eq!(MarkdownRenderer::new().render(&mst), format!("{}\n", line));
+  }
 }
@@ -0,0 +1,362 @@
+//! 项目配置文件模块
+//!
+//! 从文档所在目录逐级向上查找 `.mdtools.toml`，为工具参数提供项目级默认值，
+//! 避免每次调用都重复传入编号风格、检查规则和图片设置。调用时显式传入的参数优先于配置文件。
+//!
+//! ```toml
+//! [numbering]
+//! use_chinese_number = true
+//!
+//! [lint]
+//! max_heading_level = 4
+//!
+//! [images]
+//! image_dir = "{full_dir_of_original_file}/assets/"
+//!
+//! [[overrides]]
+//! files = ["en/**/*.md"]
+//! numbering = { use_chinese_number = false }
+//! ```
+
+use crate::config::tool_arg_names;
+use crate::error::{MarkdownError, Result};
+use crate::sandbox::Sandbox;
+use crate::tr;
+use glob::{MatchOptions, Pattern};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 项目配置文件名
+pub const PROJECT_CONFIG_FILE: &str = ".mdtools.toml";
+
+/// 章节编号设置，对应 generate_chapter_number 的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NumberingSettings {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub ignore_h1: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub use_chinese_number: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub use_arabic_number_for_sublevel: Option<bool>,
+}
+
+/// 标题检查规则，对应 check_heading 的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintSettings {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub allow_skipped_levels: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_heading_level: Option<u8>,
+}
+
+/// 图片设置，对应 localize_images、publish_images 和 clean_assets 的参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImageSettings {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub image_dir: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub image_file_name_pattern: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_image_size: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub localize_data_uris: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub copy_local_images: Option<bool>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub assets_dir: Option<String>,
+}
+
+/// 按文件匹配的覆盖设置
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OverrideSettings {
+  /// 相对于配置文件所在目录的 glob 模式，`*` 不跨目录，`**` 匹配任意层目录
+  pub files: Vec<String>,
+  #[serde(default)]
+  pub numbering: NumberingSettings,
+  #[serde(default)]
+  pub lint: LintSettings,
+  #[serde(default)]
+  pub images: ImageSettings,
+}
+
+/// `.mdtools.toml` 的内容
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfigFile {
+  #[serde(default)]
+  pub numbering: NumberingSettings,
+  #[serde(default)]
+  pub lint: LintSettings,
+  #[serde(default)]
+  pub images: ImageSettings,
+  /// 按顺序应用，后面的覆盖前面的
+  #[serde(default)]
+  pub overrides: Vec<OverrideSettings>,
+}
+
+/// 已加载的项目配置
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+  /// 配置文件路径
+  pub path: PathBuf,
+  file: ProjectConfigFile,
+}
+
+/// 对某个文档生效的项目设置
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EffectiveSettings {
+  /// 使用的配置文件，没有找到时为 None
+  pub config_file: Option<PathBuf>,
+  /// 匹配到的覆盖设置的 files 模式
+  pub matched_overrides: Vec<Vec<String>>,
+  /// 合并后的设置，键为工具参数名
+  pub values: Map<String, Value>,
+}
+
+impl ProjectConfig {
+  /// 读取并解析配置文件
+  pub fn load(path: &Path) -> Result<Self> {
+    let content = fs::read_to_string(path).map_err(|e| MarkdownError::io(path.display().to_string(), e))?;
+    let file: ProjectConfigFile = toml::from_str(&content)
+      .map_err(|e| MarkdownError::ConfigError(tr!("配置文件格式错误: {} - {}", path.display(), e.message())))?;
+
+    for pattern in file.overrides.iter().flat_map(|o| &o.files) {
+      Pattern::new(pattern).map_err(|e| MarkdownError::InvalidPattern {
+        pattern: pattern.clone(),
+        message: tr!("配置文件 {}: {}", path.display(), e),
+      })?;
+    }
+
+    Ok(Self { path: path.to_path_buf(), file })
+  }
+
+  /// 从文档所在目录逐级向上查找配置文件，超出沙箱根目录时停止查找
+  pub fn discover(full_file_path: &str, sandbox: &Sandbox) -> Result<Option<Self>> {
+    let Some(start) = Path::new(full_file_path).parent() else {
+      return Ok(None);
+    };
+
+    for dir in start.ancestors().filter(|dir| !dir.as_os_str().is_empty()) {
+      if sandbox.check_path(&dir.to_string_lossy()).is_err() {
+        break;
+      }
+      let candidate = dir.join(PROJECT_CONFIG_FILE);
+      if candidate.is_file() {
+        return Self::load(&candidate).map(Some);
+      }
+    }
+    Ok(None)
+  }
+
+  /// 计算对某个文档生效的设置：先取顶层设置，再按顺序应用匹配的覆盖设置
+  pub fn settings_for(&self, full_file_path: &str) -> EffectiveSettings {
+    let config_dir = self.path.parent().unwrap_or(Path::new("/"));
+    let relative = Path::new(full_file_path).strip_prefix(config_dir).unwrap_or(Path::new(full_file_path));
+    let options = MatchOptions { require_literal_separator: true, ..MatchOptions::new() };
+
+    let mut effective = EffectiveSettings { config_file: Some(self.path.clone()), ..Default::default() };
+    merge_sections(&mut effective.values, &self.file.numbering, &self.file.lint, &self.file.images);
+
+    for settings in &self.file.overrides {
+      let matched = settings
+        .files
+        .iter()
+        .filter_map(|pattern| Pattern::new(pattern).ok())
+        .any(|pattern| pattern.matches_path_with(relative, options));
+      if matched {
+        effective.matched_overrides.push(settings.files.clone());
+        merge_sections(&mut effective.values, &settings.numbering, &settings.lint, &settings.images);
+      }
+    }
+
+    effective
+  }
+}
+
+/// 查找对某个文档生效的项目设置
+pub fn effective_settings(full_file_path: &str, sandbox: &Sandbox) -> Result<EffectiveSettings> {
+  Ok(match ProjectConfig::discover(full_file_path, sandbox)? {
+    Some(config) => config.settings_for(full_file_path),
+    None => EffectiveSettings::default(),
+  })
+}
+
+/// 以项目配置作为默认值合并工具参数，调用时传入的参数优先
+///
+/// 只合并工具 `T` 支持的参数；文档路径取 full_file_path 或 full_file_paths 的第一个，
+/// 缺少文档路径时原样返回，由参数校验报告错误
+pub fn apply_project_config<T: JsonSchema>(
+  args: Option<&Map<String, Value>>,
+  sandbox: &Sandbox,
+) -> Result<Option<Map<String, Value>>> {
+  let Some(args) = args else {
+    return Ok(None);
+  };
+  let Some(full_file_path) = document_path(args) else {
+    return Ok(Some(args.clone()));
+  };
+
+  // 显式传入的 null 视为未提供，不覆盖项目设置
+  let mut merged = tool_defaults::<T>(&effective_settings(full_file_path, sandbox)?);
+  for (key, value) in args {
+    if !(value.is_null() && merged.contains_key(key)) {
+      merged.insert(key.clone(), value.clone());
+    }
+  }
+  Ok(Some(merged))
+}
+
+/// 项目设置中工具 `T` 支持的部分
+pub fn tool_defaults<T: JsonSchema>(settings: &EffectiveSettings) -> Map<String, Value> {
+  settings_for_args(settings, &tool_arg_names::<T>())
+}
+
+/// 项目设置中参数名属于 `names` 的部分
+pub fn settings_for_args(settings: &EffectiveSettings, names: &[String]) -> Map<String, Value> {
+  settings.values.iter().filter(|(key, _)| names.contains(key)).map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+/// 参数中的文档路径
+fn document_path(args: &Map<String, Value>) -> Option<&str> {
+  args
+    .get("full_file_path")
+    .and_then(|v| v.as_str())
+    .or_else(|| args.get("full_file_paths")?.as_array()?.first()?.as_str())
+    .filter(|path| !path.is_empty())
+}
+
+/// 将各部分设置合并到参数表中，已设置的值覆盖旧值
+fn merge_sections(
+  values: &mut Map<String, Value>,
+  numbering: &NumberingSettings,
+  lint: &LintSettings,
+  images: &ImageSettings,
+) {
+  let sections = [serde_json::to_value(numbering), serde_json::to_value(lint), serde_json::to_value(images)];
+  for section in sections.into_iter().flatten() {
+    if let Value::Object(section) = section {
+      values.extend(section);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::{CheckHeadingConfig, GenerateChapterConfig, LocalizeImagesConfig};
+  use serde_json::json;
+  use tempfile::TempDir;
+
+  const CONFIG: &str = r#"
+[numbering]
+use_chinese_number = true
+ignore_h1 = true
+
+[lint]
+max_heading_level = 4
+
+[images]
+image_dir = "{full_dir_of_original_file}/img/"
+max_image_size = 1024
+
+[[overrides]]
+files = ["en/**/*.md"]
+numbering = { use_chinese_number = false }
+
+[[overrides]]
+files = ["en/draft.md"]
+lint = { allow_skipped_levels = true }
+"#;
+
+  fn project() -> (TempDir, PathBuf) {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir_all(root.join("en/guide")).unwrap();
+    fs::write(root.join(PROJECT_CONFIG_FILE), CONFIG).unwrap();
+    (temp_dir, root)
+  }
+
+  /// 测试逐级向上查找配置文件
+  #[test]
+  fn test_discover() {
+    let (_temp_dir, root) = project();
+    let doc = root.join("en/guide/intro.md");
+
+    let config = ProjectConfig::discover(doc.to_str().unwrap(), &Sandbox::default()).unwrap().unwrap();
+    assert_eq!(config.path, root.join(PROJECT_CONFIG_FILE));
+
+    // 配置文件位于沙箱之外时不读取
+    let sandbox = Sandbox::new(&[root.join("en")], None);
+    assert!(ProjectConfig::discover(doc.to_str().unwrap(), &sandbox).unwrap().is_none());
+  }
+
+  /// 测试覆盖设置按顺序应用
+  #[test]
+  fn test_settings_for() {
+    let (_temp_dir, root) = project();
+    let config = ProjectConfig::load(&root.join(PROJECT_CONFIG_FILE)).unwrap();
+
+    let settings = config.settings_for(root.join("zh/a.md").to_str().unwrap());
+    assert!(settings.matched_overrides.is_empty());
+    assert_eq!(settings.values["use_chinese_number"], json!(true));
+
+    let settings = config.settings_for(root.join("en/guide/a.md").to_str().unwrap());
+    assert_eq!(settings.matched_overrides, vec![vec!["en/**/*.md".to_string()]]);
+    assert_eq!(settings.values["use_chinese_number"], json!(false));
+    assert_eq!(settings.values["ignore_h1"], json!(true));
+
+    let settings = config.settings_for(root.join("en/draft.md").to_str().unwrap());
+    assert_eq!(settings.matched_overrides.len(), 2);
+    assert_eq!(settings.values["allow_skipped_levels"], json!(true));
+  }
+
+  /// 测试合并工具参数，调用参数优先
+  #[test]
+  fn test_apply_project_config() {
+    let (_temp_dir, root) = project();
+    let doc = root.join("en/guide/a.md");
+    let args = json!({ "full_file_path": doc, "ignore_h1": false }).as_object().cloned();
+
+    let merged = apply_project_config::<GenerateChapterConfig>(args.as_ref(), &Sandbox::default()).unwrap().unwrap();
+    let config = GenerateChapterConfig::from_args(Some(&merged)).unwrap();
+    assert!(!config.ignore_h1);
+    assert!(!config.use_chinese_number);
+
+    // 只合并工具支持的参数
+    let args = json!({ "full_file_path": doc }).as_object().cloned();
+    let merged = apply_project_config::<CheckHeadingConfig>(args.as_ref(), &Sandbox::default()).unwrap().unwrap();
+    assert_eq!(CheckHeadingConfig::from_args(Some(&merged)).unwrap().max_heading_level, 4);
+
+    // 显式传入的 null 不覆盖项目设置
+    let null_args = json!({ "full_file_path": doc, "max_heading_level": null }).as_object().cloned();
+    let merged = apply_project_config::<CheckHeadingConfig>(null_args.as_ref(), &Sandbox::default()).unwrap().unwrap();
+    assert_eq!(CheckHeadingConfig::from_args(Some(&merged)).unwrap().max_heading_level, 4);
+
+    let merged = apply_project_config::<LocalizeImagesConfig>(args.as_ref(), &Sandbox::default()).unwrap().unwrap();
+    let config = LocalizeImagesConfig::from_args(Some(&merged)).unwrap();
+    assert_eq!(config.max_image_size, 1024);
+    assert_eq!(config.get_resolved_save_dir(), format!("{}/img/", root.join("en/guide").display()));
+  }
+
+  /// 测试配置文件中的错误
+  #[test]
+  fn test_invalid_config_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(PROJECT_CONFIG_FILE);
+
+    fs::write(&path, "[numbering]\nuse_roman_number = true\n").unwrap();
+    assert!(ProjectConfig::load(&path).unwrap_err().to_string().contains("use_roman_number"));
+
+    fs::write(&path, "[[overrides]]\nfiles = [\"[\"]\n").unwrap();
+    let error = ProjectConfig::load(&path).unwrap_err();
+    assert!(error.to_string().contains("无效"));
+    assert_eq!(error.code(), "invalid_pattern");
+  }
+}
@@ -0,0 +1,230 @@
+//! MCP 提示模板模块
+//!
+//! 为常见的文档处理流程提供预置提示，用户可以在助手的提示选择器中直接调用，
+//! 不必每次手动描述需要调用哪些工具、按什么顺序调用
+
+use crate::resources::{outline_uri, read_markdown_resource, section_uri};
+use crate::sandbox::Sandbox;
+use crate::tr;
+use rmcp::{model::*, ErrorData as McpError};
+
+/// 规范化标题
+pub const NORMALIZE_HEADINGS: &str = "normalize_headings";
+/// 发布前准备
+pub const PREPARE_FOR_PUBLISHING: &str = "prepare_for_publishing";
+/// 总结章节
+pub const SUMMARIZE_SECTION: &str = "summarize_section";
+
+/// 服务器提供的提示模板
+pub fn prompt_list() -> Vec<Prompt> {
+  let argument = |name: &str, description: &str, required: bool| PromptArgument {
+    name: name.to_string(),
+    description: Some(description.to_string()),
+    required: Some(required),
+  };
+  let file_path = || argument("full_file_path", tr!("Markdown 文件的绝对路径"), true);
+
+  vec![
+    Prompt::new(
+      NORMALIZE_HEADINGS,
+      Some(tr!("规范化文档标题：检查标题层级和编号，修复问题后重新生成统一的章节编号")),
+      Some(vec![
+        file_path(),
+        argument("use_chinese_number", tr!("一级标题是否使用中文编号（true/false），默认 false"), false),
+      ]),
+    ),
+    Prompt::new(
+      PREPARE_FOR_PUBLISHING,
+      Some(tr!("发布前准备：检查并修复标题、生成章节编号、本地化远程图片并插入目录")),
+      Some(vec![file_path(), argument("image_dir", tr!("图片保存目录，默认 ./assets/"), false)]),
+    ),
+    Prompt::new(
+      SUMMARIZE_SECTION,
+      Some(tr!("总结文档中的某个章节，章节原文作为资源附在提示中")),
+      Some(vec![
+        file_path(),
+        argument("anchor", tr!("章节标题的锚点，可从 markdown://outline/{path} 资源中获取"), true),
+      ]),
+    ),
+  ]
+}
+
+/// 按名称和参数生成提示内容，附带的资源按沙箱限制读取
+pub fn get_markdown_prompt(
+  name: &str,
+  arguments: Option<&JsonObject>,
+  sandbox: &Sandbox,
+) -> Result<GetPromptResult, McpError> {
+  if !prompt_list().iter().any(|prompt| prompt.name == name) {
+    return Err(McpError::invalid_params(tr!("未知的提示: {}", name), None));
+  }
+  let full_file_path = required_argument(arguments, "full_file_path")?;
+
+  match name {
+    NORMALIZE_HEADINGS => {
+      let use_chinese_number = optional_argument(arguments, "use_chinese_number").is_some_and(|v| v == "true");
+      let outline = embedded_resource(&outline_uri(&full_file_path), sandbox)?;
+      let instructions = tr!(
+        "请规范化文档 {0} 的标题，当前标题大纲见附带的资源。按以下步骤处理：\n\
+         1. 调用 check_heading 检查标题层级和编号问题；\n\
+         2. 如有问题（如跳级、# 后缺少空格），直接修改文档修复；\n\
+         3. 调用 remove_all_chapter_numbers 移除已有编号（不另存为新文件）；\n\
+         4. 调用 generate_chapter_number 重新生成编号，use_chinese_number 为 {1}，不另存为新文件；\n\
+         5. 再次调用 check_heading 确认没有问题，最后简要说明修改了哪些内容。",
+        full_file_path,
+        use_chinese_number
+      );
+      Ok(GetPromptResult {
+        description: Some(tr!("规范化 {} 的标题", full_file_path)),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, instructions), outline],
+      })
+    }
+    PREPARE_FOR_PUBLISHING => {
+      let image_dir = optional_argument(arguments, "image_dir").unwrap_or_else(|| "./assets/".to_string());
+      let instructions = tr!(
+        "请为发布准备文档 {0}，按以下步骤处理：\n\
+         1. 调用 check_heading 检查标题，如有问题直接修改文档修复；\n\
+         2. 调用 remove_all_chapter_numbers 和 generate_chapter_number 生成统一的章节编号（均不另存为新文件）；\n\
+         3. 调用 localize_images 将远程图片下载到 {1}，new_full_file_path 留空以覆盖原文件，并检查失败的图片；\n\
+         4. 读取资源 {2}，在第一个标题之前插入目录（指向各标题锚点的嵌套列表），已有目录时更新它；\n\
+         5. 汇总每一步的结果以及需要人工处理的问题。",
+        full_file_path,
+        image_dir,
+        outline_uri(&full_file_path)
+      );
+      Ok(GetPromptResult {
+        description: Some(tr!("为发布准备 {}", full_file_path)),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, instructions)],
+      })
+    }
+    SUMMARIZE_SECTION => {
+      let anchor = required_argument(arguments, "anchor")?;
+      let section = embedded_resource(&section_uri(&full_file_path, &anchor), sandbox)?;
+      let instructions = tr!(
+        "请总结文档 {} 中 #{} 章节的内容（原文见附带的资源）：先用一两句话概括要点，再列出关键信息，保持原文使用的语言。",
+        full_file_path,
+        anchor.trim_start_matches('#')
+      );
+      Ok(GetPromptResult {
+        description: Some(tr!("总结 {} 的 #{} 章节", full_file_path, anchor.trim_start_matches('#'))),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, instructions), section],
+      })
+    }
+    _ => unreachable!("提示名称已校验"),
+  }
+}
+
+/// 读取资源并作为消息附在提示中
+fn embedded_resource(uri: &str, sandbox: &Sandbox) -> Result<PromptMessage, McpError> {
+  let result = read_markdown_resource(uri, sandbox)?;
+  let Some(ResourceContents::TextResourceContents { uri, mime_type, text }) = result.contents.into_iter().next() else {
+    return Err(McpError::internal_error(tr!("资源 {} 没有文本内容", uri), None));
+  };
+  Ok(PromptMessage::new_resource(
+    PromptMessageRole::User,
+    uri,
+    mime_type.unwrap_or_else(|| "text/markdown".to_string()),
+    Some(text),
+    None,
+  ))
+}
+
+/// 读取必填的字符串参数
+fn required_argument(arguments: Option<&JsonObject>, name: &str) -> Result<String, McpError> {
+  optional_argument(arguments, name).ok_or_else(|| McpError::invalid_params(tr!("缺少 {} 参数", name), None))
+}
+
+/// 读取可选的字符串参数，空字符串视为未提供
+fn optional_argument(arguments: Option<&JsonObject>, name: &str) -> Option<String> {
+  let value = arguments?.get(name)?;
+  let value = match value {
+    serde_json::Value::String(s) => s.trim().to_string(),
+    other => other.to_string(),
+  };
+  Some(value).filter(|v| !v.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use tempfile::TempDir;
+
+  fn arguments(pairs: &[(&str, &str)]) -> JsonObject {
+    pairs.iter().map(|(k, v)| (k.to_string(), serde_json::Value::String(v.to_string()))).collect()
+  }
+
+  fn message_text(message: &PromptMessage) -> &str {
+    match &message.content {
+      PromptMessageContent::Text { text } => text,
+      PromptMessageContent::Resource { resource } => match &resource.resource {
+        ResourceContents::TextResourceContents { text, .. } => text,
+        ResourceContents::BlobResourceContents { .. } => panic!("应为文本资源"),
+      },
+      PromptMessageContent::Image { .. } => panic!("不应包含图片"),
+    }
+  }
+
+  /// 测试提示列表
+  #[test]
+  fn test_prompt_list() {
+    let prompts = prompt_list();
+    let names: Vec<&str> = prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec![NORMALIZE_HEADINGS, PREPARE_FOR_PUBLISHING, SUMMARIZE_SECTION]);
+    assert!(prompts.iter().all(|p| p.arguments.as_ref().unwrap()[0].name == "full_file_path"));
+  }
+
+  /// 测试生成提示内容
+  #[test]
+  fn test_get_markdown_prompt() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("doc.md");
+    fs::write(&md_file, "# 介绍\n\n## 背景\n\n背景内容\n\n# 结论\n").unwrap();
+    let path = md_file.to_str().unwrap();
+
+    let result = get_markdown_prompt(
+      NORMALIZE_HEADINGS,
+      Some(&arguments(&[("full_file_path", path), ("use_chinese_number", "true")])),
+      &Sandbox::default(),
+    )
+    .unwrap();
+    assert!(message_text(&result.messages[0]).contains("use_chinese_number 为 true"));
+    assert!(message_text(&result.messages[1]).contains("- 介绍 (#介绍, 第1行)"));
+
+    let result =
+      get_markdown_prompt(PREPARE_FOR_PUBLISHING, Some(&arguments(&[("full_file_path", path)])), &Sandbox::default())
+        .unwrap();
+    assert_eq!(result.messages.len(), 1);
+    assert!(message_text(&result.messages[0]).contains("./assets/"));
+
+    let result = get_markdown_prompt(
+      SUMMARIZE_SECTION,
+      Some(&arguments(&[("full_file_path", path), ("anchor", "#背景")])),
+      &Sandbox::default(),
+    )
+    .unwrap();
+    assert_eq!(message_text(&result.messages[1]), "## 背景\n\n背景内容");
+  }
+
+  /// 测试参数错误
+  #[test]
+  fn test_get_markdown_prompt_errors() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("doc.md");
+    fs::write(&md_file, "# 介绍\n").unwrap();
+    let path = md_file.to_str().unwrap();
+
+    assert!(get_markdown_prompt(NORMALIZE_HEADINGS, None, &Sandbox::default()).is_err());
+    assert!(get_markdown_prompt(SUMMARIZE_SECTION, Some(&arguments(&[("full_file_path", path)])), &Sandbox::default())
+      .is_err());
+    assert!(get_markdown_prompt("unknown", Some(&arguments(&[("full_file_path", path)])), &Sandbox::default()).is_err());
+
+    let err = get_markdown_prompt(
+      SUMMARIZE_SECTION,
+      Some(&arguments(&[("full_file_path", path), ("anchor", "不存在")])),
+      &Sandbox::default(),
+    )
+    .unwrap_err();
+    assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+  }
+}
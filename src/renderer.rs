@@ -2,7 +2,8 @@
 //!
 //! 将 MST 结构渲染回 Markdown 文本
 
-use crate::mst::{MSTNode, NodeType};
+use crate::mst::{ImageType, LinkDefinition, MSTNode, NodeType};
+use std::collections::HashMap;
 
 /// Markdown 渲染器
 pub struct MarkdownRenderer;
@@ -21,6 +22,45 @@ impl MarkdownRenderer {
     result.join("\n")
   }
 
+  /// 将 MST 中图片、链接引用定义和行内图片的改动写回原文
+  ///
+  /// 只替换这些节点所在的行，标题和其余各行逐字节保持原样，避免重新渲染整篇文档时丢失编号和空白
+  pub fn render_into(&self, original: &str, mst: &MSTNode) -> String {
+    let lines: Vec<&str> = original.lines().collect();
+    let mut replacements = HashMap::new();
+    self.collect_line_changes(mst, &lines, &mut replacements);
+
+    original
+      .split_inclusive('\n')
+      .enumerate()
+      .map(|(i, line)| {
+        let text = line.trim_end_matches(['\n', '\r']);
+        match replacements.get(&(i + 1)) {
+          Some(new_text) => format!("{}{}", new_text, &line[text.len()..]),
+          None => line.to_string(),
+        }
+      })
+      .collect()
+  }
+
+  /// 收集改动过的行，键为行号
+  fn collect_line_changes(&self, node: &MSTNode, lines: &[&str], replacements: &mut HashMap<usize, String>) {
+    let line = node.line_number.checked_sub(1).and_then(|i| lines.get(i)).copied().unwrap_or_default();
+    let new_text = match &node.node_type {
+      NodeType::Content(content) => Some(content.clone()),
+      NodeType::Image(_) => Some(line.replacen(&node.raw, &self.render_image(node), 1)),
+      NodeType::LinkDefinition(definition) => Some(self.render_link_definition(definition)),
+      NodeType::Root | NodeType::Header(_) => None,
+    };
+    if let Some(new_text) = new_text.filter(|new_text| new_text != line) {
+      replacements.insert(node.line_number, new_text);
+    }
+
+    for child in &node.children {
+      self.collect_line_changes(child, lines, replacements);
+    }
+  }
+
   /// 渲染单个节点
   fn render_node(&self, node: &MSTNode, result: &mut Vec<String>) {
     match &node.node_type {
@@ -52,42 +92,11 @@ impl MarkdownRenderer {
       NodeType::Content(content) => {
         result.push(content.clone());
       }
-      NodeType::Image(image_info) => {
-        // 渲染图片，优先使用本地路径
-        let url = image_info.local_path.as_ref().unwrap_or(&image_info.original_url);
-
-        match image_info.image_type {
-          crate::mst::ImageType::Markdown => {
-            let title_part = image_info.title.as_ref().map(|t| format!(" \"{}\"", t)).unwrap_or_default();
-            result.push(format!("![{}]({}{})", image_info.alt_text, url, title_part));
-          }
-          crate::mst::ImageType::Html => {
-            // 渲染 HTML img 标签，保留原有属性
-            let mut img_tag = String::from("<img");
-
-            // 添加前置属性
-            if let Some(attrs) = &image_info.html_attributes {
-              if !attrs.is_empty() {
-                img_tag.push(' ');
-                img_tag.push_str(attrs);
-              }
-            }
-
-            // 添加 src 属性
-            img_tag.push_str(&format!(" src=\"{}\"", url));
-
-            // 如果有 alt 文本且不在现有属性中，添加 alt 属性
-            if !image_info.alt_text.is_empty() {
-              let attrs_str = image_info.html_attributes.as_deref().unwrap_or("");
-              if !attrs_str.contains("alt=") {
-                img_tag.push_str(&format!(" alt=\"{}\"", image_info.alt_text));
-              }
-            }
-
-            img_tag.push('>');
-            result.push(img_tag);
-          }
-        }
+      NodeType::Image(_) => {
+        result.push(self.render_image(node));
+      }
+      NodeType::LinkDefinition(definition) => {
+        result.push(self.render_link_definition(definition));
       }
     }
   }
@@ -128,45 +137,77 @@ impl MarkdownRenderer {
       NodeType::Content(content) => {
         result.push(content.clone());
       }
-      NodeType::Image(image_info) => {
-        // 渲染图片，优先使用本地路径
-        let url = image_info.local_path.as_ref().unwrap_or(&image_info.original_url);
-
-        match image_info.image_type {
-          crate::mst::ImageType::Markdown => {
-            let title_part = image_info.title.as_ref().map(|t| format!(" \"{}\"", t)).unwrap_or_default();
-            result.push(format!("![{}]({}{})", image_info.alt_text, url, title_part));
+      NodeType::Image(_) => {
+        result.push(self.render_image(node));
+      }
+      NodeType::LinkDefinition(definition) => {
+        result.push(self.render_link_definition(definition));
+      }
+    }
+  }
+
+  /// 渲染图片节点，优先使用本地路径
+  ///
+  /// 解析得到的图片只替换原文中的地址，空白、属性顺序和自闭合写法保持原样，地址未改变时输出原文
+  pub fn render_image(&self, node: &MSTNode) -> String {
+    let image_info = match node.get_image_info() {
+      Some(image_info) => image_info,
+      None => return node.raw.clone(),
+    };
+    let url = image_info.current_url();
+    if let Some(range) = image_info.url_range.clone().filter(|range| node.raw.get(range.clone()).is_some()) {
+      return format!("{}{}{}", &node.raw[..range.start], url, &node.raw[range.end..]);
+    }
+
+    match image_info.image_type {
+      ImageType::Markdown => {
+        let title_part =
+          image_info.title.as_ref().map(|t| format!(" {}", image_info.title_delimiter.wrap(t))).unwrap_or_default();
+        format!("![{}]({}{})", image_info.alt_text, url, title_part)
+      }
+      ImageType::Html => {
+        // 渲染 HTML img 标签，保留原有属性
+        let mut img_tag = String::from("<img");
+
+        // 添加前置属性
+        if let Some(attrs) = &image_info.html_attributes {
+          if !attrs.is_empty() {
+            img_tag.push(' ');
+            img_tag.push_str(attrs);
           }
-          crate::mst::ImageType::Html => {
-            // 渲染 HTML img 标签，保留原有属性
-            let mut img_tag = String::from("<img");
-
-            // 添加前置属性
-            if let Some(attrs) = &image_info.html_attributes {
-              if !attrs.is_empty() {
-                img_tag.push(' ');
-                img_tag.push_str(attrs);
-              }
-            }
-
-            // 添加 src 属性
-            img_tag.push_str(&format!(" src=\"{}\"", url));
-
-            // 如果有 alt 文本且不在现有属性中，添加 alt 属性
-            if !image_info.alt_text.is_empty() {
-              let attrs_str = image_info.html_attributes.as_deref().unwrap_or("");
-              if !attrs_str.contains("alt=") {
-                img_tag.push_str(&format!(" alt=\"{}\"", image_info.alt_text));
-              }
-            }
-
-            img_tag.push('>');
-            result.push(img_tag);
+        }
+
+        // 添加 src 属性
+        img_tag.push_str(&format!(" src=\"{}\"", url));
+
+        // 如果有 alt 文本且不在现有属性中，添加 alt 属性
+        if !image_info.alt_text.is_empty() {
+          let attrs_str = image_info.html_attributes.as_deref().unwrap_or("");
+          if !attrs_str.contains("alt=") {
+            img_tag.push_str(&format!(" alt=\"{}\"", image_info.alt_text));
           }
         }
+
+        img_tag.push('>');
+        img_tag
       }
+      // 引用式图片不包含 URL，本地化时改写的是对应的链接引用定义，这里原样输出
+      ImageType::Reference => node.raw.clone(),
     }
   }
+
+  /// 渲染链接引用定义，优先使用本地路径
+  ///
+  /// 解析得到的定义只替换原文中的地址，缩进、尖括号和标题保持原样，地址未改变时输出原文
+  pub fn render_link_definition(&self, definition: &LinkDefinition) -> String {
+    let url = definition.current_url();
+    if let Some(range) = definition.url_range.clone().filter(|range| definition.raw.get(range.clone()).is_some()) {
+      return format!("{}{}{}", &definition.raw[..range.start], url, &definition.raw[range.end..]);
+    }
+    let title_part =
+      definition.title.as_ref().map(|t| format!(" {}", definition.title_delimiter.wrap(t))).unwrap_or_default();
+    format!("[{}]: {}{}", definition.label, url, title_part)
+  }
 }
 
 impl Default for MarkdownRenderer {
@@ -178,7 +219,7 @@ impl Default for MarkdownRenderer {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::mst::{MSTNode, NumberingInfo};
+  use crate::mst::{ImageInfo, MSTNode, NumberingInfo, TitleDelimiter};
 
   fn create_test_mst_with_numbering() -> MSTNode {
     let mut root = MSTNode::new_root();
@@ -317,4 +358,96 @@ mod tests {
 
     assert_eq!(result, expected);
   }
+
+  #[test]
+  fn test_render_image_keeps_title_delimiter() {
+    let renderer = MarkdownRenderer::new();
+    let image_info = ImageInfo {
+      image_type: ImageType::Markdown,
+      original_url: "https://example.com/a.png".to_string(),
+      local_path: Some("assets/1.png".to_string()),
+      alt_text: "图".to_string(),
+      title: Some("It's (here)".to_string()),
+      html_attributes: None,
+      title_delimiter: TitleDelimiter::DoubleQuote,
+      reference_label: None,
+      url_range: None,
+    };
+    let mut node = MSTNode::new_image(image_info, String::new(), 1);
+    assert_eq!(renderer.render_image(&node), "![图](assets/1.png \"It's (here)\")");
+
+    node.get_image_info_mut().unwrap().title_delimiter = TitleDelimiter::Parentheses;
+    node.get_image_info_mut().unwrap().title = Some("标题".to_string());
+    assert_eq!(renderer.render_image(&node), "![图](assets/1.png (标题))");
+  }
+
+  /// 测试解析得到的图片原样输出，只替换地址
+  #[test]
+  fn test_render_parsed_image_keeps_raw() {
+    let parser = crate::parser::MarkdownParser::new().unwrap();
+    let renderer = MarkdownRenderer::new();
+    let line = "<img src=\"a.png\" width=\"100\" /> ![a](  b.png  'B')";
+    let mut images = parser.parse_images_in_line(line, 1);
+    assert_eq!(renderer.render_image(&images[0]), "<img src=\"a.png\" width=\"100\" />");
+    assert_eq!(renderer.render_image(&images[1]), "![a](  b.png  'B')");
+
+    images[0].get_image_info_mut().unwrap().local_path = Some("assets/1.png".to_string());
+    images[1].get_image_info_mut().unwrap().original_url = "https://cdn.example.com/b.png".to_string();
+    assert_eq!(renderer.render_image(&images[0]), "<img src=\"assets/1.png\" width=\"100\" />");
+    assert_eq!(renderer.render_image(&images[1]), "![a](  https://cdn.example.com/b.png  'B')");
+  }
+
+  /// 测试只把改动过的图片和链接引用定义写回原文，标题编号、空白和换行符保持原样
+  #[test]
+  fn test_render_into_keeps_other_lines() {
+    let parser = crate::parser::MarkdownParser::new().unwrap();
+    let renderer = MarkdownRenderer::new();
+    let original =
+      "# 1. Intro\r\n\r\n##   2024 Roadmap\r\n\r\n  ![a](a.png)\r\n文字 ![b](b.png) 文字\r\n[c]: c.png\r\n![d](d.png)";
+    let mut mst = parser.parse(original).unwrap();
+    assert_eq!(renderer.render_into(original, &mst), original);
+
+    fn relocate(node: &mut MSTNode) {
+      if let Some(info) = node.get_image_info_mut().filter(|info| info.original_url != "d.png") {
+        info.local_path = Some(format!("assets/{}", info.original_url));
+      }
+      if let Some(definition) = node.get_link_definition_mut() {
+        definition.local_path = Some("assets/c.png".to_string());
+      }
+      if let NodeType::Content(content) = &mut node.node_type {
+        *content = content.replace("(b.png)", "(assets/b.png)");
+      }
+      node.children.iter_mut().for_each(relocate);
+    }
+    relocate(&mut mst);
+    assert_eq!(
+      renderer.render_into(original, &mst),
+      "# 1. Intro\r\n\r\n##   2024 Roadmap\r\n\r\n  ![a](assets/a.png)\r\n文字 ![b](assets/b.png) 文字\r\n[c]: assets/c.png\r\n![d](d.png)"
+    );
+  }
+
+  #[test]
+  fn test_render_link_definition() {
+    let renderer = MarkdownRenderer::new();
+    let mut definition = LinkDefinition {
+      label: "Logo".to_string(),
+      url: "https://example.com/logo.png".to_string(),
+      local_path: None,
+      title: Some("Logo".to_string()),
+      title_delimiter: TitleDelimiter::SingleQuote,
+      raw: String::new(),
+      url_range: None,
+    };
+    assert_eq!(renderer.render_link_definition(&definition), "[Logo]: https://example.com/logo.png 'Logo'");
+
+    definition.local_path = Some("assets/1.png".to_string());
+    assert_eq!(renderer.render_link_definition(&definition), "[Logo]: assets/1.png 'Logo'");
+
+    // 解析得到的定义保留缩进和尖括号
+    let parser = crate::parser::MarkdownParser::new().unwrap();
+    let mut definition = parser.parse_link_definition("  [Logo]:   <logo.png>  \"Logo\"").unwrap();
+    assert_eq!(renderer.render_link_definition(&definition), "  [Logo]:   <logo.png>  \"Logo\"");
+    definition.local_path = Some("assets/1.png".to_string());
+    assert_eq!(renderer.render_link_definition(&definition), "  [Logo]:   <assets/1.png>  \"Logo\"");
+  }
 }
@@ -0,0 +1,299 @@
+//! MCP 资源模块
+//!
+//! 将文档以只读资源的形式暴露给客户端，不必调用工具即可读取大纲、章节和图片清单：
+//! - `markdown://outline/{path}`：标题大纲
+//! - `markdown://section/{path}#{anchor}`：按锚点截取的章节原文
+//! - `markdown://images/{path}`：图片清单（JSON）
+
+use crate::image_localizer::{collect_document_images, display_url, resolve_local_image_path};
+use crate::mst::{ImageSource, ImageType, MSTNode};
+use crate::outline::{build_outline, find_section, section_text, OutlineEntry};
+use crate::parser::MarkdownParser;
+use crate::sandbox::Sandbox;
+use crate::tr;
+use crate::utils::validate_markdown_file;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
+use rmcp::{model::*, ErrorData as McpError};
+use std::fs;
+use std::path::Path;
+
+const OUTLINE_PREFIX: &str = "markdown://outline/";
+const SECTION_PREFIX: &str = "markdown://section/";
+const IMAGES_PREFIX: &str = "markdown://images/";
+
+/// 资源 URI 中需要编码的字符，/ 保持原样
+const PATH_ENCODE_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'"').add(b'#').add(b'%').add(b'<').add(b'>').add(b'?');
+
+/// 资源 URI 解析结果
+#[derive(Debug, Clone, PartialEq)]
+enum ResourceUri {
+  Outline(String),
+  Section(String, String),
+  Images(String),
+}
+
+/// 服务器提供的资源模板
+pub fn resource_templates() -> Vec<ResourceTemplate> {
+  let template = |uri_template: &str, name: &str, description: &str, mime_type: &str| {
+    RawResourceTemplate {
+      uri_template: uri_template.to_string(),
+      name: name.to_string(),
+      description: Some(description.to_string()),
+      mime_type: Some(mime_type.to_string()),
+    }
+    .no_annotation()
+  };
+
+  vec![
+    template(
+      "markdown://outline/{path}",
+      "outline",
+      tr!("文档的标题大纲，包含每个标题的锚点和行号。path 为 Markdown 文件的绝对路径"),
+      "text/markdown",
+    ),
+    template(
+      "markdown://section/{path}#{anchor}",
+      "section",
+      tr!("按标题锚点截取的章节原文，包含其下所有子章节。锚点可从大纲资源中获取"),
+      "text/markdown",
+    ),
+    template(
+      "markdown://images/{path}",
+      "images",
+      tr!("文档中所有图片的清单（JSON），包含行号、地址、来源以及本地图片是否存在"),
+      "application/json",
+    ),
+  ]
+}
+
+/// 文档大纲资源的 URI
+pub fn outline_uri(path: &str) -> String {
+  format!("{}{}", OUTLINE_PREFIX, utf8_percent_encode(path, PATH_ENCODE_SET))
+}
+
+/// 文档章节资源的 URI
+pub fn section_uri(path: &str, anchor: &str) -> String {
+  format!(
+    "{}{}#{}",
+    SECTION_PREFIX,
+    utf8_percent_encode(path, PATH_ENCODE_SET),
+    utf8_percent_encode(anchor.trim_start_matches('#'), PATH_ENCODE_SET)
+  )
+}
+
+/// 读取资源内容，文件必须位于沙箱允许的根目录内
+pub fn read_markdown_resource(uri: &str, sandbox: &Sandbox) -> Result<ReadResourceResult, McpError> {
+  let resource = parse_resource_uri(uri)?;
+  let (ResourceUri::Outline(path) | ResourceUri::Section(path, _) | ResourceUri::Images(path)) = &resource;
+
+  let resolved = sandbox.check_path(path)?.to_string_lossy().into_owned();
+  validate_markdown_file(&resolved).map_err(|e| McpError::resource_not_found(e.to_string(), None))?;
+  let content =
+    fs::read_to_string(&resolved).map_err(|e| McpError::internal_error(tr!("读取文件失败: {}", e), None))?;
+  let parser = MarkdownParser::new().map_err(|e| McpError::internal_error(tr!("创建解析器失败: {}", e), None))?;
+  let mst = parser.parse(&content).map_err(|e| McpError::internal_error(tr!("解析失败: {}", e), None))?;
+  let outline = build_outline(&mst, content.lines().count());
+
+  let (mime_type, text) = match &resource {
+    ResourceUri::Outline(_) => ("text/markdown", render_outline(&outline)),
+    ResourceUri::Section(path, anchor) => {
+      let entry = find_section(&outline, anchor)
+        .ok_or_else(|| McpError::resource_not_found(tr!("{} 中没有锚点为 #{} 的标题", path, anchor), None))?;
+      ("text/markdown", section_text(&content, entry))
+    }
+    ResourceUri::Images(path) => ("application/json", image_inventory(&parser, &mst, path)),
+  };
+
+  Ok(ReadResourceResult {
+    contents: vec![ResourceContents::TextResourceContents {
+      uri: uri.to_string(),
+      mime_type: Some(mime_type.to_string()),
+      text,
+    }],
+  })
+}
+
+/// 解析资源 URI，路径部分按百分号编码解码，缺少前导 / 时补上
+fn parse_resource_uri(uri: &str) -> Result<ResourceUri, McpError> {
+  let decode = |path: &str| -> Result<String, McpError> {
+    let path = percent_decode_str(path)
+      .decode_utf8()
+      .map_err(|_| McpError::invalid_params(tr!("资源路径不是有效的 UTF-8: {}", uri), None))?;
+    if path.is_empty() {
+      return Err(McpError::invalid_params(tr!("资源 URI 缺少文件路径: {}", uri), None));
+    }
+    Ok(if path.starts_with('/') { path.into_owned() } else { format!("/{}", path) })
+  };
+
+  if let Some(path) = uri.strip_prefix(OUTLINE_PREFIX) {
+    return Ok(ResourceUri::Outline(decode(path)?));
+  }
+  if let Some(path) = uri.strip_prefix(IMAGES_PREFIX) {
+    return Ok(ResourceUri::Images(decode(path)?));
+  }
+  if let Some(rest) = uri.strip_prefix(SECTION_PREFIX) {
+    // 锚点以最后一个 # 分隔，文件路径中的 # 需要编码为 %23
+    let (path, anchor) = rest
+      .rsplit_once('#')
+      .filter(|(_, anchor)| !anchor.is_empty())
+      .ok_or_else(|| McpError::invalid_params(tr!("章节资源 URI 缺少锚点: {}", uri), None))?;
+    let anchor = percent_decode_str(anchor).decode_utf8_lossy().into_owned();
+    return Ok(ResourceUri::Section(decode(path)?, anchor));
+  }
+
+  Err(McpError::resource_not_found(tr!("未知的资源 URI: {}", uri), None))
+}
+
+/// 将大纲渲染为按级别缩进的 Markdown 列表
+fn render_outline(outline: &[OutlineEntry]) -> String {
+  if outline.is_empty() {
+    return tr!("（文档中没有标题）").to_string();
+  }
+
+  let min_level = outline.iter().map(|entry| entry.level).min().unwrap_or(1);
+  outline
+    .iter()
+    .map(|entry| {
+      tr!("{}- {} (#{}, 第{}行)", "  ".repeat(entry.level - min_level), entry.text, entry.anchor, entry.line_number)
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// 生成图片清单 JSON
+fn image_inventory(parser: &MarkdownParser, mst: &MSTNode, path: &str) -> String {
+  let md_dir = Path::new(path).parent().unwrap_or(Path::new("/"));
+
+  let images: Vec<serde_json::Value> = collect_document_images(parser, mst)
+    .iter()
+    .filter_map(|image| {
+      let info = image.get_image_info()?;
+      let url = info.current_url();
+      let source = ImageSource::from_url(url);
+
+      let mut entry = serde_json::json!({
+        "line": image.line_number,
+        "alt": info.alt_text,
+        "url": url,
+        "type": match info.image_type {
+          ImageType::Markdown => "markdown",
+          ImageType::Html => "html",
+          ImageType::Reference => "reference",
+        },
+        "source": match source {
+          ImageSource::Remote => "remote",
+          ImageSource::DataUri => "data_uri",
+          ImageSource::Local => "local",
+        },
+        "title": info.title,
+        "reference_label": info.reference_label,
+      });
+      // data URI 内容可能很长，清单中只保留前缀
+      if source == ImageSource::DataUri {
+        entry["url"] = serde_json::Value::String(display_url(url));
+      }
+      if source == ImageSource::Local && !url.is_empty() {
+        let exists = matches!(resolve_local_image_path(md_dir, url), Ok(Some(path)) if path.is_file());
+        entry["exists"] = serde_json::Value::Bool(exists);
+      }
+      Some(entry)
+    })
+    .collect();
+
+  serde_json::to_string_pretty(&serde_json::json!({ "file": path, "count": images.len(), "images": images }))
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tempfile::TempDir;
+
+  fn resource_text(result: &ReadResourceResult) -> &str {
+    match &result.contents[0] {
+      ResourceContents::TextResourceContents { text, .. } => text,
+      ResourceContents::BlobResourceContents { .. } => panic!("应返回文本资源"),
+    }
+  }
+
+  /// 测试资源 URI 解析
+  #[test]
+  fn test_parse_resource_uri() {
+    assert_eq!(
+      parse_resource_uri("markdown://outline//tmp/a%20b.md").unwrap(),
+      ResourceUri::Outline("/tmp/a b.md".to_string())
+    );
+    assert_eq!(
+      parse_resource_uri("markdown://images/tmp/doc.md").unwrap(),
+      ResourceUri::Images("/tmp/doc.md".to_string())
+    );
+    assert_eq!(
+      parse_resource_uri("markdown://section//tmp/c%23.md#%E8%83%8C%E6%99%AF").unwrap(),
+      ResourceUri::Section("/tmp/c#.md".to_string(), "背景".to_string())
+    );
+    assert!(parse_resource_uri("markdown://section//tmp/doc.md").is_err());
+    assert!(parse_resource_uri("markdown://outline/").is_err());
+    assert!(parse_resource_uri("file:///tmp/doc.md").is_err());
+
+    // 构造的 URI 可以解析回原路径和锚点
+    assert_eq!(
+      parse_resource_uri(&section_uri("/tmp/my docs/c#.md", "#背景-1")).unwrap(),
+      ResourceUri::Section("/tmp/my docs/c#.md".to_string(), "背景-1".to_string())
+    );
+    assert_eq!(
+      parse_resource_uri(&outline_uri("/tmp/a%b.md")).unwrap(),
+      ResourceUri::Outline("/tmp/a%b.md".to_string())
+    );
+  }
+
+  /// 测试读取大纲、章节和图片清单
+  #[test]
+  fn test_read_markdown_resource() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir_all(temp_dir.path().join("assets")).unwrap();
+    fs::write(temp_dir.path().join("assets/a.png"), b"png").unwrap();
+    let md_file = temp_dir.path().join("doc.md");
+    fs::write(
+      &md_file,
+      "# 介绍\n\n## 背景\n\n![本地](assets/a.png)\n\n文字 ![缺失](assets/b.png)\n\n# 远程\n\n![远程](https://example.com/c.png)\n",
+    )
+    .unwrap();
+    let path = md_file.to_str().unwrap();
+
+    let outline = read_markdown_resource(&format!("markdown://outline/{}", path), &Sandbox::default()).unwrap();
+    assert_eq!(resource_text(&outline), "- 介绍 (#介绍, 第1行)\n  - 背景 (#背景, 第3行)\n- 远程 (#远程, 第9行)");
+
+    let section = read_markdown_resource(&format!("markdown://section/{}#背景", path), &Sandbox::default()).unwrap();
+    assert_eq!(resource_text(&section), "## 背景\n\n![本地](assets/a.png)\n\n文字 ![缺失](assets/b.png)");
+
+    let images = read_markdown_resource(&format!("markdown://images/{}", path), &Sandbox::default()).unwrap();
+    let json: serde_json::Value = serde_json::from_str(resource_text(&images)).unwrap();
+    assert_eq!(json["count"], 3);
+    assert_eq!(json["images"][0]["exists"], true);
+    assert_eq!(json["images"][1]["exists"], false);
+    assert_eq!(json["images"][2]["source"], "remote");
+    assert!(json["images"][2].get("exists").is_none());
+  }
+
+  /// 测试不存在的文件和锚点
+  #[test]
+  fn test_read_missing_resource() {
+    let temp_dir = TempDir::new().unwrap();
+    let md_file = temp_dir.path().join("doc.md");
+    fs::write(&md_file, "# 标题\n").unwrap();
+
+    let err = read_markdown_resource(&format!("markdown://section/{}#不存在", md_file.display()), &Sandbox::default())
+      .unwrap_err();
+    assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+    let err = read_markdown_resource("markdown://outline//nonexistent/doc.md", &Sandbox::default()).unwrap_err();
+    assert_eq!(err.code, ErrorCode::RESOURCE_NOT_FOUND);
+
+    // 沙箱之外的文件
+    let other_dir = TempDir::new().unwrap();
+    let sandbox = Sandbox::new(&[other_dir.path().canonicalize().unwrap()], None);
+    let err = read_markdown_resource(&format!("markdown://outline/{}", md_file.display()), &sandbox).unwrap_err();
+    assert_eq!(err.code, ErrorCode::INVALID_PARAMS);
+    assert!(err.message.contains("不在允许访问的根目录内"));
+  }
+}
@@ -0,0 +1,295 @@
+//! 工作区根目录沙箱模块
+//!
+//! 限制工具和资源只能读写允许的根目录内的文件。允许的根目录来自两处：
+//! - 启动参数 `--allow-dir`（可多次指定）
+//! - 客户端通过 MCP `roots` 声明的根目录（仅 `file://` URI）
+//!
+//! 同时存在时取两者的交集，客户端的根目录只能缩小 `--allow-dir` 的范围；
+//! 两者都没有时不做限制。客户端声明了根目录但没有一个可用时（非 `file://` 或无法解析），拒绝所有访问。
+//! 所有路径都在解析符号链接后再比较。
+
+use crate::error::{MarkdownError, Result};
+use crate::tr;
+use percent_encoding::percent_decode_str;
+use rmcp::model::Root;
+use std::path::{Component, Path, PathBuf};
+
+/// 允许访问的根目录
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sandbox {
+  /// 为 None 时不限制访问
+  roots: Option<Vec<PathBuf>>,
+}
+
+impl Sandbox {
+  /// 由启动参数指定的目录和客户端声明的根目录创建沙箱
+  ///
+  /// `allow_dirs` 应已通过 [`canonicalize_allow_dir`] 规范化；
+  /// 客户端不支持 roots 时传入 None
+  pub fn new(allow_dirs: &[PathBuf], client_roots: Option<&[Root]>) -> Self {
+    let declared = client_roots.unwrap_or_default();
+    let client_roots: Vec<PathBuf> = declared
+      .iter()
+      .filter_map(|root| root_uri_to_path(&root.uri))
+      .filter_map(|path| canonicalize_lenient(&path).ok())
+      .collect();
+    if !declared.is_empty() && client_roots.is_empty() {
+      return Self::deny_all();
+    }
+
+    let roots = match (allow_dirs.is_empty(), client_roots.is_empty()) {
+      (true, true) => None,
+      (true, false) => Some(client_roots),
+      (false, true) => Some(allow_dirs.to_vec()),
+      (false, false) => {
+        // 取交集：一个根目录位于另一个之内时保留较小的那个
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for client_root in &client_roots {
+          for allow_dir in allow_dirs {
+            let inner = match (client_root.starts_with(allow_dir), allow_dir.starts_with(client_root)) {
+              (true, _) => client_root,
+              (false, true) => allow_dir,
+              (false, false) => continue,
+            };
+            if !roots.contains(inner) {
+              roots.push(inner.clone());
+            }
+          }
+        }
+        Some(roots)
+      }
+    };
+
+    Self { roots }
+  }
+
+  /// 拒绝所有访问的沙箱
+  fn deny_all() -> Self {
+    Self { roots: Some(Vec::new()) }
+  }
+
+  /// 是否限制了访问范围
+  #[cfg(test)]
+  fn is_restricted(&self) -> bool {
+    self.roots.is_some()
+  }
+
+  /// 校验路径位于允许的根目录内，返回解析符号链接后的路径，调用方应使用返回的路径读写文件
+  ///
+  /// 路径不存在时（如待创建的新文件或图片目录），按其最近的已存在上级目录解析
+  pub fn check_path(&self, path: &str) -> Result<PathBuf> {
+    let resolved = canonicalize_lenient(Path::new(path)).map_err(|e| MarkdownError::io(path, e))?;
+
+    let Some(roots) = &self.roots else {
+      return Ok(resolved);
+    };
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+      return Ok(resolved);
+    }
+
+    Err(MarkdownError::PathOutsideRoot { path: path.to_string(), resolved, roots: roots.clone() })
+  }
+
+  /// 校验路径并将其替换为解析符号链接后的路径，之后读写的就是校验过的文件
+  pub fn confine(&self, path: &mut String) -> Result<()> {
+    *path = self.check_path(path)?.to_string_lossy().into_owned();
+    Ok(())
+  }
+
+  /// 依次校验多个路径，遇到第一个越界的路径时返回错误
+  pub fn check_paths<I, S>(&self, paths: I) -> Result<()>
+  where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+  {
+    for path in paths {
+      self.check_path(path.as_ref())?;
+    }
+    Ok(())
+  }
+}
+
+/// 规范化 `--allow-dir` 指定的目录，目录必须存在
+pub fn canonicalize_allow_dir(dir: &str) -> Result<PathBuf> {
+  let path =
+    Path::new(dir).canonicalize().map_err(|e| MarkdownError::ConfigError(tr!("无法访问允许的目录 {}: {}", dir, e)))?;
+  if !path.is_dir() {
+    return Err(MarkdownError::ConfigError(tr!("允许的目录不是目录: {}", dir)));
+  }
+  Ok(path)
+}
+
+/// 将 `file://` 根目录 URI 转换为本地路径，其他协议返回 None
+fn root_uri_to_path(uri: &str) -> Option<PathBuf> {
+  let rest = uri.strip_prefix("file://")?;
+  // 忽略主机名部分（file://localhost/path）
+  let path = &rest[rest.find('/')?..];
+  let path = percent_decode_str(path).decode_utf8().ok()?;
+  Some(PathBuf::from(path.as_ref()))
+}
+
+/// 解析符号链接后的绝对路径，允许路径末尾的若干级尚不存在
+///
+/// 规范化最长的已存在前缀，再拼接剩余部分。`..` 不按字面处理，而是交给文件系统在解析符号链接之后处理，
+/// 因此 `link/../a.md` 指向的是符号链接目标的上级目录；不存在的部分中的 `..` 只能抵消其前面不存在的部分
+fn canonicalize_lenient(path: &Path) -> std::io::Result<PathBuf> {
+  let absolute = if path.is_absolute() { path.to_path_buf() } else { std::env::current_dir()?.join(path) };
+  let components: Vec<Component> = absolute.components().collect();
+
+  let mut split = components.len();
+  let mut existing: PathBuf = components.iter().collect();
+  while split > 1 && !existing.exists() {
+    split -= 1;
+    existing = components[..split].iter().collect();
+  }
+
+  let mut resolved = existing.canonicalize()?;
+  let mut appended = 0;
+  for component in &components[split..] {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir if appended > 0 => {
+        resolved.pop();
+        appended -= 1;
+      }
+      Component::ParentDir => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::InvalidInput,
+          tr!("路径中的 .. 无法在已存在的目录之上解析"),
+        ));
+      }
+      other => {
+        resolved.push(other);
+        appended += 1;
+      }
+    }
+  }
+  Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::fs;
+  use tempfile::TempDir;
+
+  fn root(path: &Path) -> Root {
+    Root { uri: format!("file://{}", path.display()), name: None }
+  }
+
+  /// 测试未配置根目录时不限制访问
+  #[test]
+  fn test_unrestricted_sandbox() {
+    let sandbox = Sandbox::new(&[], None);
+    assert!(!sandbox.is_restricted());
+    assert!(sandbox.check_path("/etc/hosts").is_ok());
+    assert_eq!(Sandbox::new(&[], Some(&[])), Sandbox::default());
+  }
+
+  /// 测试根目录内外的路径，包括尚不存在的路径和 .. 跳出
+  #[test]
+  fn test_check_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let allowed = canonicalize_allow_dir(temp_dir.path().to_str().unwrap()).unwrap();
+    let sandbox = Sandbox::new(std::slice::from_ref(&allowed), None);
+
+    assert!(sandbox.check_path(&format!("{}/doc.md", allowed.display())).is_ok());
+    assert!(sandbox.check_path(&format!("{}/new/assets/", allowed.display())).is_ok());
+
+    let err = sandbox.check_path(&format!("{}/../outside.md", allowed.display())).unwrap_err();
+    assert!(err.to_string().contains("不在允许访问的根目录内"));
+    assert!(matches!(err, MarkdownError::PathOutsideRoot { ref roots, .. } if roots == std::slice::from_ref(&allowed)));
+    assert!(sandbox.check_paths([format!("{}/a.md", allowed.display()), "/etc/hosts".to_string()]).is_err());
+  }
+
+  /// 测试指向根目录之外的符号链接
+  #[cfg(unix)]
+  #[test]
+  fn test_symlink_escape() {
+    let allowed_dir = TempDir::new().unwrap();
+    let outside_dir = TempDir::new().unwrap();
+    fs::write(outside_dir.path().join("secret.md"), "# 机密").unwrap();
+    std::os::unix::fs::symlink(outside_dir.path(), allowed_dir.path().join("link")).unwrap();
+
+    let allowed = canonicalize_allow_dir(allowed_dir.path().to_str().unwrap()).unwrap();
+    let sandbox = Sandbox::new(std::slice::from_ref(&allowed), None);
+    assert!(sandbox.check_path(&format!("{}/link/secret.md", allowed.display())).is_err());
+    assert!(sandbox.check_path(&format!("{}/link/new.md", allowed.display())).is_err());
+  }
+
+  /// 测试符号链接之后的 ..：按符号链接的目标解析，而不是按字面抵消
+  #[cfg(unix)]
+  #[test]
+  fn test_parent_dir_after_symlink() {
+    let allowed_dir = TempDir::new().unwrap();
+    let outside_dir = TempDir::new().unwrap();
+    fs::create_dir_all(outside_dir.path().join("sub")).unwrap();
+    fs::write(outside_dir.path().join("secret.md"), "# 机密").unwrap();
+    fs::write(allowed_dir.path().join("secret.md"), "# 公开").unwrap();
+    std::os::unix::fs::symlink(outside_dir.path().join("sub"), allowed_dir.path().join("link")).unwrap();
+
+    let allowed = canonicalize_allow_dir(allowed_dir.path().to_str().unwrap()).unwrap();
+    let sandbox = Sandbox::new(std::slice::from_ref(&allowed), None);
+    let err = sandbox.check_path(&format!("{}/link/../secret.md", allowed.display())).unwrap_err();
+    let outside = outside_dir.path().canonicalize().unwrap();
+    assert!(
+      matches!(err, MarkdownError::PathOutsideRoot { ref resolved, .. } if resolved == &outside.join("secret.md"))
+    );
+
+    // 不存在的目录之后的 .. 只抵消不存在的部分
+    let resolved = sandbox.check_path(&format!("{}/new/../draft.md", allowed.display())).unwrap();
+    assert_eq!(resolved, allowed.join("draft.md"));
+    let mut path = format!("{}/./secret.md", allowed.display());
+    sandbox.confine(&mut path).unwrap();
+    assert_eq!(path, allowed.join("secret.md").to_string_lossy());
+  }
+
+  /// 测试客户端根目录与 --allow-dir 取交集
+  #[test]
+  fn test_client_roots() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().canonicalize().unwrap();
+    fs::create_dir_all(base.join("project/docs")).unwrap();
+    fs::create_dir_all(base.join("other")).unwrap();
+
+    let sandbox = Sandbox::new(&[], Some(&[root(&base.join("project"))]));
+    assert!(sandbox.check_path(base.join("project/a.md").to_str().unwrap()).is_ok());
+    assert!(sandbox.check_path(base.join("other/a.md").to_str().unwrap()).is_err());
+
+    // 客户端根目录只能缩小 --allow-dir 的范围
+    let allow_dirs = [base.join("project")];
+    let sandbox = Sandbox::new(&allow_dirs, Some(&[root(&base), root(&base.join("project/docs"))]));
+    assert!(sandbox.check_path(base.join("project/docs/a.md").to_str().unwrap()).is_ok());
+    assert!(sandbox.check_path(base.join("project/a.md").to_str().unwrap()).is_ok());
+    assert!(sandbox.check_path(base.join("other/a.md").to_str().unwrap()).is_err());
+
+    let sandbox = Sandbox::new(&allow_dirs, Some(&[root(&base.join("other"))]));
+    assert!(sandbox.is_restricted());
+    assert!(sandbox.check_path(base.join("project/a.md").to_str().unwrap()).is_err());
+  }
+
+  /// 测试客户端声明的根目录都不可用时拒绝访问，而不是退回到不限制或 --allow-dir
+  #[test]
+  fn test_unusable_client_roots_deny_access() {
+    let temp_dir = TempDir::new().unwrap();
+    let base = temp_dir.path().canonicalize().unwrap();
+    let doc = base.join("a.md");
+
+    let remote = Root { uri: "https://example.com/docs".to_string(), name: None };
+    let sandbox = Sandbox::new(&[], Some(std::slice::from_ref(&remote)));
+    assert!(matches!(
+      sandbox.check_path(doc.to_str().unwrap()).unwrap_err(),
+      MarkdownError::PathOutsideRoot { ref roots, .. } if roots.is_empty()
+    ));
+    let sandbox = Sandbox::new(std::slice::from_ref(&base), Some(&[remote]));
+    assert!(sandbox.check_path(doc.to_str().unwrap()).is_err());
+  }
+
+  /// 测试根目录 URI 解析
+  #[test]
+  fn test_root_uri_to_path() {
+    assert_eq!(root_uri_to_path("file:///home/a%20b"), Some(PathBuf::from("/home/a b")));
+    assert_eq!(root_uri_to_path("file://localhost/srv/docs"), Some(PathBuf::from("/srv/docs")));
+    assert_eq!(root_uri_to_path("https://example.com/docs"), None);
+  }
+}
@@ -0,0 +1,1137 @@
+//! 章节操作模块
+//!
+//! 以标题锚点、标题路径或行号定位章节，对整篇文档或某个章节进行结构调整，
+//! 例如整体升降标题级别、移动和删除章节、将章节提取为独立的文档、按章节拆分文档以及合并多个文档
+
+use crate::config::SectionPosition;
+use crate::error::{MarkdownError, Result};
+use crate::image_localizer::{collect_image_reference_labels, collect_link_definitions, resolve_local_image_path};
+use crate::mst::{ImageSource, ImageType, LinkDefinition, MSTNode, NumberingConfig};
+use crate::numbering::{detect_numbering_config, NumberingGenerator};
+use crate::outline::{
+  build_outline, child_sections, find_section, heading_anchor, section_text, HeadingSelector, OutlineEntry,
+};
+use crate::parser::MarkdownParser;
+use crate::renderer::MarkdownRenderer;
+use crate::tr;
+use crate::utils::{normalize_path, path_to_url, relative_path};
+use percent_encoding::percent_decode_str;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// 标题级别调整的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShiftOutcome {
+  /// 调整后的文档内容
+  pub content: String,
+  /// 调整了级别的标题数量
+  pub shifted: usize,
+  /// 超出 1-6 范围、被限制在范围内的标题数量
+  pub clamped: usize,
+  /// 文档原有章节编号，已按推断的编号风格重新生成
+  pub renumbered: bool,
+}
+
+/// 章节移动的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveOutcome {
+  /// 移动后的文档内容
+  pub content: String,
+  /// 被移动的章节标题（移动前）
+  pub section: OutlineEntry,
+  /// 目标标题
+  pub target: OutlineEntry,
+  /// 章节标题级别的调整量，正数为降级
+  pub level_shift: i32,
+  /// 是否重新生成了章节编号
+  pub renumbered: bool,
+}
+
+/// 章节删除的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeleteOutcome {
+  /// 删除后的文档内容
+  pub content: String,
+  /// 被删除的章节标题
+  pub section: OutlineEntry,
+  /// 一并删除的子章节数量
+  pub subsections: usize,
+  /// 是否重新生成了章节编号
+  pub renumbered: bool,
+}
+
+/// 提取出的章节
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtractedSection {
+  /// 章节的标题
+  pub heading: OutlineEntry,
+  /// 章节内容，标题级别已调整为以 H1 开始
+  pub content: String,
+}
+
+/// 读取出的章节
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionExcerpt {
+  /// 章节的标题
+  pub heading: OutlineEntry,
+  /// 章节原文，超出字符数上限时只包含开头完整的若干行
+  pub content: String,
+  /// 不包含子章节时被省略的直接子章节
+  pub omitted_children: Vec<OutlineEntry>,
+  /// 章节原文（不含省略的子章节）的总字符数
+  pub total_chars: usize,
+  /// 超出字符数上限时，第一个没有完整返回的行的行号
+  pub truncated_at: Option<usize>,
+}
+
+/// 章节替换的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReplaceOutcome {
+  /// 替换后的文档内容
+  pub content: String,
+  /// 被替换的章节标题（替换前）
+  pub section: OutlineEntry,
+  /// 章节替换后的行数
+  pub new_lines: usize,
+  /// 新内容标题级别的调整量，正数为降级
+  pub level_shift: i32,
+  /// 调整后超出 H1-H6、被限制在范围内的标题数量
+  pub clamped: usize,
+  /// 是否重新生成了章节编号
+  pub renumbered: bool,
+}
+
+/// 拆分出的一个章节文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocumentPart {
+  /// 章节的标题
+  pub heading: OutlineEntry,
+  /// 文件名，如 `01-introduction.md`
+  pub file_name: String,
+  /// 章节内容，标题级别已调整为以 H1 开始
+  pub content: String,
+}
+
+/// 文档拆分的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitOutcome {
+  /// 按文档顺序排列的章节文件
+  pub parts: Vec<DocumentPart>,
+  /// 索引文件内容：章节之外的内容保持原样，每个章节替换为指向其文件的链接
+  pub index: String,
+}
+
+/// 要合并的文档
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeSource {
+  /// 文档路径
+  pub path: PathBuf,
+  /// 文档内容
+  pub content: String,
+}
+
+/// 合并时各个文档的上级标题
+#[derive(Debug, Clone, PartialEq)]
+pub enum MergeParent {
+  /// 没有上级标题，标题级别保持不变
+  None,
+  /// 生成以该文本为标题的 H1，所有文档调整到它之下
+  Title(String),
+  /// 以第一个文档的最高级标题作为上级标题，其余文档调整到它之下
+  FirstFile,
+}
+
+/// 文档合并的结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOutcome {
+  /// 合并后的文档内容
+  pub content: String,
+  /// 调整后超出 H1-H6、被限制在范围内的标题数量
+  pub clamped: usize,
+  /// 锚点因重名或重新编号而改变的标题数量
+  pub renamed_anchors: usize,
+  /// 指向被合并文档、改写为文档内锚点的链接数量
+  pub internal_links: usize,
+}
+
+/// 解析文档并构建大纲
+fn parse_outline(parser: &MarkdownParser, content: &str) -> Result<Vec<OutlineEntry>> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  Ok(build_outline(&mst, content.lines().count()))
+}
+
+/// 提取选定的章节（标题及其下所有内容），标题级别调整为以 H1 开始
+pub fn extract_section(parser: &MarkdownParser, content: &str, selector: &HeadingSelector) -> Result<ExtractedSection> {
+  let outline = parse_outline(parser, content)?;
+  let heading = selector.find(&outline)?.clone();
+
+  let text = format!("{}\n", section_text(content, &heading));
+  let content = match 1 - heading.level as i32 {
+    0 => text,
+    shift => shift_heading_levels(parser, &text, shift, None, false, false)?.content,
+  };
+
+  Ok(ExtractedSection { heading, content })
+}
+
+/// 读取选定章节的原文，标题级别保持不变
+///
+/// `include_children` 为 false 时只读取到第一个子章节之前；指定 `max_chars` 时按整行截断，
+/// 第一行就超出上限时截取该行开头的 `max_chars` 个字符。字符数按 Unicode 字符计算，一个汉字计为一个字符
+pub fn read_section(
+  parser: &MarkdownParser,
+  content: &str,
+  selector: &HeadingSelector,
+  include_children: bool,
+  max_chars: Option<usize>,
+) -> Result<SectionExcerpt> {
+  let outline = parse_outline(parser, content)?;
+  let heading = selector.find(&outline)?.clone();
+
+  let mut range = heading.clone();
+  let mut omitted_children = Vec::new();
+  if !include_children {
+    omitted_children = child_sections(&outline, Some(&heading)).into_iter().cloned().collect();
+    if let Some(first) = omitted_children.first() {
+      range.end_line = first.line_number - 1;
+    }
+  }
+
+  let text = section_text(content, &range);
+  let total_chars = text.chars().count();
+  let Some(max_chars) = max_chars.filter(|max_chars| total_chars > *max_chars) else {
+    return Ok(SectionExcerpt { heading, content: text, omitted_children, total_chars, truncated_at: None });
+  };
+
+  // 每行计入一个换行符，最后一行不需要换行符
+  let lines: Vec<&str> = text.lines().collect();
+  let mut used = 0;
+  let fitting = lines
+    .iter()
+    .take_while(|line| {
+      used += line.chars().count() + 1;
+      used <= max_chars + 1
+    })
+    .count();
+  let content = match fitting {
+    0 => lines[0].chars().take(max_chars).collect(),
+    _ => trim_blank_lines(&lines[..fitting]).join("\n"),
+  };
+  let truncated_at = Some(heading.line_number + fitting);
+  Ok(SectionExcerpt { heading, content, omitted_children, total_chars, truncated_at })
+}
+
+/// 用新内容替换选定的章节，文档的其余部分保持原样
+///
+/// `replace_heading` 为 false 时保留章节标题，新内容替换标题下的所有内容（包括子章节），其中的标题必须比章节标题低；
+/// 为 true 时新内容必须以一个与章节标题同级的标题开始，其余标题都比它低。`adjust_levels` 为 true 时自动调整新内容的标题级别，
+/// 而不是返回错误；`renumber` 为 true 且文档原有章节编号时，替换后重新生成编号
+pub fn replace_section(
+  parser: &MarkdownParser,
+  content: &str,
+  selector: &HeadingSelector,
+  new_content: &str,
+  replace_heading: bool,
+  adjust_levels: bool,
+  renumber: bool,
+) -> Result<ReplaceOutcome> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let outline = build_outline(&mst, content.lines().count());
+  let section = selector.find(&outline)?.clone();
+
+  let new_outline = parse_outline(parser, new_content)?;
+  let level_shift = if replace_heading {
+    let first_line = new_content.lines().position(|line| !line.trim().is_empty()).map(|i| i + 1);
+    let Some(first) = new_outline.first().filter(|entry| Some(entry.line_number) == first_line) else {
+      return Err(MarkdownError::ValidationError(tr!("新内容必须以标题开始").to_string()));
+    };
+    if let Some(entry) = new_outline[1..].iter().find(|entry| entry.level <= first.level) {
+      return Err(MarkdownError::ValidationError(tr!(
+        "新内容只能包含一个章节，第{}行的标题 {} 不能与第一个标题同级或比它高",
+        entry.line_number,
+        entry.text
+      )));
+    }
+    if first.level != section.level && !adjust_levels {
+      return Err(MarkdownError::ValidationError(tr!(
+        "新内容的标题 {} 为 H{}，与被替换的标题 H{} 不同；设置 adjust_levels=true 可自动调整级别",
+        first.text,
+        first.level,
+        section.level
+      )));
+    }
+    section.level as i32 - first.level as i32
+  } else {
+    if let Some(entry) = new_outline.iter().find(|entry| entry.level <= section.level).filter(|_| !adjust_levels) {
+      return Err(MarkdownError::ValidationError(tr!(
+        "新内容第{}行的标题 {} 为 H{}，不能位于 H{} 标题 {} 之下；设置 adjust_levels=true 可自动调整级别",
+        entry.line_number,
+        entry.text,
+        entry.level,
+        section.level,
+        section.text
+      )));
+    }
+    match new_outline.iter().map(|entry| entry.level).min() {
+      Some(top) if adjust_levels => section.level as i32 + 1 - top as i32,
+      _ => 0,
+    }
+  };
+
+  let entries: Vec<&OutlineEntry> = new_outline.iter().collect();
+  let (new_content, _, clamped) = shift_lines(new_content, &entries, level_shift, true)?;
+  let new_content_lines: Vec<&str> = new_content.lines().collect();
+  let block = trim_blank_lines(&new_content_lines);
+
+  let mut replacement: Vec<&str> = Vec::new();
+  if !replace_heading {
+    replacement.push(content.lines().nth(section.line_number - 1).unwrap_or_default());
+    if !block.is_empty() {
+      replacement.push("");
+    }
+  }
+  replacement.extend(block);
+  let new_lines = replacement.len();
+
+  let replaced = replace_section_lines(content, &section, &replacement.join("\n"));
+  let lines: Vec<&str> = replaced.lines().collect();
+  let (content, renumbered) = finish(parser, &mst, content, &lines, renumber)?;
+  Ok(ReplaceOutcome { content, section, new_lines, level_shift, clamped, renumbered })
+}
+
+/// 用 `replacement` 替换章节在原文中的所有行，与后面的内容之间保留一个空行
+pub fn replace_section_lines(content: &str, heading: &OutlineEntry, replacement: &str) -> String {
+  let lines: Vec<&str> = content.lines().collect();
+  let mut result: Vec<&str> = lines[..heading.line_number - 1].to_vec();
+  result.push(replacement.trim_end_matches('\n'));
+
+  let rest = &lines[heading.end_line.min(lines.len())..];
+  if !rest.is_empty() {
+    result.push("");
+    result.extend(rest);
+  }
+
+  let mut result = result.join("\n");
+  if content.ends_with('\n') {
+    result.push('\n');
+  }
+  result
+}
+
+/// 按 `level` 级标题拆分文档，每个该级别的标题及其下所有内容成为一个文件
+///
+/// 文件名由序号和去掉编号后的标题锚点组成；第一个章节之前以及更高级标题下不属于任何章节的内容保留在索引中
+pub fn split_document(parser: &MarkdownParser, content: &str, level: usize) -> Result<SplitOutcome> {
+  let outline = parse_outline(parser, content)?;
+  let chapters: Vec<&OutlineEntry> = outline.iter().filter(|entry| entry.level == level).collect();
+  if chapters.is_empty() {
+    return Err(MarkdownError::ValidationError(tr!("文档中没有 H{} 标题，无法拆分", level)));
+  }
+
+  let width = chapters.len().to_string().len().max(2);
+  let lines: Vec<&str> = content.lines().collect();
+  let mut parts = Vec::with_capacity(chapters.len());
+  let mut index_blocks: Vec<String> = Vec::new();
+  let mut last_is_list = false;
+  let mut next_line = 1;
+
+  for (i, heading) in chapters.into_iter().enumerate() {
+    let slug = match heading_anchor(&heading.title) {
+      slug if slug.is_empty() => "part".to_string(),
+      slug => slug,
+    };
+    let file_name = format!("{:0width$}-{}.md", i + 1, slug, width = width);
+
+    let text = format!("{}\n", section_text(content, heading));
+    let part_content = match 1 - level as i32 {
+      0 => text,
+      shift => shift_heading_levels(parser, &text, shift, None, false, false)?.content,
+    };
+
+    // 章节之间不属于任何章节的内容原样保留，相邻章节的链接合并为一个列表
+    let gap = lines[next_line - 1..heading.line_number - 1].join("\n");
+    if !gap.trim().is_empty() {
+      index_blocks.push(gap.trim_matches('\n').to_string());
+      last_is_list = false;
+    }
+    let item = format!("- [{}]({})", heading.text, file_name);
+    match index_blocks.last_mut() {
+      Some(block) if last_is_list => {
+        block.push('\n');
+        block.push_str(&item);
+      }
+      _ => index_blocks.push(item),
+    }
+    last_is_list = true;
+    next_line = heading.end_line + 1;
+
+    parts.push(DocumentPart { heading: heading.clone(), file_name, content: part_content });
+  }
+
+  let rest = lines[(next_line - 1).min(lines.len())..].join("\n");
+  if !rest.trim().is_empty() {
+    index_blocks.push(rest.trim_matches('\n').to_string());
+  }
+
+  Ok(SplitOutcome { parts, index: format!("{}\n", index_blocks.join("\n\n")) })
+}
+
+/// 改写文本中的本地图片地址，`rewrite` 返回新地址时替换，返回 None 时保持原样
+///
+/// 处理 Markdown 和 HTML 图片以及被图片引用的链接引用定义，远程图片和 data URI 不会传给 `rewrite`
+pub fn rewrite_local_images<F>(parser: &MarkdownParser, content: &str, mut rewrite: F) -> Result<String>
+where
+  F: FnMut(&str) -> Option<String>,
+{
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let definitions = collect_link_definitions(&mst);
+  let referenced_labels = collect_image_reference_labels(parser, &mst, &definitions);
+  let renderer = MarkdownRenderer::new();
+  let mut rewrite_local = |url: &str| match ImageSource::from_url(url) {
+    ImageSource::Local => rewrite(url),
+    _ => None,
+  };
+
+  let mut lines = Vec::new();
+  for line in content.split_inclusive('\n') {
+    let (text, newline) = line.strip_suffix('\n').map_or((line, ""), |text| (text, "\n"));
+
+    if let Some(mut definition) = parser.parse_link_definition(text) {
+      let new_url = match referenced_labels.contains(&LinkDefinition::normalize_label(&definition.label)) {
+        true => rewrite_local(definition.current_url()),
+        false => None,
+      };
+      if let Some(new_url) = new_url {
+        definition.url = new_url;
+        definition.local_path = None;
+        lines.push(format!("{}{}", renderer.render_link_definition(&definition), newline));
+        continue;
+      }
+    }
+
+    let mut updated = text.to_string();
+    for mut image_node in parser.parse_images_in_line(text, 0) {
+      let original_text = image_node.raw.clone();
+      let Some(image_info) = image_node.get_image_info_mut().filter(|info| info.image_type != ImageType::Reference)
+      else {
+        continue;
+      };
+      if let Some(new_url) = rewrite_local(image_info.current_url()) {
+        image_info.original_url = new_url;
+        image_info.local_path = None;
+        updated = updated.replacen(&original_text, &renderer.render_image(&image_node), 1);
+      }
+    }
+    lines.push(format!("{}{}", updated, newline));
+  }
+
+  Ok(lines.concat())
+}
+
+/// 改写文本中的链接地址，`rewrite` 返回新地址时替换，返回 None 时保持原样
+///
+/// 处理行内链接 [text](url) 和未被图片引用的链接引用定义；图片地址由 [`rewrite_local_images`] 处理
+pub fn rewrite_links<F>(parser: &MarkdownParser, content: &str, mut rewrite: F) -> Result<String>
+where
+  F: FnMut(&str) -> Option<String>,
+{
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let definitions = collect_link_definitions(&mst);
+  let image_labels = collect_image_reference_labels(parser, &mst, &definitions);
+  let renderer = MarkdownRenderer::new();
+
+  let mut lines = Vec::new();
+  for (i, line) in content.split_inclusive('\n').enumerate() {
+    let (text, newline) = line.strip_suffix('\n').map_or((line, ""), |text| (text, "\n"));
+
+    let definition = parser
+      .parse_link_definition(text)
+      .filter(|definition| !image_labels.contains(&LinkDefinition::normalize_label(&definition.label)));
+    let new_url = definition.as_ref().and_then(|definition| rewrite(&definition.url));
+    if let (Some(mut definition), Some(new_url)) = (definition, new_url) {
+      definition.url = new_url;
+      lines.push(format!("{}{}", renderer.render_link_definition(&definition), newline));
+      continue;
+    }
+
+    // 从后往前替换，前面链接的字节范围保持有效
+    let mut updated = text.to_string();
+    for link in parser.parse_links_in_line(text, i + 1).into_iter().rev() {
+      if let Some(new_url) = rewrite(&link.url) {
+        let new_url = match new_url.contains(' ') {
+          true => format!("<{}>", new_url),
+          false => new_url,
+        };
+        let range = match text[..link.url_range.start].ends_with('<') {
+          true => link.url_range.start - 1..link.url_range.end + 1,
+          false => link.url_range,
+        };
+        updated.replace_range(range, &new_url);
+      }
+    }
+    lines.push(format!("{}{}", updated, newline));
+  }
+
+  Ok(lines.concat())
+}
+
+/// 将相对于 `from_dir` 的本地相对地址改写为相对于 `to_dir` 的地址
+///
+/// 绝对路径、带协议的地址和只有锚点的地址返回 None，两个目录相同时也返回 None
+pub fn relocate_relative_url(url: &str, from_dir: &Path, to_dir: &Path) -> Option<String> {
+  if url.is_empty()
+    || url.starts_with(['/', '#'])
+    || url.starts_with("file:")
+    || normalize_path(from_dir) == normalize_path(to_dir)
+  {
+    return None;
+  }
+  let path = resolve_local_image_path(from_dir, url).ok().flatten()?;
+  Some(path_to_url(&relative_path(to_dir, &path)))
+}
+
+/// 按顺序合并多个文档，合并后的文档保存在 `output_path`
+///
+/// 按 `parent` 调整各个文档的标题级别；相对路径的图片和链接改写为从 `output_path` 所在目录出发的路径，
+/// 指向被合并文档的链接改写为合并后文档内的锚点。同名标题的锚点依次追加 -1、-2，指向它们的链接同步更新
+pub fn merge_documents(
+  parser: &MarkdownParser,
+  sources: &[MergeSource],
+  output_path: &Path,
+  parent: &MergeParent,
+) -> Result<MergeOutcome> {
+  let output_dir = output_path.parent().unwrap_or(Path::new(""));
+
+  // 调整标题级别，使每个文档的最高级标题位于上级标题之下
+  let mut parent_level = match parent {
+    MergeParent::Title(_) => Some(1),
+    _ => None,
+  };
+  let mut outlines = Vec::with_capacity(sources.len());
+  let mut contents = Vec::with_capacity(sources.len());
+  let mut clamped = 0;
+  for (i, source) in sources.iter().enumerate() {
+    let outline = parse_outline(parser, &source.content)?;
+    let top_level = outline.iter().map(|entry| entry.level).min();
+    let content = match (top_level, parent_level) {
+      (Some(top_level), Some(parent_level)) if top_level != parent_level + 1 => {
+        let shift = (parent_level + 1) as i32 - top_level as i32;
+        let outcome = shift_heading_levels(parser, &source.content, shift, None, true, false)?;
+        clamped += outcome.clamped;
+        outcome.content
+      }
+      _ => source.content.clone(),
+    };
+    if i == 0 && *parent == MergeParent::FirstFile {
+      parent_level = top_level;
+    }
+    outlines.push(outline);
+    contents.push(content);
+  }
+
+  // 由合并后的大纲得到每个标题最终的锚点，按标题顺序与各文档原来的锚点对应
+  let title = match parent {
+    MergeParent::Title(title) => Some(format!("# {}", title)),
+    _ => None,
+  };
+  let merged = join_documents(title.as_deref(), &contents);
+  let mut merged_outline = parse_outline(parser, &merged)?.into_iter().skip(usize::from(title.is_some()));
+  let mut renamed_anchors = 0;
+  let anchor_maps: Vec<HashMap<String, String>> = outlines
+    .iter()
+    .map(|outline| {
+      outline
+        .iter()
+        .filter_map(|entry| {
+          let merged_entry = merged_outline.next()?;
+          renamed_anchors += usize::from(merged_entry.anchor != entry.anchor);
+          Some((entry.anchor.clone(), merged_entry.anchor))
+        })
+        .collect()
+    })
+    .collect();
+  let first_anchors: Vec<Option<&String>> = outlines
+    .iter()
+    .zip(&anchor_maps)
+    .map(|(outline, anchors)| outline.first().and_then(|entry| anchors.get(&entry.anchor)))
+    .collect();
+
+  // 改写图片和链接地址
+  let source_paths: Vec<PathBuf> = sources.iter().map(|source| normalize_path(&source.path)).collect();
+  let mut internal_links = 0;
+  for (i, content) in contents.iter_mut().enumerate() {
+    let md_dir = sources[i].path.parent().unwrap_or(Path::new(""));
+    let lookup = |anchors: &HashMap<String, String>, fragment: &str| {
+      let fragment = percent_decode_str(fragment).decode_utf8_lossy().to_lowercase();
+      anchors.get(&fragment).map(|anchor| format!("#{}", anchor))
+    };
+
+    let with_images = rewrite_local_images(parser, content, |url| relocate_relative_url(url, md_dir, output_dir))?;
+    *content = rewrite_links(parser, &with_images, |url| {
+      if let Some(fragment) = url.strip_prefix('#') {
+        return lookup(&anchor_maps[i], fragment).filter(|new_url| new_url != url);
+      }
+      if url.starts_with('/') || Url::parse(url).is_ok_and(|parsed| parsed.scheme().len() > 1) {
+        return None;
+      }
+
+      let (path, fragment) = url.split_once('#').map_or((url, None), |(path, fragment)| (path, Some(fragment)));
+      let target = normalize_path(&md_dir.join(percent_decode_str(path).decode_utf8_lossy().as_ref()));
+      if let Some(j) = source_paths.iter().position(|source_path| *source_path == target) {
+        let anchor = match fragment {
+          Some(fragment) => lookup(&anchor_maps[j], fragment),
+          None => first_anchors[j].map(|anchor| format!("#{}", anchor)),
+        };
+        if anchor.is_some() {
+          internal_links += 1;
+          return anchor;
+        }
+      }
+
+      let relocated = relocate_relative_url(path, md_dir, output_dir)?;
+      Some(match fragment {
+        Some(fragment) => format!("{}#{}", relocated, fragment),
+        None => relocated,
+      })
+    })?;
+  }
+
+  Ok(MergeOutcome { content: join_documents(title.as_deref(), &contents), clamped, renamed_anchors, internal_links })
+}
+
+/// 依次拼接标题和各个文档，文档之间以一个空行分隔
+fn join_documents(title: Option<&str>, contents: &[String]) -> String {
+  let blocks: Vec<&str> = title
+    .into_iter()
+    .chain(contents.iter().map(|content| content.trim_matches('\n')))
+    .filter(|b| !b.is_empty())
+    .collect();
+  format!("{}\n", blocks.join("\n\n"))
+}
+
+/// 将标题整体升降 `shift` 级，正数降级（# 变为 ##），负数升级
+///
+/// `anchor` 不为空时只调整该标题及其下所有子标题。调整后级别超出 1-6 时，
+/// `clamp` 为 true 则限制在范围内，否则返回错误、不做任何修改。
+/// 只改写标题行开头的 #，其余内容保持原样；`renumber` 为 true 且文档原有章节编号时，调整后重新生成编号
+pub fn shift_heading_levels(
+  parser: &MarkdownParser,
+  content: &str,
+  shift: i32,
+  anchor: Option<&str>,
+  clamp: bool,
+  renumber_document: bool,
+) -> Result<ShiftOutcome> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let outline = build_outline(&mst, content.lines().count());
+
+  let targets: Vec<&OutlineEntry> = match anchor {
+    Some(anchor) => {
+      let section = find_section(&outline, anchor)
+        .ok_or_else(|| MarkdownError::ValidationError(tr!("没有锚点为 #{} 的标题", anchor.trim_start_matches('#'))))?;
+      subtree(&outline, section)
+    }
+    None => outline.iter().collect(),
+  };
+  let (shifted_content, shifted, clamped) = shift_lines(content, &targets, shift, clamp)?;
+
+  // 原有编号与调整后的层级不再对应，按原来的编号风格重新生成
+  let numbering_config = detect_numbering_config(&mst).filter(|_| renumber_document);
+  let renumbered = numbering_config.is_some();
+  let content = match numbering_config {
+    Some(config) => renumber(parser, &shifted_content, config)?,
+    None => shifted_content,
+  };
+
+  Ok(ShiftOutcome { content, shifted, clamped, renumbered })
+}
+
+/// 将章节（标题及其下所有内容）移动到目标标题之前、之后或之内，标题级别随新的位置调整
+///
+/// 只移动原文中的行，其余内容保持原样；`renumber` 为 true 且文档原有章节编号时，移动后重新生成编号
+pub fn move_section(
+  parser: &MarkdownParser,
+  content: &str,
+  section: &HeadingSelector,
+  target: &HeadingSelector,
+  position: SectionPosition,
+  renumber: bool,
+) -> Result<MoveOutcome> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let outline = build_outline(&mst, content.lines().count());
+  let section = section.find(&outline)?.clone();
+  let target = target.find(&outline)?.clone();
+  if (section.line_number..=section.end_line).contains(&target.line_number) {
+    return Err(MarkdownError::ValidationError(tr!("不能将章节 {} 移动到它自身或其子章节的位置", section.text)));
+  }
+
+  let new_level = match position {
+    SectionPosition::Before | SectionPosition::After => target.level,
+    SectionPosition::Into => target.level + 1,
+  };
+  let level_shift = new_level as i32 - section.level as i32;
+  let (shifted_content, _, _) = shift_lines(content, &subtree(&outline, &section), level_shift, false)?;
+
+  let lines: Vec<&str> = shifted_content.lines().collect();
+  let section_range = section.line_number - 1..section.end_line.min(lines.len());
+  let block = trim_blank_lines(&lines[section_range.clone()]);
+  let insert_at = match position {
+    SectionPosition::Before => target.line_number - 1,
+    SectionPosition::After | SectionPosition::Into => target.end_line,
+  };
+
+  let mut result: Vec<&str> = Vec::with_capacity(lines.len() + 2);
+  for (i, line) in lines.iter().enumerate() {
+    if i == insert_at {
+      push_block(&mut result, block);
+    }
+    if !section_range.contains(&i) {
+      result.push(line);
+    }
+  }
+  if insert_at >= lines.len() {
+    push_block(&mut result, block);
+  }
+
+  let (content, renumbered) = finish(parser, &mst, content, &result, renumber)?;
+  Ok(MoveOutcome { content, section, target, level_shift, renumbered })
+}
+
+/// 删除章节（标题及其下所有内容）；`renumber` 为 true 且文档原有章节编号时，删除后重新生成编号
+pub fn delete_section(
+  parser: &MarkdownParser,
+  content: &str,
+  selector: &HeadingSelector,
+  renumber: bool,
+) -> Result<DeleteOutcome> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let outline = build_outline(&mst, content.lines().count());
+  let section = selector.find(&outline)?.clone();
+  let subsections = subtree(&outline, &section).len() - 1;
+
+  let lines: Vec<&str> = content.lines().collect();
+  let section_range = section.line_number - 1..section.end_line.min(lines.len());
+  let result: Vec<&str> =
+    lines.iter().enumerate().filter(|(i, _)| !section_range.contains(i)).map(|(_, line)| *line).collect();
+
+  let (content, renumbered) = finish(parser, &mst, content, &result, renumber)?;
+  Ok(DeleteOutcome { content, section, subsections, renumbered })
+}
+
+/// 章节标题及其下所有子标题
+fn subtree<'a>(outline: &'a [OutlineEntry], section: &OutlineEntry) -> Vec<&'a OutlineEntry> {
+  outline.iter().filter(|entry| (section.line_number..=section.end_line).contains(&entry.line_number)).collect()
+}
+
+/// 将 `entries` 对应的标题行开头的 # 整体升降 `shift` 级，其余内容保持原样
+///
+/// 返回调整后的内容、调整了级别的标题数量和被限制在 H1-H6 范围内的标题数量；`clamp` 为 false 时超出范围返回错误
+fn shift_lines(content: &str, entries: &[&OutlineEntry], shift: i32, clamp: bool) -> Result<(String, usize, usize)> {
+  let mut new_levels = Vec::with_capacity(entries.len());
+  let mut clamped = 0;
+  for entry in entries {
+    let level = entry.level as i32 + shift;
+    if (1..=6).contains(&level) {
+      new_levels.push((entry.line_number, entry.level, level as usize));
+    } else if clamp {
+      clamped += 1;
+      new_levels.push((entry.line_number, entry.level, level.clamp(1, 6) as usize));
+    } else {
+      return Err(MarkdownError::ValidationError(tr!(
+        "第{}行的标题 {} 调整后为 H{}，超出 H1-H6 的范围；设置 clamp=true 可将级别限制在范围内",
+        entry.line_number,
+        entry.text,
+        level
+      )));
+    }
+  }
+
+  let mut lines: Vec<String> = content.split_inclusive('\n').map(str::to_string).collect();
+  for (line_number, old_level, new_level) in &new_levels {
+    let line = &mut lines[line_number - 1];
+    *line = format!("{}{}", "#".repeat(*new_level), &line[*old_level..]);
+  }
+
+  let shifted = new_levels.iter().filter(|(_, old_level, new_level)| old_level != new_level).count();
+  Ok((lines.concat(), shifted, clamped))
+}
+
+/// 按给定的编号风格重新生成章节编号
+fn renumber(parser: &MarkdownParser, content: &str, config: NumberingConfig) -> Result<String> {
+  let mut mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  NumberingGenerator::new(config).generate_numbering(&mut mst);
+  Ok(MarkdownRenderer::new().render_with_numbering(&mst))
+}
+
+/// 去掉开头和结尾的空行
+fn trim_blank_lines<'a, 'b>(lines: &'b [&'a str]) -> &'b [&'a str] {
+  let start = lines.iter().position(|line| !line.trim().is_empty()).unwrap_or(lines.len());
+  let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(start, |i| i + 1);
+  &lines[start..end]
+}
+
+/// 插入一段内容，与前后的内容之间各保留一个空行
+fn push_block<'a>(result: &mut Vec<&'a str>, block: &[&'a str]) {
+  if result.last().is_some_and(|line| !line.trim().is_empty()) {
+    result.push("");
+  }
+  result.extend(block);
+  result.push("");
+}
+
+/// 拼接调整后的各行：去掉末尾的空行，保留原文末尾的换行；按需以原文的编号风格重新生成编号
+fn finish(
+  parser: &MarkdownParser,
+  mst: &MSTNode,
+  original: &str,
+  lines: &[&str],
+  renumber_document: bool,
+) -> Result<(String, bool)> {
+  let end = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |i| i + 1);
+  let mut content = lines[..end].join("\n");
+  if original.ends_with('\n') {
+    content.push('\n');
+  }
+
+  match detect_numbering_config(mst).filter(|_| renumber_document) {
+    Some(config) => Ok((renumber(parser, &content, config)?, true)),
+    None => Ok((content, false)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn shift(content: &str, shift: i32, anchor: Option<&str>, clamp: bool) -> Result<ShiftOutcome> {
+    shift_heading_levels(&MarkdownParser::new().unwrap(), content, shift, anchor, clamp, false)
+  }
+
+  /// 测试整篇文档降级，正文保持原样
+  #[test]
+  fn test_shift_whole_document() {
+    let content = "# 介绍\n\n正文 # 不是标题\n\n## 背景 ##\n\n```\ncode\n```\n";
+    let outcome = shift(content, 1, None, false).unwrap();
+    assert_eq!(outcome.content, "## 介绍\n\n正文 # 不是标题\n\n### 背景 ##\n\n```\ncode\n```\n");
+    assert_eq!((outcome.shifted, outcome.clamped, outcome.renumbered), (2, 0, false));
+
+    let outcome = shift(&outcome.content, -1, None, false).unwrap();
+    assert_eq!(outcome.content, content);
+  }
+
+  /// 测试代码块内形如标题的行保持原样
+  #[test]
+  fn test_shift_skips_fenced_code() {
+    let content = "# 安装\n\n```bash\n# install deps\nnpm install\n```\n";
+    let outcome = shift(content, 1, None, false).unwrap();
+    assert_eq!(outcome.content, "## 安装\n\n```bash\n# install deps\nnpm install\n```\n");
+    assert_eq!(outcome.shifted, 1);
+  }
+
+  /// 测试只调整某个章节
+  #[test]
+  fn test_shift_subtree() {
+    let content = "# A\n\n## B\n\n### C\n\n## D\n";
+    let outcome = shift(content, 1, Some("#b"), false).unwrap();
+    assert_eq!(outcome.content, "# A\n\n### B\n\n#### C\n\n## D\n");
+    assert_eq!(outcome.shifted, 2);
+
+    let err = shift(content, 1, Some("missing"), false).unwrap_err();
+    assert_eq!(err.code(), "validation_error");
+  }
+
+  /// 测试超出级别范围时拒绝或限制
+  #[test]
+  fn test_shift_out_of_range() {
+    let content = "# A\n\n## B\n";
+    let err = shift(content, -1, None, false).unwrap_err();
+    assert!(err.to_string().contains("第1行"), "{}", err);
+
+    let outcome = shift(content, -1, None, true).unwrap();
+    assert_eq!(outcome.content, "# A\n\n# B\n");
+    assert_eq!((outcome.shifted, outcome.clamped), (1, 1));
+  }
+
+  /// 测试读取章节：包含或不包含子章节，超出字符数上限时按整行截断
+  #[test]
+  fn test_read_section() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 设计\n\n## 存储\n\n数据保存在本地\n\n### 索引\n\n## 接口\n";
+    let read = |selector: HeadingSelector, include_children: bool, max_chars: Option<usize>| {
+      read_section(&parser, content, &selector, include_children, max_chars).unwrap()
+    };
+
+    let excerpt = read(HeadingSelector::NumberPath("1.1".to_string()), true, None);
+    assert_eq!(excerpt.content, "## 存储\n\n数据保存在本地\n\n### 索引");
+    assert_eq!((excerpt.total_chars, excerpt.truncated_at), (22, None));
+
+    let excerpt = read(HeadingSelector::Line(1), false, None);
+    assert_eq!(excerpt.content, "# 设计");
+    let children: Vec<&str> = excerpt.omitted_children.iter().map(|entry| entry.anchor.as_str()).collect();
+    assert_eq!(children, vec!["存储", "接口"]);
+
+    // "## 存储\n\n" 共 7 个字符，第 5 行放不下，末尾的空行不返回
+    let excerpt = read(HeadingSelector::Anchor("存储".to_string()), true, Some(10));
+    assert_eq!(excerpt.content, "## 存储");
+    assert_eq!(excerpt.truncated_at, Some(5));
+
+    let excerpt = read(HeadingSelector::Anchor("存储".to_string()), true, Some(3));
+    assert_eq!((excerpt.content.as_str(), excerpt.truncated_at), ("## ", Some(3)));
+  }
+
+  /// 测试提取章节并将级别调整为以 H1 开始
+  #[test]
+  fn test_extract_section() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 设计\n\n## 存储\n\n正文\n\n### 索引\n\n## 接口\n";
+
+    let section = extract_section(&parser, content, &HeadingSelector::TitlePath("设计/存储".to_string())).unwrap();
+    assert_eq!(section.content, "# 存储\n\n正文\n\n## 索引\n");
+    assert_eq!((section.heading.line_number, section.heading.anchor.as_str()), (3, "存储"));
+
+    let section = extract_section(&parser, content, &HeadingSelector::Line(1)).unwrap();
+    assert_eq!(section.content, content);
+  }
+
+  /// 测试替换章节所在的行
+  #[test]
+  fn test_replace_section_lines() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 设计\n\n## 存储\n\n正文\n\n## 接口\n";
+    let outline = parse_outline(&parser, content).unwrap();
+
+    let replaced = replace_section_lines(content, &outline[1], "## 存储\n\n[存储](存储.md)\n");
+    assert_eq!(replaced, "# 设计\n\n## 存储\n\n[存储](存储.md)\n\n## 接口\n");
+
+    let replaced = replace_section_lines(content, &outline[2], "## 接口\n\n见附录");
+    assert_eq!(replaced, "# 设计\n\n## 存储\n\n正文\n\n## 接口\n\n见附录\n");
+  }
+
+  /// 测试替换章节内容：校验或调整新内容的标题级别，文档其余部分保持原样
+  #[test]
+  fn test_replace_section() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 设计\n\n## 存储\n\n旧内容\n\n### 索引\n\n## 接口\n\n* 列表\n";
+    let storage = HeadingSelector::Anchor("存储".to_string());
+    let replace = |new_content: &str, replace_heading: bool, adjust_levels: bool| {
+      replace_section(&parser, content, &storage, new_content, replace_heading, adjust_levels, false)
+    };
+
+    let outcome = replace("\n新内容\n\n### 缓存\n\n", false, false).unwrap();
+    assert_eq!(outcome.content, "# 设计\n\n## 存储\n\n新内容\n\n### 缓存\n\n## 接口\n\n* 列表\n");
+    assert_eq!((outcome.section.end_line, outcome.new_lines, outcome.level_shift), (8, 5, 0));
+
+    // 新内容的标题不低于章节标题时拒绝，或按需调整
+    let err = replace("# 缓存\n\n内容", false, false).unwrap_err();
+    assert!(err.to_string().contains("adjust_levels"), "{}", err);
+    let outcome = replace("# 缓存\n\n内容", false, true).unwrap();
+    assert_eq!(outcome.content, "# 设计\n\n## 存储\n\n### 缓存\n\n内容\n\n## 接口\n\n* 列表\n");
+    assert_eq!(outcome.level_shift, 2);
+
+    // 替换标题时，新内容必须以一个与章节标题同级的标题开始
+    let outcome = replace("# 持久化\n\n## 文件\n", true, true).unwrap();
+    assert_eq!(outcome.content, "# 设计\n\n## 持久化\n\n### 文件\n\n## 接口\n\n* 列表\n");
+    assert!(replace("## 持久化\n\n## 文件\n", true, true).is_err());
+    assert!(replace("正文\n\n## 持久化\n", true, true).is_err());
+    assert!(replace("### 持久化\n", true, false).is_err());
+
+    // 清空章节内容，只保留标题
+    let outcome = replace("", false, false).unwrap();
+    assert_eq!(outcome.content, "# 设计\n\n## 存储\n\n## 接口\n\n* 列表\n");
+  }
+
+  /// 测试按一级标题拆分文档并生成索引
+  #[test]
+  fn test_split_document() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "前言\n\n# 1. Introduction\n\n介绍\n\n## Goals\n\n# 2. 使用\n\n用法\n";
+    let outcome = split_document(&parser, content, 1).unwrap();
+
+    let files: Vec<&str> = outcome.parts.iter().map(|part| part.file_name.as_str()).collect();
+    assert_eq!(files, ["01-introduction.md", "02-使用.md"]);
+    assert_eq!(outcome.parts[0].content, "# 1. Introduction\n\n介绍\n\n## Goals\n");
+    assert_eq!(outcome.index, "前言\n\n- [1. Introduction](01-introduction.md)\n- [2. 使用](02-使用.md)\n");
+
+    assert!(split_document(&parser, content, 3).is_err());
+  }
+
+  /// 测试按二级标题拆分时，一级标题保留在索引中，章节调整为以 H1 开始
+  #[test]
+  fn test_split_document_sublevel() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 指南\n\n## 安装\n\n### 依赖\n\n# 附录\n\n## 术语\n";
+    let outcome = split_document(&parser, content, 2).unwrap();
+
+    assert_eq!(outcome.parts[0].content, "# 安装\n\n## 依赖\n");
+    assert_eq!(outcome.index, "# 指南\n\n- [安装](01-安装.md)\n\n# 附录\n\n- [术语](02-术语.md)\n");
+  }
+
+  /// 测试改写本地图片地址，远程图片和非图片的链接引用定义保持原样
+  #[test]
+  fn test_rewrite_local_images() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "![a](assets/a.png) ![b](https://example.com/b.png)\n<img src=\"c.png\">\n![d][logo]\n\n[logo]: assets/logo.png\n[doc]: other.md\n";
+    let rewritten = rewrite_local_images(&parser, content, |url| Some(format!("../{}", url))).unwrap();
+    assert_eq!(
+      rewritten,
+      "![a](../assets/a.png) ![b](https://example.com/b.png)\n<img src=\"../c.png\">\n![d][logo]\n\n[logo]: ../assets/logo.png\n[doc]: other.md\n"
+    );
+  }
+
+  /// 测试改写链接地址，图片和被图片引用的定义不受影响
+  #[test]
+  fn test_rewrite_links() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "[a](a.md) ![img](a.png) [b](<b c.md>)\n![logo][logo]\n\n[logo]: logo.png\n[doc]: doc.md\n";
+    let rewritten = rewrite_links(&parser, content, |url| Some(format!("x/{}", url))).unwrap();
+    assert_eq!(
+      rewritten,
+      "[a](x/a.md) ![img](a.png) [b](<x/b c.md>)\n![logo][logo]\n\n[logo]: logo.png\n[doc]: x/doc.md\n"
+    );
+  }
+
+  /// 测试合并文档：生成上级标题、调整级别、改写路径和锚点
+  #[test]
+  fn test_merge_documents() {
+    let parser = MarkdownParser::new().unwrap();
+    let sources = [
+      MergeSource {
+        path: PathBuf::from("/docs/parts/01-intro.md"),
+        content: "# Intro\n\n## Overview\n\n见 [用法](02-usage.md#overview) 和 [概述](#overview)\n\n![logo](../assets/logo.png)\n".to_string(),
+      },
+      MergeSource {
+        path: PathBuf::from("/docs/parts/02-usage.md"),
+        content: "# Usage\n\n## Overview\n\n[返回](01-intro.md)、[概述](#overview)、[API](api/index.md#list)\n".to_string(),
+      },
+    ];
+    let outcome =
+      merge_documents(&parser, &sources, Path::new("/docs/guide.md"), &MergeParent::Title("Guide".to_string()))
+        .unwrap();
+
+    assert_eq!(
+      outcome.content,
+      "# Guide\n\n## Intro\n\n### Overview\n\n见 [用法](#overview-1) 和 [概述](#overview)\n\n![logo](assets/logo.png)\n\n\
+       ## Usage\n\n### Overview\n\n[返回](#intro)、[概述](#overview-1)、[API](parts/api/index.md#list)\n"
+    );
+    assert_eq!((outcome.renamed_anchors, outcome.internal_links, outcome.clamped), (1, 2, 0));
+  }
+
+  /// 测试以第一个文档的标题作为上级标题
+  #[test]
+  fn test_merge_documents_first_file_as_parent() {
+    let parser = MarkdownParser::new().unwrap();
+    let source = |name: &str, content: &str| MergeSource { path: PathBuf::from(name), content: content.to_string() };
+    let sources = [
+      source("/d/index.md", "# 指南\n\n前言\n"),
+      source("/d/a.md", "# 安装\n\n## 依赖\n"),
+      source("/d/b.md", "正文\n"),
+    ];
+    let outcome = merge_documents(&parser, &sources, Path::new("/d/all.md"), &MergeParent::FirstFile).unwrap();
+    assert_eq!(outcome.content, "# 指南\n\n前言\n\n## 安装\n\n### 依赖\n\n正文\n");
+  }
+
+  fn line(n: usize) -> HeadingSelector {
+    HeadingSelector::Line(n)
+  }
+
+  /// 测试将章节移动到目标标题之前、之后或之内，级别随新位置调整
+  #[test]
+  fn test_move_section() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# A\n\n## A1\n\ntext\n\n# B\n\n## B1\n\n### B1a\n";
+
+    let moved = move_section(&parser, content, &line(9), &line(1), SectionPosition::Before, false).unwrap();
+    assert_eq!(moved.content, "# B1\n\n## B1a\n\n# A\n\n## A1\n\ntext\n\n# B\n");
+    assert_eq!(moved.level_shift, -1);
+
+    let moved = move_section(&parser, content, &line(3), &line(7), SectionPosition::After, false).unwrap();
+    assert_eq!(moved.content, "# A\n\n# B\n\n## B1\n\n### B1a\n\n# A1\n\ntext\n");
+
+    let moved = move_section(&parser, content, &line(1), &line(9), SectionPosition::Into, false).unwrap();
+    assert_eq!(moved.content, "# B\n\n## B1\n\n### B1a\n\n### A\n\n#### A1\n\ntext\n");
+
+    let err = move_section(&parser, content, &line(7), &line(11), SectionPosition::After, false).unwrap_err();
+    assert_eq!(err.code(), "validation_error");
+  }
+
+  /// 测试移动后按原有风格重新编号
+  #[test]
+  fn test_move_section_renumber() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 1. A\n\n# 2. B\n\n# 3. C\n";
+    let moved = move_section(&parser, content, &line(5), &line(1), SectionPosition::Before, true).unwrap();
+    assert!(moved.renumbered);
+    assert_eq!(moved.content, "# 1. C\n\n# 2. A\n\n# 3. B\n");
+  }
+
+  /// 测试删除章节及其子章节
+  #[test]
+  fn test_delete_section() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 1. A\n\n## 1.1. A1\n\n# 2. B\n\n## 2.1. B1\n\n# 3. C\n";
+
+    let deleted = delete_section(&parser, content, &line(5), false).unwrap();
+    assert_eq!(deleted.content, "# 1. A\n\n## 1.1. A1\n\n# 3. C\n");
+    assert_eq!((deleted.subsections, deleted.renumbered), (1, false));
+
+    let deleted = delete_section(&parser, content, &line(5), true).unwrap();
+    assert_eq!(deleted.content, "# 1. A\n\n## 1.1. A1\n\n# 2. C\n");
+
+    let deleted = delete_section(&parser, content, &line(9), false).unwrap();
+    assert_eq!(deleted.content, "# 1. A\n\n## 1.1. A1\n\n# 2. B\n\n## 2.1. B1\n");
+  }
+
+  /// 第 1 章包含以 # 开头的代码行的文档
+  const FENCED_DOC: &str = "# 1. 安装\n\n```bash\n# install deps\nnpm install\n```\n\n# 2. 使用\n\n正文\n";
+  const FENCED_INSTALL: &str = "# 1. 安装\n\n```bash\n# install deps\nnpm install\n```";
+
+  /// 测试拆分时代码块内形如标题的行不会成为拆分点
+  #[test]
+  fn test_split_skips_fenced_code() {
+    let outcome = split_document(&MarkdownParser::new().unwrap(), FENCED_DOC, 1).unwrap();
+    assert_eq!(outcome.parts.len(), 2);
+    assert_eq!(outcome.parts[0].content, format!("{}\n", FENCED_INSTALL));
+  }
+
+  /// 测试移动和删除章节时代码块保持完整，代码块内的行也不会被重新编号
+  #[test]
+  fn test_move_and_delete_skip_fenced_code() {
+    let parser = MarkdownParser::new().unwrap();
+    let moved = move_section(&parser, FENCED_DOC, &line(8), &line(1), SectionPosition::Before, true).unwrap();
+    assert_eq!(moved.content, "# 1. 使用\n\n正文\n\n# 2. 安装\n\n```bash\n# install deps\nnpm install\n```\n");
+
+    let deleted = delete_section(&parser, FENCED_DOC, &line(1), true).unwrap();
+    assert_eq!(deleted.content, "# 1. 使用\n\n正文\n");
+  }
+
+  /// 测试读取章节时代码块内形如标题的行不会截断章节
+  #[test]
+  fn test_read_section_skips_fenced_code() {
+    let excerpt = read_section(&MarkdownParser::new().unwrap(), FENCED_DOC, &line(1), false, None).unwrap();
+    assert_eq!(excerpt.content, FENCED_INSTALL);
+    assert!(excerpt.omitted_children.is_empty());
+  }
+
+  /// 测试替换内容中代码块内形如标题的行不视为子标题
+  #[test]
+  fn test_replace_section_skips_fenced_code() {
+    let parser = MarkdownParser::new().unwrap();
+    let outcome = replace_section(&parser, FENCED_DOC, &line(8), "```\n# 示例\n```", false, false, false).unwrap();
+    assert_eq!(outcome.content, format!("{}\n\n# 2. 使用\n\n```\n# 示例\n```\n", FENCED_INSTALL));
+  }
+
+  /// 测试已编号的文档：默认只改写 #，renumber 为 true 时重新生成编号
+  #[test]
+  fn test_shift_renumbers() {
+    let parser = MarkdownParser::new().unwrap();
+    let content = "# 1. A\n\n## 1.1. B\n\n#   2. 2024 Roadmap\n\n## 2.1. D\n";
+    let outcome = shift(content, 1, Some("2-2024-roadmap"), false).unwrap();
+    assert!(!outcome.renumbered);
+    assert_eq!(outcome.content, "# 1. A\n\n## 1.1. B\n\n##   2. 2024 Roadmap\n\n### 2.1. D\n");
+
+    let outcome = shift_heading_levels(&parser, content, 1, Some("2-2024-roadmap"), false, true).unwrap();
+    assert!(outcome.renumbered);
+    assert_eq!(outcome.content, "# 1. A\n\n## 1.1. B\n\n## 1.2. 2024 Roadmap\n\n### 1.2.1. D\n");
+  }
+}
@@ -0,0 +1,392 @@
+//! 文档统计模块
+//!
+//! 统计整篇文档和每个章节的字数、图片数、代码块数和预计阅读时间，并与标题大纲组合为带统计信息的章节树。
+//! 字数按中日韩文字逐字计数，其他文字按单词计数；代码块内容、链接地址和 HTML 标签不计入字数
+
+use crate::error::{MarkdownError, Result};
+use crate::image_localizer::collect_document_images;
+use crate::outline::{build_outline, child_sections, OutlineEntry};
+use crate::parser::MarkdownParser;
+use crate::tr;
+use serde::Serialize;
+use std::ops::Add;
+
+/// 中日韩文字的阅读速度（字/分钟）
+const CJK_CHARS_PER_MINUTE: f64 = 300.0;
+/// 其他文字的阅读速度（单词/分钟）
+const WORDS_PER_MINUTE: f64 = 200.0;
+
+/// 一段内容的统计信息
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct TextStats {
+  /// 字数：每个中日韩文字计为一个字，其他文字按单词计数
+  pub words: usize,
+  /// 其中中日韩文字的数量
+  pub cjk_chars: usize,
+  /// 图片数量
+  pub images: usize,
+  /// 代码块数量
+  pub code_blocks: usize,
+}
+
+impl TextStats {
+  /// 预计阅读时间（分钟，向上取整），没有文字时为 0
+  pub fn reading_minutes(&self) -> usize {
+    let other_words = (self.words - self.cjk_chars) as f64;
+    (self.cjk_chars as f64 / CJK_CHARS_PER_MINUTE + other_words / WORDS_PER_MINUTE).ceil() as usize
+  }
+}
+
+impl Add for TextStats {
+  type Output = TextStats;
+
+  fn add(self, other: TextStats) -> TextStats {
+    TextStats {
+      words: self.words + other.words,
+      cjk_chars: self.cjk_chars + other.cjk_chars,
+      images: self.images + other.images,
+      code_blocks: self.code_blocks + other.code_blocks,
+    }
+  }
+}
+
+impl std::iter::Sum for TextStats {
+  fn sum<I: Iterator<Item = TextStats>>(iter: I) -> TextStats {
+    iter.fold(TextStats::default(), Add::add)
+  }
+}
+
+/// 带统计信息的章节，统计范围包括其下所有子章节
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SectionOutline {
+  /// 标题级别（1-6）
+  pub level: usize,
+  /// 去掉编号后的标题文本
+  pub title: String,
+  /// 标题自带的章节编号，如 `2.3.`，没有编号时为空
+  pub numbering: Option<String>,
+  /// 标题锚点（不含 #）
+  pub anchor: String,
+  /// 标题所在行号（从 1 开始）
+  pub line_start: usize,
+  /// 章节最后一行的行号
+  pub line_end: usize,
+  /// 章节的统计信息
+  #[serde(flatten)]
+  pub stats: TextStats,
+  /// 预计阅读时间（分钟）
+  pub reading_minutes: usize,
+  /// 直接子章节
+  pub children: Vec<SectionOutline>,
+}
+
+/// 带统计信息的文档大纲
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DocumentOutline {
+  /// 标题总数
+  pub headings: usize,
+  /// H1-H6 各级标题的数量
+  pub headings_per_level: [usize; 6],
+  /// 整篇文档的统计信息
+  #[serde(flatten)]
+  pub stats: TextStats,
+  /// 预计阅读时间（分钟）
+  pub reading_minutes: usize,
+  /// 顶层章节
+  pub sections: Vec<SectionOutline>,
+}
+
+/// 生成带统计信息的文档大纲，只列出不超过 `max_level` 级的标题；标题统计和文档统计不受 `max_level` 影响
+pub fn document_outline(parser: &MarkdownParser, content: &str, max_level: usize) -> Result<DocumentOutline> {
+  let mst = parser.parse(content).map_err(|e| MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
+  let outline = build_outline(&mst, content.lines().count());
+
+  let mut line_stats = line_stats(content);
+  for image in collect_document_images(parser, &mst) {
+    if let Some(stats) = line_stats.get_mut(image.line_number.wrapping_sub(1)) {
+      stats.images += 1;
+    }
+  }
+
+  let mut headings_per_level = [0; 6];
+  for entry in &outline {
+    headings_per_level[entry.level.clamp(1, 6) - 1] += 1;
+  }
+
+  let stats: TextStats = line_stats.iter().copied().sum();
+  let sections = section_tree(&outline, None, &line_stats, max_level);
+  Ok(DocumentOutline {
+    headings: outline.len(),
+    headings_per_level,
+    stats,
+    reading_minutes: stats.reading_minutes(),
+    sections,
+  })
+}
+
+/// 将带统计信息的大纲渲染为按章节层级缩进的文本
+pub fn render_outline_text(outline: &DocumentOutline) -> String {
+  let levels: Vec<String> = (1..=6)
+    .zip(outline.headings_per_level)
+    .filter(|(_, count)| *count > 0)
+    .map(|(level, count)| format!("H{} × {}", level, count))
+    .collect();
+  let mut lines = vec![tr!(
+    "📑 {} 个标题（{}），共 {} 字，{} 张图片，{} 个代码块，预计阅读 {} 分钟",
+    outline.headings,
+    levels.join("、"),
+    outline.stats.words,
+    outline.stats.images,
+    outline.stats.code_blocks,
+    outline.reading_minutes
+  )];
+  lines.push(String::new());
+
+  if outline.sections.is_empty() {
+    lines.push(tr!("（文档中没有标题）").to_string());
+  }
+  let mut stack: Vec<(usize, &SectionOutline)> = outline.sections.iter().rev().map(|section| (0, section)).collect();
+  while let Some((depth, section)) = stack.pop() {
+    let text = match &section.numbering {
+      Some(numbering) => format!("{} {}", numbering, section.title),
+      None => section.title.clone(),
+    };
+    lines.push(tr!(
+      "{}- {} (#{}, 第{}-{}行)：{} 字，{} 张图片，{} 个代码块，约 {} 分钟",
+      "  ".repeat(depth),
+      text,
+      section.anchor,
+      section.line_start,
+      section.line_end,
+      section.stats.words,
+      section.stats.images,
+      section.stats.code_blocks,
+      section.reading_minutes
+    ));
+    stack.extend(section.children.iter().rev().map(|child| (depth + 1, child)));
+  }
+  lines.join("\n")
+}
+
+/// 递归构建 `parent` 的子章节树
+fn section_tree(
+  outline: &[OutlineEntry],
+  parent: Option<&OutlineEntry>,
+  line_stats: &[TextStats],
+  max_level: usize,
+) -> Vec<SectionOutline> {
+  child_sections(outline, parent)
+    .into_iter()
+    .filter(|entry| entry.level <= max_level)
+    .map(|entry| {
+      let range = entry.line_number.saturating_sub(1)..entry.end_line.min(line_stats.len());
+      let stats: TextStats = line_stats[range].iter().copied().sum();
+      let numbering = entry.text.strip_suffix(entry.title.as_str()).map(str::trim).filter(|n| !n.is_empty());
+      SectionOutline {
+        level: entry.level,
+        title: entry.title.clone(),
+        numbering: numbering.map(str::to_string),
+        anchor: entry.anchor.clone(),
+        line_start: entry.line_number,
+        line_end: entry.end_line,
+        stats,
+        reading_minutes: stats.reading_minutes(),
+        children: section_tree(outline, Some(entry), line_stats, max_level),
+      }
+    })
+    .collect()
+}
+
+/// 统计每一行的字数和代码块：代码块计在开始围栏所在的行，代码块内的内容不计入字数
+fn line_stats(content: &str) -> Vec<TextStats> {
+  let mut fence: Option<String> = None;
+  content
+    .lines()
+    .map(|line| {
+      let mut stats = TextStats::default();
+      let marker = fence_marker(line);
+      match (&fence, marker) {
+        (Some(open), Some(marker))
+          if marker.starts_with(open.as_str()) && line.trim()[marker.len()..].trim().is_empty() =>
+        {
+          fence = None
+        }
+        (Some(_), _) => {}
+        (None, Some(marker)) => {
+          fence = Some(marker.to_string());
+          stats.code_blocks = 1;
+        }
+        (None, None) => {
+          let (words, cjk_chars) = count_words(line);
+          stats.words = words;
+          stats.cjk_chars = cjk_chars;
+        }
+      }
+      stats
+    })
+    .collect()
+}
+
+/// 标记每一行是否属于代码块（包括开始和结束围栏），代码块内形如标题的行不是标题
+pub(crate) fn fenced_lines(content: &str) -> Vec<bool> {
+  let mut fence: Option<String> = None;
+  content
+    .lines()
+    .map(|line| {
+      match (&fence, fence_marker(line)) {
+        (Some(open), Some(marker))
+          if marker.starts_with(open.as_str()) && line.trim()[marker.len()..].trim().is_empty() =>
+        {
+          fence = None
+        }
+        (Some(_), _) => {}
+        (None, Some(marker)) => fence = Some(marker.to_string()),
+        (None, None) => return false,
+      }
+      true
+    })
+    .collect()
+}
+
+/// 代码块围栏：缩进不超过 3 个空格的 3 个以上的 ` 或 ~
+pub(crate) fn fence_marker(line: &str) -> Option<&str> {
+  let trimmed = line.trim_start_matches(' ');
+  if line.len() - trimmed.len() > 3 {
+    return None;
+  }
+  let fence_char = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+  let len = trimmed.len() - trimmed.trim_start_matches(fence_char).len();
+  (len >= 3).then(|| &trimmed[..len])
+}
+
+/// 统计一行文字的字数，返回（字数，其中中日韩文字的数量）
+///
+/// 链接引用定义整行不计，链接和图片的地址以及 HTML 标签不计入字数
+pub fn count_words(line: &str) -> (usize, usize) {
+  let trimmed = line.trim_start();
+  if trimmed.starts_with('[') && trimmed.contains("]:") && !trimmed.contains("](") {
+    return (0, 0);
+  }
+
+  let mut words = 0;
+  let mut cjk_chars = 0;
+  let mut in_word = false;
+  let mut chars = line.char_indices().peekable();
+  while let Some((i, c)) = chars.next() {
+    // 跳过链接或图片的地址 ](...)
+    if c == ']' && line[i + 1..].starts_with('(') {
+      in_word = false;
+      if let Some(end) = line[i..].find(')') {
+        while chars.peek().is_some_and(|(j, _)| *j <= i + end) {
+          chars.next();
+        }
+      }
+      continue;
+    }
+    // 跳过 HTML 标签和自动链接 <...>
+    if c == '<' && line[i + 1..].starts_with(|next: char| next.is_ascii_alphabetic() || next == '/' || next == '!') {
+      in_word = false;
+      if let Some(end) = line[i..].find('>') {
+        while chars.peek().is_some_and(|(j, _)| *j <= i + end) {
+          chars.next();
+        }
+      }
+      continue;
+    }
+
+    if is_cjk(c) {
+      cjk_chars += 1;
+      in_word = false;
+    } else if c.is_alphanumeric() || (in_word && (c == '\'' || c == '_')) {
+      if !in_word {
+        words += 1;
+        in_word = true;
+      }
+    } else {
+      in_word = false;
+    }
+  }
+  (words + cjk_chars, cjk_chars)
+}
+
+/// 是否为中日韩文字（汉字、假名、谚文）
+fn is_cjk(c: char) -> bool {
+  matches!(c,
+    '\u{3040}'..='\u{30FF}' // 平假名、片假名
+    | '\u{3400}'..='\u{4DBF}' // 扩展 A
+    | '\u{4E00}'..='\u{9FFF}' // 基本汉字
+    | '\u{AC00}'..='\u{D7AF}' // 谚文音节
+    | '\u{F900}'..='\u{FAFF}' // 兼容汉字
+    | '\u{20000}'..='\u{2EBEF}' // 扩展 B-F
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 测试代码块行标记：结束围栏不能短于开始围栏，也不能换用另一种字符
+  #[test]
+  fn test_fenced_lines() {
+    let content = "# A\n````\n```\n# b\n````\n~~~\n# c\n```\n~~~\nd\n";
+    let expected = vec![false, true, true, true, true, true, true, true, true, false];
+    assert_eq!(fenced_lines(content), expected);
+  }
+
+  /// 测试字数统计：汉字逐字计数，其他文字按单词计数，忽略链接地址和 HTML 标签
+  #[test]
+  fn test_count_words() {
+    assert_eq!(count_words("Markdown 工具 supports CJK"), (5, 2));
+    assert_eq!(count_words("## 1.2. 背景"), (4, 2));
+    assert_eq!(count_words("见[官方文档](https://example.com/docs) and <b>bold</b>"), (7, 5));
+    assert_eq!(count_words("![架构图](assets/arch.png)"), (3, 3));
+    assert_eq!(count_words("[docs]: https://example.com/docs"), (0, 0));
+    assert_eq!(count_words("it's snake_case"), (2, 0));
+  }
+
+  /// 测试阅读时间估算
+  #[test]
+  fn test_reading_minutes() {
+    assert_eq!(TextStats::default().reading_minutes(), 0);
+    let stats = TextStats { words: 900, cjk_chars: 600, ..TextStats::default() };
+    assert_eq!(stats.reading_minutes(), 4);
+  }
+
+  /// 测试带统计信息的大纲：章节统计包含子章节，代码块内容不计入字数
+  #[test]
+  fn test_document_outline() {
+    let content =
+      "# 1. 介绍\n\n这是简介。\n\n## 1.1. 示例\n\n```rust\nlet x = 1;\n```\n\n![图](a.png)\n\n# 2. 附录\n\nSee also.\n";
+    let outline = document_outline(&MarkdownParser::new().unwrap(), content, 6).unwrap();
+
+    assert_eq!((outline.headings, outline.headings_per_level), (3, [2, 1, 0, 0, 0, 0]));
+    assert_eq!((outline.stats.images, outline.stats.code_blocks), (1, 1));
+
+    let intro = &outline.sections[0];
+    assert_eq!((intro.numbering.as_deref(), intro.title.as_str()), (Some("1."), "介绍"));
+    assert_eq!((intro.line_start, intro.line_end), (1, 12));
+    assert_eq!((intro.stats.words, intro.stats.images, intro.stats.code_blocks), (12, 1, 1));
+    assert_eq!(intro.children[0].anchor, "11-示例");
+
+    let appendix = &outline.sections[1];
+    assert_eq!((appendix.stats.words, appendix.stats.cjk_chars, appendix.reading_minutes), (5, 2, 1));
+
+    let outline = document_outline(&MarkdownParser::new().unwrap(), content, 1).unwrap();
+    assert!(outline.sections[0].children.is_empty());
+    assert_eq!(outline.headings, 3);
+  }
+
+  /// 测试大纲文本按章节层级缩进
+  #[test]
+  fn test_render_outline_text() {
+    let content = "# 介绍\n\n简介\n\n## 背景\n\n# 附录\n";
+    let outline = document_outline(&MarkdownParser::new().unwrap(), content, 6).unwrap();
+    assert_eq!(
+      render_outline_text(&outline),
+      "📑 3 个标题（H1 × 2、H2 × 1），共 8 字，0 张图片，0 个代码块，预计阅读 1 分钟\n\n\
+       - 介绍 (#介绍, 第1-6行)：6 字，0 张图片，0 个代码块，约 1 分钟\n\
+       \x20 - 背景 (#背景, 第5-6行)：2 字，0 张图片，0 个代码块，约 1 分钟\n\
+       - 附录 (#附录, 第7-7行)：2 字，0 张图片，0 个代码块，约 1 分钟"
+    );
+  }
+}
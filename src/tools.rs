@@ -1,16 +1,98 @@
-use std::path::Path;
-
-use crate::config::{CheckHeadingConfig, GenerateChapterConfig, LocalizeImagesConfig, RemoveChapterConfig};
-use crate::image_localizer::ImageLocalizer;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::asset_cleaner::AssetCleaner;
+use crate::config::{
+  find_tool, CheckHeadingConfig, CheckLinksConfig, CleanAssetsAction, CleanAssetsConfig, DeleteSectionConfig,
+  ExtractSectionConfig, GenerateChapterConfig, LocalizeImagesConfig, MergeDocumentsConfig, MoveSectionConfig,
+  OutlineConfig, OutlineFormat, PublishImagesConfig, ReadSectionConfig, RemoveChapterConfig, ReplaceSectionConfig,
+  SectionPosition, ShiftHeadingLevelsConfig, ShowEffectiveConfigConfig, SplitDocumentConfig, TOOLS,
+};
+use crate::error::MarkdownError;
+use crate::image_localizer::{resolve_local_image_path, ImageLocalizer, LocalizeProgress, ProgressCallback};
+use crate::image_publisher::ImagePublisher;
+use crate::links::{check_internal_links, ExternalLinkChecker};
 use crate::mst::NumberingConfig;
 use crate::numbering::NumberingGenerator;
 use crate::parser::MarkdownParser;
+use crate::project_config::{effective_settings, settings_for_args, PROJECT_CONFIG_FILE};
 use crate::renderer::MarkdownRenderer;
-use crate::utils::execute_markdown_operation;
+use crate::sandbox::Sandbox;
+use crate::sections::{
+  delete_section, extract_section, merge_documents, move_section, read_section, relocate_relative_url, replace_section,
+  replace_section_lines, rewrite_local_images, shift_heading_levels, split_document, MergeParent, MergeSource,
+};
+use crate::statistics::{document_outline, render_outline_text};
+use crate::tr;
+use crate::utils::{execute_markdown_operation, natural_cmp, PathTemplate};
+use rmcp::service::{RequestContext, RoleServer};
 use rmcp::{model::*, ErrorData as McpError};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 
 pub struct MarkdownToolsImpl;
 
+/// 图片本地化的运行选项，默认不报告进度、不会被取消、不限制访问范围
+#[derive(Default)]
+pub struct LocalizeOptions {
+  /// 每处理完一张图片调用一次的进度回调
+  pub progress: Option<ProgressCallback>,
+  /// 取消令牌，取消时不写回文档
+  pub cancellation: CancellationToken,
+  /// 允许读写的根目录
+  pub sandbox: Sandbox,
+}
+
+/// 将工具的进度转发为 MCP 进度通知
+///
+/// 客户端在请求中提供了 progress token 时才会创建；通知按顺序在后台发送，
+/// 工具返回前调用 `finish` 等待所有通知发送完毕
+pub struct ProgressNotifier {
+  sender: mpsc::UnboundedSender<LocalizeProgress>,
+  task: JoinHandle<()>,
+}
+
+impl ProgressNotifier {
+  /// 根据请求上下文创建，客户端未请求进度时返回 None
+  pub fn from_context(context: &RequestContext<RoleServer>) -> Option<Self> {
+    let progress_token = context.meta.get_progress_token()?;
+    let peer = context.peer.clone();
+    let (sender, mut receiver) = mpsc::unbounded_channel::<LocalizeProgress>();
+
+    let task = tokio::spawn(async move {
+      while let Some(progress) = receiver.recv().await {
+        let notification = ProgressNotificationParam {
+          progress_token: progress_token.clone(),
+          progress: progress.processed as f64,
+          total: Some(progress.total as f64),
+          message: Some(format!("{}/{} {}", progress.processed, progress.total, progress.url)),
+        };
+        if peer.notify_progress(notification).await.is_err() {
+          break;
+        }
+      }
+    });
+
+    Some(Self { sender, task })
+  }
+
+  /// 供本地化器使用的进度回调
+  pub fn callback(&self) -> ProgressCallback {
+    let sender = self.sender.clone();
+    Box::new(move |progress| {
+      let _ = sender.send(progress);
+    })
+  }
+
+  /// 等待已产生的进度通知全部发送
+  pub async fn finish(self) {
+    drop(self.sender);
+    let _ = self.task.await;
+  }
+}
+
 impl MarkdownToolsImpl {
   pub async fn generate_chapter_number_impl(
     config: GenerateChapterConfig,
@@ -22,9 +104,9 @@ impl MarkdownToolsImpl {
     execute_markdown_operation(
       &config.full_file_path,
       |content| {
-        let parser = MarkdownParser::new().map_err(|e| format!("创建解析器失败: {}", e))?;
+        let parser = MarkdownParser::new().map_err(|e| tr!("创建解析器失败: {}", e))?;
 
-        let mut mst = parser.parse(content).map_err(|e| format!("解析 Markdown 失败: {}", e))?;
+        let mut mst = parser.parse(content).map_err(|e| tr!("解析 Markdown 失败: {}", e))?;
 
         let numbering_config = NumberingConfig {
           ignore_h1: config.ignore_h1,
@@ -40,7 +122,7 @@ impl MarkdownToolsImpl {
 
         Ok(result)
       },
-      format!("成功为文件 {} 生成章节编号", config.full_file_path),
+      tr!("成功为文件 {} 生成章节编号", config.full_file_path),
       config.save_as_new_file,
       new_full_file_path.as_str(),
     )
@@ -56,16 +138,16 @@ impl MarkdownToolsImpl {
     execute_markdown_operation(
       &config.full_file_path,
       |content| {
-        let parser = MarkdownParser::new().map_err(|e| format!("创建解析器失败: {}", e))?;
+        let parser = MarkdownParser::new().map_err(|e| tr!("创建解析器失败: {}", e))?;
 
-        let mst = parser.parse(content).map_err(|e| format!("解析 Markdown 失败: {}", e))?;
+        let mst = parser.parse(content).map_err(|e| tr!("解析 Markdown 失败: {}", e))?;
 
         let renderer = MarkdownRenderer::new();
         let result = renderer.render_without_numbering(&mst);
 
         Ok(result)
       },
-      format!("成功清除文件 {} 的所有章节编号", config.full_file_path),
+      tr!("成功清除文件 {} 的所有章节编号", config.full_file_path),
       config.save_as_new_file,
       new_full_file_path.as_str(),
     )
@@ -79,20 +161,18 @@ impl MarkdownToolsImpl {
 
       // 解析文档
       let parser =
-        MarkdownParser::new().map_err(|e| crate::error::MarkdownError::ParseError(format!("创建解析器失败: {}", e)))?;
+        MarkdownParser::new().map_err(|e| crate::error::MarkdownError::ParseError(tr!("创建解析器失败: {}", e)))?;
 
       let mst = parser
         .parse(&content)
-        .map_err(|e| crate::error::MarkdownError::ParseError(format!("解析 Markdown 失败: {}", e)))?;
+        .map_err(|e| crate::error::MarkdownError::ParseError(tr!("解析 Markdown 失败: {}", e)))?;
 
       // 验证标题结构
-      let validation_result = Self::validate_heading_structure(&mst);
+      let validation_result = Self::validate_heading_structure(&mst, &config);
 
       match validation_result {
-        Ok(report) => Ok(CallToolResult::success(vec![Content::text(format!("✅ 标题验证通过\n\n{}", report))])),
-        Err(errors) => {
-          Ok(CallToolResult::error(vec![Content::text(format!("❌ 标题验证失败\n\n{}", errors.join("\n")))]))
-        }
+        Ok(report) => Ok(CallToolResult::success(vec![Content::text(tr!("✅ 标题验证通过\n\n{}", report))])),
+        Err(errors) => Ok(CallToolResult::error(vec![Content::text(tr!("❌ 标题验证失败\n\n{}", errors.join("\n")))])),
       }
     })();
 
@@ -100,13 +180,13 @@ impl MarkdownToolsImpl {
   }
 
   /// 验证标题结构
-  fn validate_heading_structure(mst: &crate::mst::MSTNode) -> Result<String, Vec<String>> {
+  fn validate_heading_structure(mst: &crate::mst::MSTNode, config: &CheckHeadingConfig) -> Result<String, Vec<String>> {
     let headers = mst.get_headers();
     let mut errors = Vec::new();
     let mut report_lines = Vec::new();
 
     if headers.is_empty() {
-      return Ok("文档中没有标题行。".to_string());
+      return Ok(tr!("文档中没有标题行。").to_string());
     }
 
     // 统计信息
@@ -117,10 +197,10 @@ impl MarkdownToolsImpl {
       }
     }
 
-    report_lines.push(format!("📊 标题统计："));
+    report_lines.push(tr!("📊 标题统计：").to_string());
     for level in 1..=6 {
       if let Some(count) = level_counts.get(&level) {
-        report_lines.push(format!("  H{}: {} 个", level, count));
+        report_lines.push(tr!("  H{}: {} 个", level, count));
       }
     }
     report_lines.push(String::new());
@@ -140,8 +220,18 @@ impl MarkdownToolsImpl {
         continue;
       }
 
-      // 验证层级结构
-      if let Some(prev) = prev_level {
+      // 验证最深级别
+      if current_level > config.max_heading_level as usize {
+        errors.push(tr!(
+          "第{}行：标题级别 H{} 超过允许的最深级别 H{}",
+          line_number,
+          current_level,
+          config.max_heading_level
+        ));
+      }
+
+      // 验证层级结构，允许跳级时跳过
+      if let Some(prev) = prev_level.filter(|_| !config.allow_skipped_levels) {
         // 更新层级栈
         while let Some(&stack_level) = level_stack.last() {
           if stack_level >= current_level {
@@ -153,7 +243,7 @@ impl MarkdownToolsImpl {
 
         // 检查是否跳级
         if current_level > prev + 1 && level_stack.is_empty() {
-          errors.push(format!(
+          errors.push(tr!(
             "第{}行：标题级别跳级，从 H{} 直接跳到 H{}（跳过了 H{}）",
             line_number,
             prev,
@@ -164,7 +254,7 @@ impl MarkdownToolsImpl {
           // 检查是否相对于栈顶跳级
           if let Some(&stack_top) = level_stack.last() {
             if current_level > stack_top + 1 {
-              errors.push(format!(
+              errors.push(tr!(
                 "第{}行：标题级别跳级，从 H{} 直接跳到 H{}（跳过了 H{}）",
                 line_number,
                 stack_top,
@@ -181,7 +271,7 @@ impl MarkdownToolsImpl {
     }
 
     if errors.is_empty() {
-      report_lines.push("✅ 所有标题格式和层级结构都正确。".to_string());
+      report_lines.push(tr!("✅ 所有标题格式和层级结构都正确。").to_string());
       Ok(report_lines.join("\n"))
     } else {
       Err(errors)
@@ -194,28 +284,28 @@ impl MarkdownToolsImpl {
     let expected_prefix = "#".repeat(expected_level);
 
     if !raw.starts_with(&expected_prefix) {
-      return Err(format!("第{}行：标题格式错误，应该以 {} 开头", line_number, expected_prefix));
+      return Err(tr!("第{}行：标题格式错误，应该以 {} 开头", line_number, expected_prefix));
     }
 
     // 检查#前面是否有空格
     if raw.chars().next() != Some('#') {
-      return Err(format!("第{}行：标题格式错误，# 符号前不能有空格或其他字符", line_number));
+      return Err(tr!("第{}行：标题格式错误，# 符号前不能有空格或其他字符", line_number));
     }
 
     // 检查#后面是否有且仅有一个空格
     let after_hashes = &raw[expected_level..];
     if !after_hashes.starts_with(' ') {
-      return Err(format!("第{}行：标题格式错误，{} 后面必须有一个空格", line_number, expected_prefix));
+      return Err(tr!("第{}行：标题格式错误，{} 后面必须有一个空格", line_number, expected_prefix));
     }
 
     if after_hashes.starts_with("  ") {
-      return Err(format!("第{}行：标题格式错误，{} 后面只能有一个空格", line_number, expected_prefix));
+      return Err(tr!("第{}行：标题格式错误，{} 后面只能有一个空格", line_number, expected_prefix));
     }
 
     // 检查是否有标题内容
     let title_content = after_hashes.trim_start();
     if title_content.is_empty() {
-      return Err(format!("第{}行：标题格式错误，缺少标题内容", line_number));
+      return Err(tr!("第{}行：标题格式错误，缺少标题内容", line_number));
     }
 
     Ok(())
@@ -228,65 +318,876 @@ impl MarkdownToolsImpl {
     let stem = path.file_stem().unwrap_or_else(|| std::ffi::OsStr::new("file")).to_str().unwrap_or("file");
     let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("md");
 
-    let new_path = match new_full_file_path {
-      Some(name) => Path::new(name).to_path_buf(),
+    let new_path = match new_full_file_path.filter(|name| !name.is_empty()) {
+      Some(name) => Path::new(&PathTemplate::new(full_file_path).resolve(name)).to_path_buf(),
       None => parent.join(format!("{}_{}.{}", stem, default_suffix, extension)),
     };
     new_path.to_str().unwrap().to_string()
   }
 
-  /// 本地化图片实现
-  pub async fn localize_images_impl(config: LocalizeImagesConfig) -> Result<CallToolResult, McpError> {
+  /// 图片本地化实现，支持进度回调、取消和沙箱；取消时不写回文档
+  pub async fn localize_images_impl(
+    config: LocalizeImagesConfig,
+    options: LocalizeOptions,
+  ) -> Result<CallToolResult, McpError> {
+    let LocalizeOptions { progress, cancellation, sandbox } = options;
+
     // 验证文件
     if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
-      return Ok(CallToolResult::error(vec![Content::text(format!("文件验证失败: {}", e))]));
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
     }
 
     // 读取文件内容
     let content = match crate::utils::read_file_content(&config.full_file_path) {
       Ok(content) => content,
-      Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("读取文件失败: {}", e))])),
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
     };
 
     // 解析文档
     let parser = match MarkdownParser::new() {
       Ok(parser) => parser,
-      Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("创建解析器失败: {}", e))])),
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
     };
 
     let mut mst = match parser.parse(&content) {
       Ok(mst) => mst,
-      Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("解析 Markdown 失败: {}", e))])),
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("解析 Markdown 失败: {}", e))])),
     };
 
     // 创建图片本地化器
-    let localizer = ImageLocalizer::new(config.clone());
+    let mut localizer =
+      ImageLocalizer::new(config.clone()).with_cancellation(cancellation.clone()).with_sandbox(sandbox);
+    if let Some(progress) = progress {
+      localizer = localizer.with_progress(progress);
+    }
 
     // 本地化图片
     let results = match localizer.localize_images(&mut mst).await {
       Ok(results) => results,
-      Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("图片本地化失败: {}", e))])),
+      Err(e @ MarkdownError::Cancelled) => {
+        return Ok(e.to_tool_result(tr!("{}，文档未修改: {}", e, config.full_file_path)));
+      }
+      Err(e) => return Ok(e.to_tool_result(tr!("图片本地化失败: {}", e))),
     };
 
-    // 渲染更新后的文档
-    let renderer = MarkdownRenderer::new();
-    let new_content = renderer.render(&mst);
+    // 只把改写后的图片地址写回原文
+    let new_content = MarkdownRenderer::new().render_into(&content, &mst);
 
     // 写回文件
-    let save_full_file_path = match config.new_full_file_path {
-      Some(p) => p,
-      None => config.full_file_path.clone(),
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
+    if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &new_content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    // 生成结果报告
+    let mut report = vec![tr!("✅ 处理完毕: {}", config.full_file_path)];
+    report.extend(results);
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 发布本地图片实现：内嵌为 data URI 或改写为远程 URL，内嵌时只读取沙箱根目录内的图片
+  pub async fn publish_images_impl(config: PublishImagesConfig, sandbox: &Sandbox) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    // 解析文档
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let mut mst = match parser.parse(&content) {
+      Ok(mst) => mst,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("解析 Markdown 失败: {}", e))])),
+    };
+
+    // 发布图片
+    let publisher = ImagePublisher::new(config.clone()).with_sandbox(sandbox.clone());
+    let results = match publisher.publish_images(&mut mst) {
+      Ok(results) => results,
+      Err(e) => return Ok(e.to_tool_result(tr!("图片发布失败: {}", e))),
     };
+
+    // 只把发布后的图片地址写回原文
+    let new_content = MarkdownRenderer::new().render_into(&content, &mst);
+
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
     if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &new_content) {
-      return Ok(CallToolResult::error(vec![Content::text(format!("写入文件失败: {}", e))]));
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
     }
 
     // 生成结果报告
-    let mut report = vec![format!("✅ 处理完毕: {}", config.full_file_path)];
+    let mut report = vec![tr!("✅ 处理完毕: {}", config.full_file_path)];
+    if results.is_empty() {
+      report.push(tr!("文档中没有需要处理的本地图片").to_string());
+    }
     report.extend(results);
 
     Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
   }
+
+  /// 标题级别调整实现：整体或按锚点升降标题级别，按需重新生成编号
+  pub async fn shift_heading_levels_impl(config: ShiftHeadingLevelsConfig) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    // 调整标题级别
+    let outcome = match shift_heading_levels(
+      &parser,
+      &content,
+      config.shift,
+      config.anchor.as_deref(),
+      config.clamp,
+      config.renumber,
+    ) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("调整标题级别失败: {}", e))),
+    };
+
+    // 写回文件
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
+    if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &outcome.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    // 生成结果报告
+    let mut report = vec![tr!(
+      "✅ 已将 {} 个标题的级别调整 {}，保存为: {}",
+      outcome.shifted,
+      format!("{:+}", config.shift),
+      save_full_file_path
+    )];
+    if outcome.clamped > 0 {
+      report.push(tr!("⚠️ {} 个标题超出 H1-H6 的范围，已限制在范围内", outcome.clamped));
+    }
+    if outcome.renumbered {
+      report.push(tr!("🔢 文档原有章节编号，已按原来的编号风格重新生成").to_string());
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 文档大纲实现：返回带统计信息的章节树
+  pub async fn outline_impl(config: OutlineConfig) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let outline = match document_outline(&parser, &content, config.max_level as usize) {
+      Ok(outline) => outline,
+      Err(e) => return Ok(e.to_tool_result(tr!("生成大纲失败: {}", e))),
+    };
+
+    let text = match config.format {
+      OutlineFormat::Text => render_outline_text(&outline),
+      OutlineFormat::Json => serde_json::to_string_pretty(&outline)
+        .map_err(|e| McpError::internal_error(tr!("序列化大纲失败: {}", e), None))?,
+    };
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+  }
+
+  /// 链接检查实现：检查文档内的锚点链接和指向本地文件的相对链接，允许的目录之外的文件不检查；
+  /// 设置 check_external 时再通过网络检查 http(s) 外部链接
+  pub async fn check_links_impl(config: CheckLinksConfig, sandbox: &Sandbox) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let md_dir = Path::new(&config.full_file_path).parent().unwrap_or(Path::new("."));
+    let allowed = |path: &Path| sandbox.check_path(&path.to_string_lossy()).is_ok();
+    let mut report = match check_internal_links(&parser, &content, md_dir, allowed) {
+      Ok(report) => report,
+      Err(e) => return Ok(e.to_tool_result(tr!("检查链接失败: {}", e))),
+    };
+    if config.check_external {
+      let timeout = std::time::Duration::from_secs(config.timeout_secs);
+      match ExternalLinkChecker::new(timeout, config.max_concurrency, &config.ignore_domains) {
+        Ok(checker) => checker.check(&mut report).await,
+        Err(e) => return Ok(e.to_tool_result(tr!("检查链接失败: {}", e))),
+      }
+    }
+
+    let mut lines = vec![tr!(
+      "🔗 共检查 {} 个链接，跳过 {} 个外部链接和 {} 个无法检查的链接",
+      report.checked,
+      report.external.len(),
+      report.skipped
+    )];
+    if !report.redirected.is_empty() {
+      lines.push(tr!("↪️ {} 个外部链接发生了重定向：", report.redirected.len()));
+      for redirected in &report.redirected {
+        lines.push(tr!(
+          "- 第 {} 行第 {} 列 {}：{} 重定向到 {}",
+          redirected.link.line_number,
+          redirected.link.column,
+          redirected.link,
+          redirected.status,
+          redirected.location
+        ));
+      }
+    }
+    if report.broken.is_empty() {
+      lines.push(tr!("✅ 没有发现失效的链接").to_string());
+      return Ok(CallToolResult::success(vec![Content::text(lines.join("\n"))]));
+    }
+
+    lines.push(tr!("❌ 发现 {} 个失效的链接：", report.broken.len()));
+    for broken in &report.broken {
+      lines.push(tr!(
+        "- 第 {} 行第 {} 列 {}：{}",
+        broken.link.line_number,
+        broken.link.column,
+        broken.link,
+        broken.problem
+      ));
+      if let Some(suggestion) = &broken.suggestion {
+        lines.push(tr!("  💡 是否应为 {}？", suggestion));
+      }
+    }
+    Ok(CallToolResult::error(vec![Content::text(lines.join("\n"))]))
+  }
+
+  /// 章节读取实现：返回单个章节的原文，可选地省略子章节或按字符数截断
+  pub async fn read_section_impl(config: ReadSectionConfig) -> Result<CallToolResult, McpError> {
+    let selector = config.selector()?;
+
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let excerpt = match read_section(&parser, &content, &selector, config.include_children, config.max_chars) {
+      Ok(excerpt) => excerpt,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取章节失败: {}", e))),
+    };
+
+    // 截断和省略的说明以 HTML 注释附加在末尾，不影响 Markdown 的渲染
+    let mut text = excerpt.content;
+    if let Some(line_number) = excerpt.truncated_at {
+      let shown = text.chars().count();
+      let note =
+        tr!("已截断：显示了 {} / {} 个字符，第 {} 行及之后的内容未显示", shown, excerpt.total_chars, line_number);
+      text.push_str(&format!("\n\n<!-- {} -->", note));
+    }
+    if !excerpt.omitted_children.is_empty() {
+      let anchors: Vec<String> = excerpt.omitted_children.iter().map(|entry| format!("#{}", entry.anchor)).collect();
+      let note = tr!("省略了 {} 个子章节：{}", anchors.len(), anchors.join("、"));
+      text.push_str(&format!("\n\n<!-- {} -->", note));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+  }
+
+  /// 章节替换实现：用新内容替换章节，文档的其余部分保持原样
+  pub async fn replace_section_impl(config: ReplaceSectionConfig) -> Result<CallToolResult, McpError> {
+    let selector = config.selector()?;
+
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    // 替换章节
+    let outcome = match replace_section(
+      &parser,
+      &content,
+      &selector,
+      &config.content,
+      config.replace_heading,
+      config.adjust_levels,
+      config.renumber,
+    ) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("替换章节失败: {}", e))),
+    };
+
+    // 写回文件
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
+    if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &outcome.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    // 生成结果报告
+    let mut report = vec![tr!(
+      "✅ 已替换章节 {}（原第 {}-{} 行，替换后 {} 行），保存为: {}",
+      outcome.section.text,
+      outcome.section.line_number,
+      outcome.section.end_line,
+      outcome.new_lines,
+      save_full_file_path
+    )];
+    if outcome.level_shift != 0 {
+      report.push(tr!("📐 新内容的标题级别调整 {}", format!("{:+}", outcome.level_shift)));
+    }
+    if outcome.clamped > 0 {
+      report.push(tr!("⚠️ {} 个标题超出 H1-H6 的范围，已限制在范围内", outcome.clamped));
+    }
+    if outcome.renumbered {
+      report.push(tr!("🔢 已按原来的编号风格重新生成章节编号").to_string());
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 章节移动实现：将章节移动到目标标题之前、之后或之内，可选地重新生成编号
+  pub async fn move_section_impl(config: MoveSectionConfig) -> Result<CallToolResult, McpError> {
+    let selector = config.selector()?;
+    let target_selector = config.target_selector()?;
+
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    // 移动章节
+    let outcome = match move_section(&parser, &content, &selector, &target_selector, config.position, config.renumber) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("移动章节失败: {}", e))),
+    };
+
+    // 写回文件
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
+    if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &outcome.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    // 生成结果报告
+    let (section, target) = (&outcome.section.text, &outcome.target.text);
+    let mut report = vec![match config.position {
+      SectionPosition::Before => tr!("✅ 已将章节 {} 移动到 {} 之前，保存为: {}", section, target, save_full_file_path),
+      SectionPosition::After => tr!("✅ 已将章节 {} 移动到 {} 之后，保存为: {}", section, target, save_full_file_path),
+      SectionPosition::Into => tr!("✅ 已将章节 {} 移动到 {} 之内，保存为: {}", section, target, save_full_file_path),
+    }];
+    if outcome.level_shift != 0 {
+      report.push(tr!("📐 章节的标题级别调整 {}", format!("{:+}", outcome.level_shift)));
+    }
+    if outcome.renumbered {
+      report.push(tr!("🔢 已按原来的编号风格重新生成章节编号").to_string());
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 章节删除实现：删除章节及其所有子章节，可选地重新生成编号
+  pub async fn delete_section_impl(config: DeleteSectionConfig) -> Result<CallToolResult, McpError> {
+    let selector = config.selector()?;
+
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    // 删除章节
+    let outcome = match delete_section(&parser, &content, &selector, config.renumber) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("删除章节失败: {}", e))),
+    };
+
+    // 写回文件
+    let save_full_file_path = config.get_resolved_new_file_path().unwrap_or_else(|| config.full_file_path.clone());
+    if let Err(e) = crate::utils::write_file_content(&save_full_file_path, &outcome.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    // 生成结果报告
+    let mut report = vec![tr!(
+      "✅ 已删除章节 {}（第 {}-{} 行，包含 {} 个子章节），保存为: {}",
+      outcome.section.text,
+      outcome.section.line_number,
+      outcome.section.end_line,
+      outcome.subsections,
+      save_full_file_path
+    )];
+    if outcome.renumbered {
+      report.push(tr!("🔢 已按原来的编号风格重新生成章节编号").to_string());
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 章节提取实现：将章节写入新文档，可选地在原文中替换为指向新文档的链接
+  pub async fn extract_section_impl(
+    config: ExtractSectionConfig,
+    sandbox: &Sandbox,
+  ) -> Result<CallToolResult, McpError> {
+    let selector = config.selector()?;
+
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    // 提取章节
+    let section = match extract_section(&parser, &content, &selector) {
+      Ok(section) => section,
+      Err(e) => return Ok(e.to_tool_result(tr!("提取章节失败: {}", e))),
+    };
+
+    // 写入新文档，未指定路径时以锚点命名，锚点为空时使用行号
+    let file_stem = match section.heading.anchor.as_str() {
+      "" => format!("section-{}", section.heading.line_number),
+      anchor => anchor.to_string(),
+    };
+    let output_file_path = config.get_resolved_output_file_path(&file_stem);
+    let output_file_path = sandbox.check_path(&output_file_path)?.to_string_lossy().into_owned();
+    if Path::new(&output_file_path) == Path::new(&config.full_file_path) {
+      return Err(MarkdownError::ValidationError(tr!("新文档不能与原文档相同: {}", output_file_path)).into());
+    }
+    if Path::new(&output_file_path).exists() && !config.overwrite {
+      let e = MarkdownError::ValidationError(tr!("文件已存在: {}，设置 overwrite=true 可覆盖", output_file_path));
+      return Ok(e.to_tool_result(e.to_string()));
+    }
+    if let Err(e) = crate::utils::write_file_content(&output_file_path, &section.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    let mut report = vec![tr!(
+      "✅ 已将章节 {}（第{}行）提取到: {}",
+      section.heading.text,
+      section.heading.line_number,
+      output_file_path
+    )];
+
+    // 原文中保留章节标题，内容替换为指向新文档的链接
+    if config.replace_with_link {
+      let heading_line = content.lines().nth(section.heading.line_number - 1).unwrap_or_default();
+      let link = format!(
+        "{}\n\n[{}]({})",
+        heading_line,
+        section.heading.text,
+        Self::relative_link(&config.full_file_path, &output_file_path)
+      );
+      let new_content = replace_section_lines(&content, &section.heading, &link);
+      if let Err(e) = crate::utils::write_file_content(&config.full_file_path, &new_content) {
+        return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+      }
+      report.push(tr!("🔗 原文中的章节内容已替换为指向新文档的链接").to_string());
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 从原文档指向目标文件的链接地址：位于原文档目录下时使用相对路径，否则使用绝对路径；包含空格时用尖括号包裹
+  fn relative_link(full_file_path: &str, target: &str) -> String {
+    let base_dir = Path::new(full_file_path).parent().unwrap_or(Path::new(""));
+    let link = match Path::new(target).strip_prefix(base_dir) {
+      Ok(relative) => relative.to_string_lossy().replace('\\', "/"),
+      Err(_) => target.to_string(),
+    };
+    if link.contains(' ') {
+      format!("<{}>", link)
+    } else {
+      link
+    }
+  }
+
+  /// 拆分文档实现：每个章节保存为一个文件，并生成链接各个文件的索引；原文档保持不变
+  pub async fn split_document_impl(config: SplitDocumentConfig, sandbox: &Sandbox) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    if let Err(e) = crate::utils::validate_markdown_file(&config.full_file_path) {
+      return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+    }
+
+    // 读取文件内容
+    let content = match crate::utils::read_file_content(&config.full_file_path) {
+      Ok(content) => content,
+      Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+    };
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let outcome = match split_document(&parser, &content, config.level as usize) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("拆分文档失败: {}", e))),
+    };
+
+    // 写入任何文件之前检查是否会覆盖已有文件
+    let output_dir = PathBuf::from(config.get_resolved_output_dir());
+    let file_names = outcome.parts.iter().map(|part| part.file_name.as_str()).chain([config.index_file_name.as_str()]);
+    let output_paths: Vec<PathBuf> = file_names.map(|file_name| output_dir.join(file_name)).collect();
+    if let Some(existing) = output_paths.iter().find(|path| path.exists()).filter(|_| !config.overwrite) {
+      let e = MarkdownError::ValidationError(tr!("文件已存在: {}，设置 overwrite=true 可覆盖", existing.display()));
+      return Ok(e.to_tool_result(e.to_string()));
+    }
+    if let Err(e) = fs::create_dir_all(&output_dir) {
+      let e = MarkdownError::io(output_dir.display().to_string(), e);
+      return Ok(e.to_tool_result(tr!("创建目录失败: {}", e)));
+    }
+
+    // 章节和索引中的本地图片：移动到保存目录，或改写为从保存目录出发的相对路径
+    let md_dir = Path::new(&config.full_file_path).parent().unwrap_or(Path::new("")).to_path_buf();
+    let mut moved = HashSet::new();
+    let mut image_report = Vec::new();
+    let mut relocate = |url: &str| {
+      Self::relocate_image(url, &md_dir, &output_dir, config.move_images, sandbox, &mut moved, &mut image_report)
+    };
+    let mut documents = Vec::with_capacity(output_paths.len());
+    for text in outcome.parts.iter().map(|part| part.content.as_str()).chain([outcome.index.as_str()]) {
+      match rewrite_local_images(&parser, text, &mut relocate) {
+        Ok(document) => documents.push(document),
+        Err(e) => return Ok(e.to_tool_result(tr!("拆分文档失败: {}", e))),
+      }
+    }
+
+    for (path, document) in output_paths.iter().zip(&documents) {
+      if let Err(e) = crate::utils::write_file_content(&path.to_string_lossy(), document) {
+        return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+      }
+    }
+
+    let mut report = vec![tr!("✅ 已将文档拆分为 {} 个文件，保存在: {}", outcome.parts.len(), output_dir.display())];
+    report.extend(outcome.parts.iter().map(|part| format!("📄 {} - {}", part.file_name, part.heading.text)));
+    report.push(tr!("📑 索引文件: {}", config.index_file_name));
+    report.extend(image_report);
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 处理拆分后文档中的一个本地图片地址，返回改写后的地址
+  ///
+  /// 绝对路径保持不变；`move_images` 为 true 时，将原文档目录下的图片移动到保存目录的相同相对位置，地址不变；
+  /// 其他情况（包括移动失败）改写为从保存目录出发的相对路径
+  fn relocate_image(
+    url: &str,
+    md_dir: &Path,
+    output_dir: &Path,
+    move_images: bool,
+    sandbox: &Sandbox,
+    moved: &mut HashSet<PathBuf>,
+    report: &mut Vec<String>,
+  ) -> Option<String> {
+    let source = resolve_local_image_path(md_dir, url).ok().flatten()?;
+    if url.starts_with('/') || url.starts_with("file:") {
+      return None;
+    }
+
+    let relative = source.strip_prefix(md_dir).ok().filter(|_| move_images);
+    if let Some(target) = relative.map(|relative| output_dir.join(relative)) {
+      if moved.contains(&source) || target == source {
+        return None;
+      }
+      match Self::move_image(&source, &target, sandbox) {
+        Ok(()) => {
+          report.push(tr!("🖼️ 已移动图片: {} -> {}", source.display(), target.display()));
+          moved.insert(source);
+          return None;
+        }
+        Err(e) => report.push(tr!("❌ 移动图片失败: {} - {}", source.display(), e)),
+      }
+    }
+
+    relocate_relative_url(url, md_dir, output_dir)
+  }
+
+  /// 移动图片文件，不能直接重命名（如跨文件系统）时复制后删除原文件
+  fn move_image(source: &Path, target: &Path, sandbox: &Sandbox) -> crate::error::Result<()> {
+    let source = &sandbox.check_path(&source.to_string_lossy())?;
+    let target = &sandbox.check_path(&target.to_string_lossy())?;
+    if !source.is_file() {
+      return Err(MarkdownError::FileNotFound { path: source.display().to_string() });
+    }
+    if target.exists() {
+      return Err(MarkdownError::ValidationError(tr!("目标位置已存在同名文件: {}", target.display())));
+    }
+
+    let io_error = |path: &Path| {
+      let path = path.display().to_string();
+      move |e| MarkdownError::io(path, e)
+    };
+    if let Some(parent) = target.parent() {
+      fs::create_dir_all(parent).map_err(io_error(parent))?;
+    }
+    if fs::rename(source, target).is_err() {
+      fs::copy(source, target).map_err(io_error(target))?;
+      fs::remove_file(source).map_err(io_error(source))?;
+    }
+    Ok(())
+  }
+
+  /// 合并文档实现：按顺序合并多个文档，调整标题级别并改写相对路径和锚点
+  pub async fn merge_documents_impl(
+    config: MergeDocumentsConfig,
+    sandbox: &Sandbox,
+  ) -> Result<CallToolResult, McpError> {
+    let full_file_paths = match &config.input_dir {
+      Some(input_dir) => match Self::list_markdown_files(input_dir, &config.output_file_path) {
+        Ok(paths) => paths,
+        Err(e) => return Ok(e.to_tool_result(tr!("合并文档失败: {}", e))),
+      },
+      None => config.full_file_paths.clone(),
+    };
+    let full_file_paths = full_file_paths
+      .iter()
+      .map(|path| sandbox.check_path(path).map(|path| path.to_string_lossy().into_owned()))
+      .collect::<crate::error::Result<Vec<String>>>()?;
+
+    let output_path = Path::new(&config.output_file_path);
+    if full_file_paths.iter().any(|path| Path::new(path) == output_path) {
+      let e = MarkdownError::ValidationError(tr!("合并后的文档不能是要合并的文档之一: {}", config.output_file_path));
+      return Ok(e.to_tool_result(e.to_string()));
+    }
+    if output_path.exists() && !config.overwrite {
+      let e =
+        MarkdownError::ValidationError(tr!("文件已存在: {}，设置 overwrite=true 可覆盖", config.output_file_path));
+      return Ok(e.to_tool_result(e.to_string()));
+    }
+
+    // 验证并读取所有文档
+    let mut sources = Vec::with_capacity(full_file_paths.len());
+    for path in &full_file_paths {
+      if let Err(e) = crate::utils::validate_markdown_file(path) {
+        return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+      }
+      match crate::utils::read_file_content(path) {
+        Ok(content) => sources.push(MergeSource { path: PathBuf::from(path), content }),
+        Err(e) => return Ok(e.to_tool_result(tr!("读取文件失败: {}", e))),
+      }
+    }
+
+    let parser = match MarkdownParser::new() {
+      Ok(parser) => parser,
+      Err(e) => return Ok(CallToolResult::error(vec![Content::text(tr!("创建解析器失败: {}", e))])),
+    };
+
+    let parent = match (&config.title, config.first_file_as_parent) {
+      (Some(title), _) => MergeParent::Title(title.clone()),
+      (None, true) => MergeParent::FirstFile,
+      (None, false) => MergeParent::None,
+    };
+    let outcome = match merge_documents(&parser, &sources, output_path, &parent) {
+      Ok(outcome) => outcome,
+      Err(e) => return Ok(e.to_tool_result(tr!("合并文档失败: {}", e))),
+    };
+
+    let output_dir = output_path.parent().unwrap_or(Path::new(""));
+    if let Err(e) = fs::create_dir_all(output_dir) {
+      let e = MarkdownError::io(output_dir.display().to_string(), e);
+      return Ok(e.to_tool_result(tr!("创建目录失败: {}", e)));
+    }
+    if let Err(e) = crate::utils::write_file_content(&config.output_file_path, &outcome.content) {
+      return Ok(e.to_tool_result(tr!("写入文件失败: {}", e)));
+    }
+
+    let mut report = vec![tr!("✅ 已将 {} 个文档合并到: {}", sources.len(), config.output_file_path)];
+    report.extend(full_file_paths.iter().map(|path| format!("📄 {}", path)));
+    if outcome.internal_links > 0 {
+      report.push(tr!("🔗 {} 个指向被合并文档的链接已改写为文档内的锚点", outcome.internal_links));
+    }
+    if outcome.renamed_anchors > 0 {
+      report.push(tr!("⚓ {} 个标题的锚点因重名或重新编号而改变，指向它们的链接已同步更新", outcome.renamed_anchors));
+    }
+    if outcome.clamped > 0 {
+      report.push(tr!("⚠️ {} 个标题超出 H1-H6 的范围，已限制在范围内", outcome.clamped));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 列出目录下的 Markdown 文档（不包括子目录和 `exclude`），按文件名自然排序
+  fn list_markdown_files(dir: &str, exclude: &str) -> crate::error::Result<Vec<String>> {
+    let entries = fs::read_dir(dir).map_err(|e| MarkdownError::io(dir, e))?;
+    let mut paths: Vec<PathBuf> = entries
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .filter(|path| path.is_file() && path != Path::new(exclude))
+      .filter(|path| {
+        let ext = path.extension().and_then(|ext| ext.to_str()).unwrap_or_default().to_lowercase();
+        ext == "md" || ext == "markdown"
+      })
+      .collect();
+    if paths.is_empty() {
+      return Err(MarkdownError::ValidationError(tr!("目录中没有 Markdown 文档: {}", dir)));
+    }
+
+    let file_name =
+      |path: &PathBuf| path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+    paths.sort_by(|a, b| natural_cmp(&file_name(a), &file_name(b)));
+    Ok(paths.into_iter().map(|path| path.to_string_lossy().into_owned()).collect())
+  }
+
+  /// 显示对文档生效的项目配置，便于排查参数来源
+  pub async fn show_effective_config_impl(
+    config: ShowEffectiveConfigConfig,
+    sandbox: &Sandbox,
+  ) -> Result<CallToolResult, McpError> {
+    let settings = effective_settings(&config.full_file_path, sandbox)?;
+    let values = match config.tool.as_deref() {
+      None => settings.values.clone(),
+      Some(name) => match find_tool(name) {
+        Some(tool) => settings_for_args(&settings, &tool.arg_names()),
+        None => {
+          let names: Vec<&str> = TOOLS.iter().map(|tool| tool.name).collect();
+          return Err(McpError::invalid_params(tr!("未知的工具: {}，可选值为: {}", name, names.join(", ")), None));
+        }
+      },
+    };
+
+    let mut report = vec![match &settings.config_file {
+      Some(path) => tr!("配置文件: {}", path.display()),
+      None => tr!("未找到 {}，使用工具的默认参数", PROJECT_CONFIG_FILE),
+    }];
+    for files in &settings.matched_overrides {
+      report.push(tr!("匹配的覆盖设置: {}", files.join(", ")));
+    }
+    match &config.tool {
+      Some(tool) => report.push(tr!("{} 使用的项目设置（调用时传入的参数优先）:", tool)),
+      None => report.push(tr!("生效的项目设置（调用时传入的参数优先）:").to_string()),
+    }
+    report.push(serde_json::to_string_pretty(&values).unwrap_or_default());
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
+
+  /// 资源清理实现：报告未被引用的文件和失效的图片引用，按配置删除或移动到回收目录
+  pub async fn clean_assets_impl(config: CleanAssetsConfig) -> Result<CallToolResult, McpError> {
+    // 验证文件
+    for file in &config.full_file_paths {
+      if let Err(e) = crate::utils::validate_markdown_file(file) {
+        return Ok(e.to_tool_result(tr!("文件验证失败: {}", e)));
+      }
+    }
+
+    let cleaner = AssetCleaner::new(config.clone());
+    let asset_report = match cleaner.scan() {
+      Ok(asset_report) => asset_report,
+      Err(e) => return Ok(e.to_tool_result(tr!("扫描资源失败: {}", e))),
+    };
+
+    let mut report = vec![tr!(
+      "📊 扫描了 {} 个文档，资源目录 {} 中共有 {} 个图片文件",
+      config.full_file_paths.len(),
+      asset_report.assets_dir.display(),
+      asset_report.asset_count
+    )];
+
+    if asset_report.orphans.is_empty() {
+      report.push(tr!("✅ 没有未被引用的文件").to_string());
+    } else {
+      report.push(tr!("🗑️ 未被引用的文件（{} 个）：", asset_report.orphans.len()));
+      for orphan in &asset_report.orphans {
+        report.push(format!("  - {}", asset_report.display_path(orphan)));
+      }
+    }
+
+    if asset_report.broken_references.is_empty() {
+      report.push(tr!("✅ 没有失效的图片引用").to_string());
+    } else {
+      report.push(tr!("❌ 失效的图片引用（{} 个）：", asset_report.broken_references.len()));
+      for broken in &asset_report.broken_references {
+        report.push(tr!("  - {} 第{}行: {}", broken.file, broken.line_number, broken.url));
+      }
+    }
+
+    if config.action != CleanAssetsAction::Report && !asset_report.orphans.is_empty() {
+      report.push(String::new());
+      report.extend(cleaner.apply(&asset_report));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(report.join("\n"))]))
+  }
 }
 
 #[cfg(test)]
@@ -295,6 +1196,301 @@ mod tests {
   use std::fs;
   use tempfile::{NamedTempFile, TempDir};
 
+  /// 测试调整标题级别：另存为新文件，超出范围时返回带错误码的错误结果
+  #[tokio::test]
+  async fn test_shift_heading_levels() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("doc.md");
+    fs::write(&file_path, "# 1. 介绍\n\n## 1.1. 背景\n").unwrap();
+    let new_file_path = temp_dir.path().join("embedded.md");
+
+    let config = ShiftHeadingLevelsConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      shift: 1,
+      anchor: None,
+      clamp: false,
+      renumber: false,
+      new_full_file_path: Some(new_file_path.to_str().unwrap().to_string()),
+    };
+    let result = MarkdownToolsImpl::shift_heading_levels_impl(config.clone()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(fs::read_to_string(&new_file_path).unwrap(), "## 1. 介绍\n\n### 1.1. 背景\n");
+
+    let result =
+      MarkdownToolsImpl::shift_heading_levels_impl(ShiftHeadingLevelsConfig { shift: 5, ..config }).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.structured_content.unwrap()["code"], "validation_error");
+  }
+
+  /// 测试提取章节：写入以锚点命名的新文档，原文替换为链接；新文档已存在时拒绝覆盖
+  #[tokio::test]
+  async fn test_extract_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("doc.md");
+    fs::write(&file_path, "# 设计\n\n## 存储\n\n正文\n\n### 索引\n\n## 接口\n").unwrap();
+
+    let config = ExtractSectionConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      anchor: Some("存储".to_string()),
+      title_path: None,
+      line_number: None,
+      output_file_path: None,
+      overwrite: false,
+      replace_with_link: true,
+    };
+    let result = MarkdownToolsImpl::extract_section_impl(config.clone(), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(fs::read_to_string(temp_dir.path().join("存储.md")).unwrap(), "# 存储\n\n正文\n\n## 索引\n");
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "# 设计\n\n## 存储\n\n[存储](存储.md)\n\n## 接口\n");
+
+    let result = MarkdownToolsImpl::extract_section_impl(config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+  }
+
+  fn split_config(file_path: &Path, move_images: bool) -> SplitDocumentConfig {
+    SplitDocumentConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      level: 1,
+      output_dir: "{full_dir_of_original_file}/{original_file_name}".to_string(),
+      index_file_name: "index.md".to_string(),
+      move_images,
+      overwrite: false,
+    }
+  }
+
+  /// 测试拆分文档：生成章节文件和索引，改写图片的相对路径；文件已存在时拒绝覆盖
+  #[tokio::test]
+  async fn test_split_document() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("guide.md");
+    fs::write(&file_path, "# Intro\n\n![logo](assets/logo.png)\n\n# Usage\n\n用法\n").unwrap();
+
+    let config = split_config(&file_path, false);
+    let result = MarkdownToolsImpl::split_document_impl(config.clone(), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let output_dir = temp_dir.path().join("guide");
+    assert_eq!(fs::read_to_string(output_dir.join("01-intro.md")).unwrap(), "# Intro\n\n![logo](../assets/logo.png)\n");
+    assert_eq!(fs::read_to_string(output_dir.join("02-usage.md")).unwrap(), "# Usage\n\n用法\n");
+    assert_eq!(
+      fs::read_to_string(output_dir.join("index.md")).unwrap(),
+      "- [Intro](01-intro.md)\n- [Usage](02-usage.md)\n"
+    );
+
+    let result = MarkdownToolsImpl::split_document_impl(config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+  }
+
+  /// 测试拆分文档时将图片移动到保存目录
+  #[tokio::test]
+  async fn test_split_document_move_images() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("guide.md");
+    fs::create_dir(temp_dir.path().join("assets")).unwrap();
+    fs::write(temp_dir.path().join("assets/logo.png"), b"png").unwrap();
+    fs::write(&file_path, "# Intro\n\n![logo](assets/logo.png)\n\n# Usage\n\n![logo](assets/logo.png)\n").unwrap();
+
+    let result =
+      MarkdownToolsImpl::split_document_impl(split_config(&file_path, true), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let output_dir = temp_dir.path().join("guide");
+    assert!(output_dir.join("assets/logo.png").exists());
+    assert!(!temp_dir.path().join("assets/logo.png").exists());
+    assert_eq!(fs::read_to_string(output_dir.join("02-usage.md")).unwrap(), "# Usage\n\n![logo](assets/logo.png)\n");
+  }
+
+  /// 测试合并目录下的文档：按自然顺序合并，指向被合并文档的链接改写为锚点
+  #[tokio::test]
+  async fn test_merge_documents() {
+    let temp_dir = TempDir::new().unwrap();
+    let parts_dir = temp_dir.path().join("parts");
+    fs::create_dir(&parts_dir).unwrap();
+    fs::write(parts_dir.join("10-end.md"), "# End\n\n[开始](2-start.md)\n").unwrap();
+    fs::write(parts_dir.join("2-start.md"), "# Start\n\n![图](img/a.png)\n").unwrap();
+    fs::write(parts_dir.join("notes.txt"), "ignored").unwrap();
+
+    let output_path = temp_dir.path().join("all.md");
+    let config = MergeDocumentsConfig {
+      full_file_paths: Vec::new(),
+      input_dir: Some(parts_dir.to_str().unwrap().to_string()),
+      output_file_path: output_path.to_str().unwrap().to_string(),
+      title: Some("全部".to_string()),
+      first_file_as_parent: false,
+      overwrite: false,
+    };
+    let result = MarkdownToolsImpl::merge_documents_impl(config.clone(), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(
+      fs::read_to_string(&output_path).unwrap(),
+      "# 全部\n\n## Start\n\n![图](parts/img/a.png)\n\n## End\n\n[开始](#start)\n"
+    );
+
+    let result = MarkdownToolsImpl::merge_documents_impl(config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+  }
+
+  /// 测试文档大纲：JSON 格式包含嵌套的子章节和统计信息
+  #[tokio::test]
+  async fn test_outline() {
+    let mut temp_file = NamedTempFile::with_suffix(".md").unwrap();
+    std::io::Write::write_all(&mut temp_file, "# 1. 概述\n\n## 1.1. 背景\n\n```\ncode\n```\n".as_bytes()).unwrap();
+    let config = OutlineConfig {
+      full_file_path: temp_file.path().to_str().unwrap().to_string(),
+      format: OutlineFormat::Json,
+      max_level: 6,
+    };
+
+    let result = MarkdownToolsImpl::outline_impl(config).await.unwrap();
+    let json: serde_json::Value = serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+    assert_eq!(json["headings_per_level"], serde_json::json!([1, 1, 0, 0, 0, 0]));
+    assert_eq!(json["sections"][0]["numbering"], "1.");
+    assert_eq!(json["sections"][0]["children"][0]["title"], "背景");
+    assert_eq!(json["sections"][0]["children"][0]["code_blocks"], 1);
+  }
+
+  /// 测试链接检查：报告失效链接的位置和建议，允许的目录之外的文件不检查
+  #[tokio::test]
+  async fn test_check_links() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("guide.md");
+    fs::write(temp_dir.path().join("usage.md"), "# Usage\n").unwrap();
+    fs::write(&file_path, "# 安装\n\n见 [用法](usage.md#usage)、[安装](#安袋) 和 [外部](../other.md)\n").unwrap();
+    let config = CheckLinksConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      check_external: false,
+      timeout_secs: 10,
+      max_concurrency: 8,
+      ignore_domains: Vec::new(),
+    };
+
+    let sandbox = Sandbox::new(&[temp_dir.path().canonicalize().unwrap()], None);
+    let result = MarkdownToolsImpl::check_links_impl(config, &sandbox).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let text = &result.content[0].as_text().unwrap().text;
+    assert!(text.contains("共检查 2 个链接，跳过 0 个外部链接和 1 个无法检查的链接"), "{}", text);
+    assert!(text.contains("第 3 行第 24 列 [安装](#安袋)：文档中没有锚点 #安袋"), "{}", text);
+    assert!(text.contains("是否应为 #安装？"), "{}", text);
+  }
+
+  /// 测试读取章节：按编号路径定位，省略子章节和截断时附加说明
+  #[tokio::test]
+  async fn test_read_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("spec.md");
+    fs::write(&file_path, "# 1. 概述\n\n# 2. 设计\n\n总体设计\n\n## 2.1. 存储\n\n## 2.2. 接口\n").unwrap();
+    let config = |include_children: bool, max_chars: Option<usize>| ReadSectionConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      anchor: None,
+      title_path: None,
+      number_path: Some("2".to_string()),
+      line_number: None,
+      include_children,
+      max_chars,
+    };
+    let text = |result: CallToolResult| result.content[0].as_text().unwrap().text.clone();
+
+    let result = MarkdownToolsImpl::read_section_impl(config(true, None)).await.unwrap();
+    assert_eq!(text(result), "# 2. 设计\n\n总体设计\n\n## 2.1. 存储\n\n## 2.2. 接口");
+
+    let result = MarkdownToolsImpl::read_section_impl(config(false, None)).await.unwrap();
+    assert_eq!(text(result), "# 2. 设计\n\n总体设计\n\n<!-- 省略了 2 个子章节：#21-存储、#22-接口 -->");
+
+    let result = MarkdownToolsImpl::read_section_impl(config(true, Some(10))).await.unwrap();
+    assert_eq!(text(result), "# 2. 设计\n\n<!-- 已截断：显示了 7 / 37 个字符，第 5 行及之后的内容未显示 -->");
+  }
+
+  /// 测试替换章节：调整新内容的标题级别并重新编号，拒绝不适合的标题级别
+  #[tokio::test]
+  async fn test_replace_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("spec.md");
+    fs::write(&file_path, "# 1. 概述\n\n# 2. 设计\n\n旧内容\n\n# 3. 附录\n").unwrap();
+    let config = |content: &str, adjust_levels: bool| ReplaceSectionConfig {
+      full_file_path: file_path.to_str().unwrap().to_string(),
+      anchor: None,
+      title_path: None,
+      number_path: Some("2".to_string()),
+      line_number: None,
+      content: content.to_string(),
+      replace_heading: false,
+      adjust_levels,
+      renumber: true,
+      new_full_file_path: None,
+    };
+
+    let result = MarkdownToolsImpl::replace_section_impl(config("# 存储\n\n# 接口\n", false)).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+
+    let result = MarkdownToolsImpl::replace_section_impl(config("# 存储\n\n# 接口\n", true)).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(
+      fs::read_to_string(&file_path).unwrap(),
+      "# 1. 概述\n\n# 2. 设计\n\n## 2.1. 存储\n\n## 2.2. 接口\n\n# 3. 附录\n"
+    );
+  }
+
+  /// 测试移动和删除章节并重新编号
+  #[tokio::test]
+  async fn test_move_and_delete_section() {
+    let temp_dir = TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("spec.md");
+    fs::write(&file_path, "# 1. 概述\n\n# 2. 设计\n\n## 2.1. 存储\n\n# 3. 附录\n").unwrap();
+    let full_file_path = file_path.to_str().unwrap().to_string();
+
+    let config = MoveSectionConfig {
+      full_file_path: full_file_path.clone(),
+      anchor: None,
+      title_path: Some("设计/存储".to_string()),
+      line_number: None,
+      target_anchor: Some("1-概述".to_string()),
+      target_title_path: None,
+      target_line_number: None,
+      position: SectionPosition::Into,
+      renumber: true,
+      new_full_file_path: None,
+    };
+    let result = MarkdownToolsImpl::move_section_impl(config).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "# 1. 概述\n\n## 1.1. 存储\n\n# 2. 设计\n\n# 3. 附录\n");
+
+    let config = DeleteSectionConfig {
+      full_file_path,
+      anchor: None,
+      title_path: Some("设计".to_string()),
+      line_number: None,
+      renumber: true,
+      new_full_file_path: None,
+    };
+    let result = MarkdownToolsImpl::delete_section_impl(config).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "# 1. 概述\n\n## 1.1. 存储\n\n# 2. 附录\n");
+  }
+
+  /// 测试链接地址
+  #[test]
+  fn test_relative_link() {
+    assert_eq!(MarkdownToolsImpl::relative_link("/docs/a.md", "/docs/parts/b.md"), "parts/b.md");
+    assert_eq!(MarkdownToolsImpl::relative_link("/docs/a.md", "/other/b.md"), "/other/b.md");
+    assert_eq!(MarkdownToolsImpl::relative_link("/docs/a.md", "/docs/my file.md"), "<my file.md>");
+  }
+
+  /// 测试新文件名的默认值和占位符
+  #[test]
+  fn test_generate_new_filename() {
+    assert_eq!(MarkdownToolsImpl::generate_new_filename("/docs/guide.md", None, "numed"), "/docs/guide_numed.md");
+    assert_eq!(
+      MarkdownToolsImpl::generate_new_filename(
+        "/docs/guide.md",
+        Some("{full_dir_of_original_file}/{original_file_name}_numed.md"),
+        "numed"
+      ),
+      "/docs/guide_numed.md"
+    );
+    assert_eq!(MarkdownToolsImpl::generate_new_filename("/docs/guide.md", Some("/out/a.md"), "numed"), "/out/a.md");
+    assert_eq!(MarkdownToolsImpl::generate_new_filename("/docs/guide.md", Some(""), "numed"), "/docs/guide_numed.md");
+  }
+
   /// 测试生成章节编号 - 阿拉伯数字
   #[tokio::test]
   async fn test_generate_chapter_number_arabic() {
@@ -522,7 +1718,7 @@ mod tests {
     let temp_file = NamedTempFile::with_suffix(".md").unwrap();
     fs::write(temp_file.path(), content).unwrap();
 
-    let config = CheckHeadingConfig { full_file_path: temp_file.path().to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(temp_file.path().to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
 
@@ -546,7 +1742,7 @@ mod tests {
     let temp_file = NamedTempFile::with_suffix(".md").unwrap();
     fs::write(temp_file.path(), content).unwrap();
 
-    let config = CheckHeadingConfig { full_file_path: temp_file.path().to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(temp_file.path().to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
 
@@ -555,6 +1751,69 @@ mod tests {
     assert_eq!(call_result.is_error, Some(true));
   }
 
+  /// 测试标题检查规则：允许跳级和最深级别
+  #[tokio::test]
+  async fn test_check_heading_lint_rules() {
+    let temp_file = NamedTempFile::with_suffix(".md").unwrap();
+    fs::write(temp_file.path(), "# 标题\n\n### 跳级\n\n#### 四级\n").unwrap();
+    let path = temp_file.path().to_str().unwrap();
+
+    let result = MarkdownToolsImpl::check_heading_impl(CheckHeadingConfig::new(path)).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+
+    let mut config = CheckHeadingConfig::new(path);
+    config.allow_skipped_levels = true;
+    let result = MarkdownToolsImpl::check_heading_impl(config.clone()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    config.max_heading_level = 3;
+    let result = MarkdownToolsImpl::check_heading_impl(config).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    assert!(format!("{:?}", result.content).contains("第5行：标题级别 H4 超过允许的最深级别 H3"));
+  }
+
+  /// 测试显示生效的项目配置
+  #[tokio::test]
+  async fn test_show_effective_config() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join(".mdtools.toml"), "[numbering]\nuse_chinese_number = true\n").unwrap();
+    let doc = temp_dir.path().join("doc.md");
+    fs::write(&doc, "# 标题\n").unwrap();
+
+    let config = ShowEffectiveConfigConfig {
+      full_file_path: doc.to_str().unwrap().to_string(),
+      tool: Some("generate_chapter_number".to_string()),
+    };
+    let result = MarkdownToolsImpl::show_effective_config_impl(config.clone(), &Sandbox::default()).await.unwrap();
+    let text = format!("{:?}", result.content);
+    assert!(text.contains(".mdtools.toml"), "{}", text);
+    assert!(text.contains("use_chinese_number"), "{}", text);
+
+    let result = MarkdownToolsImpl::show_effective_config_impl(
+      ShowEffectiveConfigConfig { tool: Some("remove_all_chapter_numbers".to_string()), ..config.clone() },
+      &Sandbox::default(),
+    )
+    .await
+    .unwrap();
+    assert!(!format!("{:?}", result.content).contains("use_chinese_number"));
+
+    // 所有注册的工具都可以查询，未知工具的错误信息列出全部工具
+    let result = MarkdownToolsImpl::show_effective_config_impl(
+      ShowEffectiveConfigConfig { tool: Some("split_document".to_string()), ..config.clone() },
+      &Sandbox::default(),
+    )
+    .await;
+    assert!(result.is_ok());
+
+    let err = MarkdownToolsImpl::show_effective_config_impl(
+      ShowEffectiveConfigConfig { tool: Some("unknown".to_string()), ..config },
+      &Sandbox::default(),
+    )
+    .await
+    .unwrap_err();
+    assert!(TOOLS.iter().all(|tool| err.message.contains(tool.name)), "{}", err.message);
+  }
+
   /// 测试文件验证错误
   #[tokio::test]
   async fn test_file_validation_error() {
@@ -1,35 +1,140 @@
 use crate::error::{MarkdownError, Result};
+use crate::tr;
 use rmcp::{model::*, ErrorData as McpError};
+use std::cmp::Ordering;
 use std::fs::{self, OpenOptions};
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 /// 验证文件路径并检查是否为有效的 Markdown 文件
 pub fn validate_markdown_file(full_file_path: &str) -> Result<()> {
   let path = Path::new(full_file_path);
 
   if !path.exists() {
-    return Err(MarkdownError::ValidationError(format!("文件不存在: {}", full_file_path)));
+    return Err(MarkdownError::FileNotFound { path: full_file_path.to_string() });
   }
 
-  if let Some(extension) = path.extension() {
-    if extension != "md" && extension != "markdown" {
-      return Err(MarkdownError::ValidationError("文件必须是Markdown格式 (.md 或 .markdown)".to_string()));
-    }
-  } else {
-    return Err(MarkdownError::ValidationError("文件必须有扩展名".to_string()));
+  if !path.extension().is_some_and(|extension| extension == "md" || extension == "markdown") {
+    return Err(MarkdownError::NotMarkdown { path: full_file_path.to_string() });
   }
 
   Ok(())
 }
 
+/// 路径模板，所有工具的路径参数（new_full_file_path、image_dir 等）共用
+///
+/// 占位符的取值来自原文档路径：
+/// - `{full_dir_of_original_file}`：原文档所在目录
+/// - `{original_file_name}`：原文档不含扩展名的文件名
+/// - `{ext}`：原文档的扩展名（不含 .）
+/// - `{date}`：当前日期，格式为 YYYY-MM-DD
+/// - `{timestamp}`：当前 Unix 时间戳（秒）
+///
+/// 同一个 PathTemplate 解析的多个模板使用相同的时间
+#[derive(Debug, Clone)]
+pub struct PathTemplate {
+  full_dir: String,
+  file_name: String,
+  ext: String,
+  now: chrono::DateTime<chrono::Local>,
+}
+
+impl PathTemplate {
+  /// 以原文档路径创建路径模板
+  pub fn new(full_file_path: &str) -> Self {
+    let path = Path::new(full_file_path);
+    let os_str = |s: Option<&std::ffi::OsStr>| s.and_then(|s| s.to_str()).unwrap_or_default().to_string();
+    Self {
+      full_dir: path.parent().map(|dir| dir.to_str().unwrap_or(".").to_string()).unwrap_or_else(|| ".".to_string()),
+      file_name: os_str(path.file_stem()),
+      ext: os_str(path.extension()),
+      now: chrono::Local::now(),
+    }
+  }
+
+  /// 替换模板中的占位符，未知的占位符保持原样
+  pub fn resolve(&self, template: &str) -> String {
+    template
+      .replace("{full_dir_of_original_file}", &self.full_dir)
+      .replace("{original_file_name}", &self.file_name)
+      .replace("{ext}", &self.ext)
+      .replace("{date}", &self.now.format("%Y-%m-%d").to_string())
+      .replace("{timestamp}", &self.now.timestamp().to_string())
+  }
+}
+
+/// 从目录 `base_dir` 指向 `target` 的相对路径，按路径的各个部分逐一比较，不访问文件系统
+pub fn relative_path(base_dir: &Path, target: &Path) -> PathBuf {
+  let (base_dir, target) = (normalize_path(base_dir), normalize_path(target));
+  let base: Vec<Component> = base_dir.components().collect();
+  let target: Vec<Component> = target.components().collect();
+  let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+  let mut result = PathBuf::new();
+  for _ in common..base.len() {
+    result.push("..");
+  }
+  for component in &target[common..] {
+    result.push(component);
+  }
+  result
+}
+
+/// 按字面处理路径中的 `.` 和 `..`，不访问文件系统、不解析符号链接
+pub fn normalize_path(path: &Path) -> PathBuf {
+  let mut result = PathBuf::new();
+  for component in path.components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir if matches!(result.components().next_back(), Some(Component::Normal(_))) => {
+        result.pop();
+      }
+      component => result.push(component),
+    }
+  }
+  result
+}
+
+/// 将相对路径转换为 Markdown 中的链接地址：使用 / 分隔，空格编码为 %20
+pub fn path_to_url(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/").replace(' ', "%20")
+}
+
+/// 自然排序：文件名中的数字部分按数值比较，如 2-a.md 排在 10-b.md 之前
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+  fn chunks(s: &str) -> Vec<(bool, String)> {
+    let mut chunks: Vec<(bool, String)> = Vec::new();
+    for c in s.chars() {
+      let is_digit = c.is_ascii_digit();
+      match chunks.last_mut() {
+        Some((last_is_digit, chunk)) if *last_is_digit == is_digit => chunk.push(c),
+        _ => chunks.push((is_digit, c.to_string())),
+      }
+    }
+    chunks
+  }
+
+  for ((a_is_digit, a_chunk), (b_is_digit, b_chunk)) in chunks(a).into_iter().zip(chunks(b)) {
+    let ordering = if a_is_digit && b_is_digit {
+      let (a_num, b_num) = (a_chunk.trim_start_matches('0'), b_chunk.trim_start_matches('0'));
+      a_num.len().cmp(&b_num.len()).then_with(|| a_num.cmp(b_num))
+    } else {
+      a_chunk.to_lowercase().cmp(&b_chunk.to_lowercase())
+    };
+    if ordering != Ordering::Equal {
+      return ordering;
+    }
+  }
+  a.chars().count().cmp(&b.chars().count()).then_with(|| a.cmp(b))
+}
+
 /// 读取文件内容
 pub fn read_file_content(full_file_path: &str) -> Result<String> {
-  fs::read_to_string(full_file_path).map_err(|e| MarkdownError::FileError(format!("读取文件失败: {}", e)))
+  fs::read_to_string(full_file_path).map_err(|e| MarkdownError::io(full_file_path, e))
 }
 
 /// 写入文件内容
 pub fn write_file_content(full_file_path: &str, content: &str) -> Result<()> {
-  fs::write(full_file_path, content).map_err(|e| MarkdownError::FileError(format!("写入文件失败: {}", e)))
+  fs::write(full_file_path, content).map_err(|e| MarkdownError::io(full_file_path, e))
 }
 
 /// 创建成功的工具调用结果
@@ -64,7 +169,7 @@ where
 
     write_file_content(output_path, &new_content)?;
 
-    let final_message = format!("{}, 新文件保存为: {}", success_message, output_path);
+    let final_message = tr!("{}, 新文件保存为: {}", success_message, output_path);
     Ok(CallToolResult::success(vec![Content::text(final_message)]))
   })();
 
@@ -96,10 +201,8 @@ mod tests {
 
     assert!(result.is_err());
     match result.unwrap_err() {
-      MarkdownError::ValidationError(msg) => {
-        assert!(msg.contains("文件不存在"));
-      }
-      _ => panic!("期望 ValidationError"),
+      MarkdownError::FileNotFound { path } => assert_eq!(path, "/nonexistent/file.md"),
+      other => panic!("期望 FileNotFound，实际为 {:?}", other),
     }
   }
 
@@ -113,12 +216,9 @@ mod tests {
     let result = validate_markdown_file(path_str);
 
     assert!(result.is_err());
-    match result.unwrap_err() {
-      MarkdownError::ValidationError(msg) => {
-        assert!(msg.contains("文件必须是Markdown格式"));
-      }
-      _ => panic!("期望 ValidationError"),
-    }
+    let error = result.unwrap_err();
+    assert!(error.to_string().contains("文件必须是Markdown格式"));
+    assert!(matches!(error, MarkdownError::NotMarkdown { path } if path == path_str));
   }
 
   /// 测试验证 .markdown 扩展名
@@ -144,12 +244,7 @@ mod tests {
     let result = validate_markdown_file(path_str);
 
     assert!(result.is_err());
-    match result.unwrap_err() {
-      MarkdownError::ValidationError(msg) => {
-        assert!(msg.contains("文件必须有扩展名"));
-      }
-      _ => panic!("期望 ValidationError"),
-    }
+    assert!(matches!(result.unwrap_err(), MarkdownError::NotMarkdown { .. }));
   }
 
   /// 测试读取文件内容
@@ -171,12 +266,7 @@ mod tests {
     let result = read_file_content("/nonexistent/file.md");
 
     assert!(result.is_err());
-    match result.unwrap_err() {
-      MarkdownError::FileError(msg) => {
-        assert!(msg.contains("读取文件失败"));
-      }
-      _ => panic!("期望 FileError"),
-    }
+    assert_eq!(result.unwrap_err().code(), "file_not_found");
   }
 
   /// 测试读取空文件
@@ -212,13 +302,9 @@ mod tests {
   fn test_write_file_content_invalid_path() {
     let result = write_file_content("/nonexistent/directory/file.md", "content");
 
-    assert!(result.is_err());
-    match result.unwrap_err() {
-      MarkdownError::FileError(msg) => {
-        assert!(msg.contains("写入文件失败"));
-      }
-      _ => panic!("期望 FileError"),
-    }
+    let err = result.unwrap_err();
+    assert_eq!(err.code(), "file_not_found");
+    assert!(err.to_string().contains("/nonexistent/directory/file.md"));
   }
 
   /// 测试创建成功结果
@@ -393,4 +479,55 @@ mod tests {
 
     assert_eq!(result.is_error, Some(false));
   }
+
+  /// 测试相对路径计算
+  #[test]
+  fn test_relative_path() {
+    assert_eq!(relative_path(Path::new("/docs"), Path::new("/docs/parts/a.md")), PathBuf::from("parts/a.md"));
+    assert_eq!(
+      relative_path(Path::new("/docs/guide"), Path::new("/docs/assets/a.png")),
+      PathBuf::from("../assets/a.png")
+    );
+    assert_eq!(relative_path(Path::new("/docs/a/b"), Path::new("/other/c.md")), PathBuf::from("../../../other/c.md"));
+    assert_eq!(relative_path(Path::new("/docs/guide"), Path::new("/docs/guide/../a.png")), PathBuf::from("../a.png"));
+  }
+
+  /// 测试路径规范化和链接地址
+  #[test]
+  fn test_normalize_path() {
+    assert_eq!(normalize_path(Path::new("/docs/./a/../b.md")), PathBuf::from("/docs/b.md"));
+    assert_eq!(normalize_path(Path::new("../a/../../b")), PathBuf::from("../../b"));
+    assert_eq!(path_to_url(Path::new("../my assets/a.png")), "../my%20assets/a.png");
+  }
+
+  /// 测试自然排序
+  #[test]
+  fn test_natural_cmp() {
+    let mut names = vec!["10-end.md", "2-usage.md", "1-intro.md", "02-setup.md", "appendix.md", "Api.md"];
+    names.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(names, ["1-intro.md", "02-setup.md", "2-usage.md", "10-end.md", "Api.md", "appendix.md"]);
+  }
+
+  /// 测试路径模板占位符
+  #[test]
+  fn test_path_template() {
+    let template = PathTemplate::new("/docs/guide.md");
+    assert_eq!(
+      template.resolve("{full_dir_of_original_file}/{original_file_name}_numed.{ext}"),
+      "/docs/guide_numed.md"
+    );
+    assert_eq!(template.resolve("/out/{unknown}/a.md"), "/out/{unknown}/a.md");
+
+    let date = template.resolve("{date}");
+    assert_eq!(date.len(), 10);
+    assert_eq!(date, chrono::Local::now().format("%Y-%m-%d").to_string());
+
+    let timestamp: i64 = template.resolve("{timestamp}").parse().unwrap();
+    assert!((chrono::Local::now().timestamp() - timestamp).abs() < 60);
+
+    // 同一个模板中的时间保持一致
+    assert_eq!(template.resolve("{timestamp}"), template.resolve("{timestamp}"));
+
+    assert_eq!(PathTemplate::new("guide.md").resolve("{full_dir_of_original_file}/assets/"), "/assets/");
+  }
 }
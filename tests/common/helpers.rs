@@ -95,6 +95,9 @@ impl ImageLocalizationConfigBuilder {
         image_file_name_pattern: "{index}-{hash}".to_string(),
         image_dir: "./assets/".to_string(),
         new_full_file_path: None,
+        max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+        localize_data_uris: false,
+        copy_local_images: false,
       },
     }
   }
@@ -114,6 +117,21 @@ impl ImageLocalizationConfigBuilder {
     self
   }
 
+  pub fn max_image_size(mut self, max_image_size: u64) -> Self {
+    self.config.max_image_size = max_image_size;
+    self
+  }
+
+  pub fn localize_data_uris(mut self, localize: bool) -> Self {
+    self.config.localize_data_uris = localize;
+    self
+  }
+
+  pub fn copy_local_images(mut self, copy: bool) -> Self {
+    self.config.copy_local_images = copy;
+    self
+  }
+
   pub fn build(self) -> LocalizeImagesConfig {
     self.config
   }
@@ -21,6 +21,11 @@ impl MockHttpServer {
     self.server.uri()
   }
 
+  /// 服务器收到的请求数量
+  pub async fn request_count(&self) -> usize {
+    self.server.received_requests().await.map_or(0, |requests| requests.len())
+  }
+
   /// 模拟图片下载响应
   pub async fn mock_image_response(&self, path_str: &str, image_data: &[u8], content_type: &str) {
     use wiremock::matchers::path;
@@ -32,6 +37,22 @@ impl MockHttpServer {
       .await;
   }
 
+  /// 模拟延迟返回的图片响应，用于测试取消
+  pub async fn mock_delayed_image_response(&self, path_str: &str, delay: std::time::Duration) {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, ResponseTemplate};
+
+    Mock::given(path(path_str))
+      .respond_with(
+        ResponseTemplate::new(200)
+          .set_body_bytes(self.create_test_png_data())
+          .insert_header("content-type", "image/png")
+          .set_delay(delay),
+      )
+      .mount(&self.server)
+      .await;
+  }
+
   /// 模拟 404 响应
   pub async fn mock_404_response(&self, path_str: &str) {
     use wiremock::matchers::path;
@@ -40,6 +61,51 @@ impl MockHttpServer {
     Mock::given(path(path_str)).respond_with(ResponseTemplate::new(404)).mount(&self.server).await;
   }
 
+  /// 模拟返回指定状态码的空响应
+  pub async fn mock_status_response(&self, path_str: &str, status: u16) {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, ResponseTemplate};
+
+    Mock::given(path(path_str)).respond_with(ResponseTemplate::new(status)).mount(&self.server).await;
+  }
+
+  /// 模拟重定向响应，`location` 可以是相对地址
+  pub async fn mock_redirect_response(&self, path_str: &str, status: u16, location: &str) {
+    use wiremock::matchers::path;
+    use wiremock::{Mock, ResponseTemplate};
+
+    Mock::given(path(path_str))
+      .respond_with(ResponseTemplate::new(status).insert_header("location", location))
+      .mount(&self.server)
+      .await;
+  }
+
+  /// 模拟不支持 HEAD 请求的页面：HEAD 返回 405，GET 返回 200
+  pub async fn mock_head_not_allowed(&self, path_str: &str) {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    Mock::given(method("HEAD")).and(path(path_str)).respond_with(ResponseTemplate::new(405)).mount(&self.server).await;
+    Mock::given(method("GET"))
+      .and(path(path_str))
+      .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+      .mount(&self.server)
+      .await;
+  }
+
+  /// 模拟返回 HTML 错误页面（状态码为 200）
+  pub async fn mock_html_response(&self, path_str: &str) {
+    let html = "<!DOCTYPE html><html><head><title>Not Found</title></head><body>404</body></html>";
+    self.mock_image_response(path_str, html.as_bytes(), "image/png").await;
+  }
+
+  /// 模拟指定大小的图片响应（PNG 文件头 + 填充数据）
+  pub async fn mock_sized_image_response(&self, path_str: &str, size: usize) {
+    let mut data = self.create_test_png_data();
+    data.resize(size.max(data.len()), 0);
+    self.mock_image_response(path_str, &data, "image/png").await;
+  }
+
   pub async fn mock_basic_images(&self) {
     let jpg_data = self.create_test_jpg_data();
     let png_data = self.create_test_png_data();
@@ -32,7 +32,7 @@ mod tests {
     let localization_config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config).await;
+    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config, Default::default()).await;
     assert!(localization_result.is_ok());
 
     let call_result = localization_result.unwrap();
@@ -235,7 +235,7 @@ mod tests {
         .image_dir(assets_dir.to_str().unwrap())
         .build();
 
-      let result = MarkdownToolsImpl::localize_images_impl(config).await;
+      let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
       assert!(result.is_ok(), "文件 {:?} 图片本地化失败", file_path);
     }
 
@@ -295,7 +295,7 @@ mod tests {
     // 场景 4：图片本地化错误处理
     let img_config = ImageLocalizationConfigBuilder::new("/nonexistent/image_file.md").build();
 
-    let img_result = MarkdownToolsImpl::localize_images_impl(img_config).await;
+    let img_result = MarkdownToolsImpl::localize_images_impl(img_config, Default::default()).await;
     // 图片本地化可能返回 Ok 但标记错误，或直接返回 Err
     match img_result {
       Ok(call_result) => assert_eq!(call_result.is_error, Some(true)),
@@ -307,7 +307,7 @@ mod tests {
       .image_dir(file_manager.assets_dir().to_str().unwrap())
       .build();
 
-    let valid_img_result = MarkdownToolsImpl::localize_images_impl(valid_img_config).await;
+    let valid_img_result = MarkdownToolsImpl::localize_images_impl(valid_img_config, Default::default()).await;
     assert!(valid_img_result.is_ok(), "错误恢复后应该能正常处理");
   }
 
@@ -346,7 +346,7 @@ mod tests {
       .image_dir(file_manager.assets_dir().to_str().unwrap())
       .build();
 
-    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config).await;
+    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config, Default::default()).await;
     assert!(localization_result.is_ok());
 
     let localization_duration = start_time.elapsed();
@@ -436,7 +436,7 @@ mod mock_e2e_tests {
     let localization_config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config).await;
+    let localization_result = MarkdownToolsImpl::localize_images_impl(localization_config, Default::default()).await;
 
     match localization_result {
       Ok(call_result) => {
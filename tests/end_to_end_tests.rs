@@ -178,7 +178,7 @@ mod tests {
     let temp_file = NamedTempFile::with_suffix(".md").unwrap();
     fs::write(temp_file.path(), valid_content).unwrap();
 
-    let config = CheckHeadingConfig { full_file_path: temp_file.path().to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(temp_file.path().to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -198,7 +198,7 @@ mod tests {
 
     fs::write(temp_file.path(), invalid_content).unwrap();
 
-    let config = CheckHeadingConfig { full_file_path: temp_file.path().to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(temp_file.path().to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -334,7 +334,7 @@ mod tests {
     fs::write(temp_file.path(), content).unwrap();
 
     // 先验证标题结构
-    let check_config = CheckHeadingConfig { full_file_path: temp_file.path().to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(temp_file.path().to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
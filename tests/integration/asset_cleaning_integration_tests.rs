@@ -0,0 +1,68 @@
+//! 资源清理集成测试
+//!
+//! 测试资源清理与图片本地化、解析器的协同工作
+
+use crate::common::{assertions, ImageLocalizationConfigBuilder, MockHttpServer, TestFileManager};
+use mcp_markdown_tools::config::{CleanAssetsAction, CleanAssetsConfig};
+use mcp_markdown_tools::tools::MarkdownToolsImpl;
+
+#[cfg(feature = "mock")]
+mod mock_integration_tests {
+  use super::*;
+  use rmcp::model::RawContent;
+
+  /// 提取工具调用结果中的文本
+  fn result_text(result: &rmcp::model::CallToolResult) -> String {
+    result
+      .content
+      .iter()
+      .filter_map(|content| match &content.raw {
+        RawContent::Text(text) => Some(text.text.clone()),
+        _ => None,
+      })
+      .collect::<Vec<_>>()
+      .join("\n")
+  }
+
+  /// 集成测试：编辑文档删除图片引用后，清理本地化时下载的多余图片
+  #[tokio::test]
+  async fn integration_clean_assets_after_edit() {
+    let server = MockHttpServer::new().await;
+    server.mock_basic_images().await;
+
+    let content = format!("# 文档\n\n![保留]({0}/jpg)\n\n![删除]({0}/png)\n", server.url());
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("doc.md", &content);
+    let other_file = file_manager.create_md_file("other.md", "# 其他\n\n![丢失](assets/gone.png)\n");
+    let assets_dir = file_manager.assets_dir();
+
+    let config =
+      ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
+    MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    assertions::assert_file_count(&assets_dir, 2);
+
+    // 删除第二张图片的引用
+    let localized = std::fs::read_to_string(&md_file).unwrap();
+    let edited: Vec<&str> = localized.lines().filter(|line| !line.starts_with("![删除]")).collect();
+    std::fs::write(&md_file, edited.join("\n")).unwrap();
+
+    let config = CleanAssetsConfig {
+      full_file_paths: vec![md_file.to_str().unwrap().to_string(), other_file.to_str().unwrap().to_string()],
+      assets_dir: assets_dir.to_str().unwrap().to_string(),
+      action: CleanAssetsAction::Report,
+      trash_dir: None,
+    };
+
+    let result = MarkdownToolsImpl::clean_assets_impl(config.clone()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let text = result_text(&result);
+    assert!(text.contains("未被引用的文件（1 个）"), "{}", text);
+    assert!(text.contains("第3行: assets/gone.png"), "{}", text);
+    assertions::assert_file_count(&assets_dir, 2);
+
+    let config = CleanAssetsConfig { action: CleanAssetsAction::Delete, ..config };
+    let result = MarkdownToolsImpl::clean_assets_impl(config).await.unwrap();
+    assert!(result_text(&result).contains("✅ 已删除"));
+    assertions::assert_file_count(&assets_dir, 1);
+  }
+}
@@ -3,8 +3,8 @@
 //! 测试图片本地化功能与其他模块的集成，包括解析器、网络请求等的协同工作
 
 use crate::common::{assertions, test_data, ImageLocalizationConfigBuilder, MockHttpServer, TestFileManager};
-use mcp_markdown_tools::config::LocalizeImagesConfig;
-use mcp_markdown_tools::tools::MarkdownToolsImpl;
+use mcp_markdown_tools::config::{LocalizeImagesConfig, DEFAULT_MAX_IMAGE_SIZE};
+use mcp_markdown_tools::tools::{LocalizeOptions, MarkdownToolsImpl};
 
 #[cfg(test)]
 mod tests {
@@ -20,7 +20,7 @@ mod tests {
     let config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     assert!(result.is_ok());
     let call_result = result.unwrap();
@@ -39,7 +39,7 @@ mod tests {
   async fn integration_error_handling_nonexistent_file() {
     let config = ImageLocalizationConfigBuilder::new("/nonexistent/file.md").build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     assert!(result.is_ok());
     let call_result = result.unwrap();
@@ -58,7 +58,7 @@ mod tests {
       .image_dir(file_manager.assets_dir().to_str().unwrap())
       .build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     assert!(result.is_ok());
     let call_result = result.unwrap();
@@ -82,6 +82,9 @@ mod tests {
       image_file_name_pattern: "{multilevel_num}-{index}".to_string(),
       image_dir: "{full_dir_of_original_file}/assets/".to_string(),
       new_full_file_path: None,
+      max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+      localize_data_uris: false,
+      copy_local_images: false,
     };
 
     // 验证占位符解析
@@ -90,7 +93,7 @@ mod tests {
     assert_eq!(resolved_dir, expected_dir);
 
     // 执行本地化
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     // 验证目录结构
     let assets_dir = docs_dir.join("assets");
@@ -112,7 +115,7 @@ mod tests {
       .image_dir(images_dir.to_str().unwrap())
       .build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     // 测试配置和基本流程
     match result {
@@ -137,7 +140,7 @@ mod tests {
       .new_file_path(Some(new_file_path.to_str().unwrap().to_string()))
       .build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     assert!(result.is_ok());
     let call_result = result.unwrap();
@@ -178,7 +181,7 @@ mod tests {
     let config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     let duration = start_time.elapsed();
 
@@ -216,7 +219,7 @@ mod tests {
         .image_dir(file_manager.assets_dir().to_str().unwrap())
         .build();
 
-      let result = MarkdownToolsImpl::localize_images_impl(config).await;
+      let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
       // 应该优雅地处理错误，不应该 panic
       match result {
@@ -248,7 +251,7 @@ mod tests {
         ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
       // 创建异步任务
-      let task = tokio::spawn(async move { MarkdownToolsImpl::localize_images_impl(config).await });
+      let task = tokio::spawn(async move { MarkdownToolsImpl::localize_images_impl(config, Default::default()).await });
 
       tasks.push((task, assets_dir));
     }
@@ -263,12 +266,77 @@ mod tests {
       assertions::assert_dir_exists(&assets_dir);
     }
   }
+
+  /// 集成测试：data URI 与文档目录之外的本地图片（Markdown 与 HTML 写法）
+  #[tokio::test]
+  async fn integration_data_uri_and_out_of_tree_images() {
+    let file_manager = TestFileManager::new();
+    let shared_dir = file_manager.temp_dir.path().join("shared");
+    std::fs::create_dir_all(&shared_dir).unwrap();
+    std::fs::create_dir_all(file_manager.temp_dir.path().join("docs")).unwrap();
+    std::fs::write(shared_dir.join("logo.png"), [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x01]).unwrap();
+
+    let content = r#"# 内嵌图片
+
+![点](data:image/gif;base64,R0lGODlhAQABAAAAACw=)
+
+行内 HTML：<img src="../shared/logo.png" alt="共享" width="20"> 结束
+
+![不存在](../shared/missing.png)
+"#;
+    let md_file = file_manager.create_md_file("docs/embedded.md", content);
+    let assets_dir = file_manager.temp_dir.path().join("docs").join("assets");
+
+    let config = ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap())
+      .image_dir(assets_dir.to_str().unwrap())
+      .localize_data_uris(true)
+      .copy_local_images(true)
+      .build();
+
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    assertions::assert_file_count(&assets_dir, 2);
+    assertions::assert_file_not_contains(&md_file, "data:image/gif");
+    assertions::assert_file_contains(&md_file, "![点](assets/");
+    assertions::assert_file_contains(&md_file, "src=\"assets/");
+    assertions::assert_file_contains(&md_file, "width=\"20\"");
+    // 无法复制的图片保持原样
+    assertions::assert_file_contains(&md_file, "![不存在](../shared/missing.png)");
+  }
+
+  /// 集成测试：本地化只改写图片地址，带编号和数字的标题、空白及其他各行逐字节保持原样
+  #[tokio::test]
+  async fn integration_localize_keeps_other_lines() {
+    let content = "# 1. Intro\n\n##   2024 Roadmap\n\n### 1.1.  3 Steps  \n\n![点](data:image/gif;base64,R0lGODlhAQABAAAAACw=)\n\n行内 ![点](data:image/gif;base64,R0lGODlhAQABAAAAACw=) 结束\n\n```\n# 2. 代码\n```\n";
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("numbered.md", content);
+    let config = ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap())
+      .image_dir(file_manager.assets_dir().to_str().unwrap())
+      .localize_data_uris(true)
+      .build();
+
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    let new_content = std::fs::read_to_string(&md_file).unwrap();
+    assert_eq!(new_content.lines().count(), content.lines().count());
+    for (old_line, new_line) in content.lines().zip(new_content.lines()) {
+      match old_line.contains("data:image") {
+        true => assert!(new_line.contains("](assets/"), "{}", new_line),
+        false => assert_eq!(old_line, new_line),
+      }
+    }
+  }
 }
 
 #[cfg(feature = "mock")]
 mod mock_integration_tests {
   use super::*;
+  use mcp_markdown_tools::image_localizer::ProgressCallback;
+  use mcp_markdown_tools::sandbox::Sandbox;
   use rstest::rstest;
+  use tokio_util::sync::CancellationToken;
 
   #[rstest::fixture]
   async fn mock_server() -> MockHttpServer {
@@ -292,7 +360,7 @@ mod mock_integration_tests {
     let config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     // 验证基本流程
     match result {
@@ -358,7 +426,7 @@ mod mock_integration_tests {
     let config =
       ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
 
-    let result = MarkdownToolsImpl::localize_images_impl(config).await;
+    let result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await;
 
     // 测试基本流程
     match result {
@@ -369,4 +437,168 @@ mod mock_integration_tests {
       Err(e) => panic!("解析器集成测试不应该失败: {:?}", e),
     }
   }
+
+  /// 集成测试：非图片内容（HTML 错误页）被拒绝，文档引用保持不变
+  #[tokio::test]
+  async fn integration_reject_non_image_payload() {
+    let server = MockHttpServer::new().await;
+    server.mock_html_response("/fake.png").await;
+    let host = server.url();
+
+    let content = format!("# 标题\n\n![假图片]({}/fake.png)\n", host);
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("html_payload.md", &content);
+    let assets_dir = file_manager.assets_dir();
+
+    let config =
+      ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
+
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    let report = format!("{:?}", call_result.content);
+    assert!(report.contains("HTML 页面"), "报告中应说明原因: {}", report);
+
+    // 不应保存任何文件，原始引用保持不变
+    assertions::assert_file_count(&assets_dir, 0);
+    assertions::assert_file_contains(&md_file, &format!("{}/fake.png", host));
+  }
+
+  /// 集成测试：扩展名以文件头识别出的格式为准，不采信 URL 和 Content-Type
+  #[tokio::test]
+  async fn integration_extension_from_magic_bytes() {
+    let server = MockHttpServer::new().await;
+    server.mock_image_response("/photo.png", &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10], "image/png").await;
+
+    let content = format!("# 标题\n\n![照片]({}/photo.png)\n", server.url());
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("magic_bytes.md", &content);
+    let assets_dir = file_manager.assets_dir();
+
+    let config =
+      ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    assertions::assert_file_count(&assets_dir, 1);
+    let saved = std::fs::read_dir(&assets_dir).unwrap().next().unwrap().unwrap().file_name();
+    assert!(saved.to_string_lossy().ends_with(".jpg"), "{:?}", saved);
+    assertions::assert_file_contains(&md_file, ".jpg)");
+  }
+
+  /// 集成测试：超过大小上限的图片被跳过
+  #[tokio::test]
+  async fn integration_reject_oversized_image() {
+    let server = MockHttpServer::new().await;
+    server.mock_sized_image_response("/big.png", 4096).await;
+    server.mock_sized_image_response("/small.png", 512).await;
+    let host = server.url();
+
+    let content = format!("# 标题\n\n![大图]({0}/big.png)\n\n![小图]({0}/small.png)\n", host);
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("oversized.md", &content);
+    let assets_dir = file_manager.assets_dir();
+
+    let config = ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap())
+      .image_dir(assets_dir.to_str().unwrap())
+      .max_image_size(1024)
+      .build();
+
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    let report = format!("{:?}", call_result.content);
+    assert!(report.contains("超过上限"), "报告中应说明原因: {}", report);
+
+    assertions::assert_file_count(&assets_dir, 1);
+    assertions::assert_file_contains(&md_file, &format!("{}/big.png", host));
+    assertions::assert_file_not_contains(&md_file, &format!("{}/small.png", host));
+  }
+
+  /// 集成测试：引用式图片只下载一次并改写定义，链接中的图片保留外层链接
+  #[tokio::test]
+  async fn integration_reference_images_and_images_in_links() {
+    let server = MockHttpServer::new().await;
+    server.mock_basic_images().await;
+    let host = server.url();
+
+    let content = format!(
+      r#"# 引用图片
+
+![Logo][logo]
+
+再次引用 ![logo][] 和 ![LOGO]。
+
+[![徽章]({0}/svg)](https://github.com/example/repo)
+
+![单引号标题]({0}/webp 'It"s a title')
+
+[logo]: {0}/png 'Logo (dark)'
+"#,
+      host
+    );
+
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("reference.md", &content);
+    let assets_dir = file_manager.assets_dir();
+
+    let config =
+      ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
+
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, Default::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    // 定义只下载一次：定义、徽章、单引号标题图片各一个文件
+    assertions::assert_file_count(&assets_dir, 3);
+
+    let final_content = std::fs::read_to_string(&md_file).unwrap();
+    assert!(!final_content.contains(&host), "不应再包含原始 URL: {}", final_content);
+    assert!(final_content.contains("![Logo][logo]"));
+    assert!(final_content.contains("再次引用 ![logo][] 和 ![LOGO]。"));
+    assert!(final_content.contains("[logo]: assets/"));
+    assert!(final_content.contains(" 'Logo (dark)'"));
+    assert!(final_content.contains("[![徽章](assets/"));
+    assert!(final_content.contains(")](https://github.com/example/repo)"));
+    assert!(final_content.contains(" 'It\"s a title')"));
+  }
+
+  /// 集成测试：取消后停止下载，文档保持不变
+  #[tokio::test]
+  async fn integration_cancel_localization() {
+    let server = MockHttpServer::new().await;
+    server.mock_sized_image_response("/fast.png", 512).await;
+    server.mock_delayed_image_response("/slow.png", std::time::Duration::from_secs(30)).await;
+    let host = server.url();
+
+    let content = format!("# 标题\n\n![快]({0}/fast.png)\n\n![慢]({0}/slow.png)\n", host);
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("cancel.md", &content);
+
+    let config = ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap())
+      .image_dir(file_manager.assets_dir().to_str().unwrap())
+      .build();
+
+    let events = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let progress: ProgressCallback = Box::new(move |progress| recorded.lock().unwrap().push(progress));
+
+    // 第一张图片处理完后取消，第二张图片的下载应立即中止
+    let cancellation = CancellationToken::new();
+    let trigger = cancellation.clone();
+    let watcher = events.clone();
+    tokio::spawn(async move {
+      while watcher.lock().unwrap().is_empty() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+      }
+      trigger.cancel();
+    });
+
+    let started = std::time::Instant::now();
+    let options = LocalizeOptions { progress: Some(progress), cancellation, ..Default::default() };
+    let call_result = MarkdownToolsImpl::localize_images_impl(config, options).await.unwrap();
+    assert!(started.elapsed() < std::time::Duration::from_secs(10), "取消后应立即返回");
+    assert_eq!(call_result.is_error, Some(true));
+    assert!(format!("{:?}", call_result.content).contains("文档未修改"));
+
+    let events = events.lock().unwrap();
+    assert_eq!((events[0].processed, events[0].total), (1, 2));
+    assert!(events[0].url.ends_with("/fast.png"));
+    assert_eq!(std::fs::read_to_string(&md_file).unwrap(), content);
+  }
 }
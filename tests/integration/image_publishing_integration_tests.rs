@@ -0,0 +1,121 @@
+//! 图片发布集成测试
+//!
+//! 测试图片发布（本地化的逆操作）与解析器、渲染器以及图片本地化的协同工作
+
+use crate::common::{assertions, ImageLocalizationConfigBuilder, MockHttpServer, TestFileManager};
+use mcp_markdown_tools::config::{PublishImagesConfig, PublishImagesMode, DEFAULT_MAX_IMAGE_SIZE};
+use mcp_markdown_tools::sandbox::Sandbox;
+use mcp_markdown_tools::tools::MarkdownToolsImpl;
+
+/// 创建发布配置
+fn publish_config(file_path: &str, mode: PublishImagesMode) -> PublishImagesConfig {
+  PublishImagesConfig {
+    full_file_path: file_path.to_string(),
+    mode,
+    base_url: None,
+    path_mapping: Vec::new(),
+    new_full_file_path: None,
+    max_image_size: DEFAULT_MAX_IMAGE_SIZE,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 集成测试：按路径映射改写本地图片，另存为新文件
+  #[tokio::test]
+  async fn integration_publish_with_path_mapping() {
+    let content = r#"# 发布
+
+![图一](assets/one.png "第一张")
+
+<img src="./assets/icons/logo.svg" alt="logo" width="32">
+
+![引用图][fig]
+
+![远程](https://example.com/remote.png)
+
+[fig]: images/fig.png
+"#;
+
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("publish.md", content);
+    let new_file = file_manager.temp_dir.path().join("publish_cdn.md");
+
+    let mut config = publish_config(md_file.to_str().unwrap(), PublishImagesMode::BaseUrl);
+    config.base_url = Some("https://static.example.com/docs".to_string());
+    config.path_mapping = vec![
+      ("assets/icons/".to_string(), "https://icons.example.com/".to_string()),
+      ("assets/".to_string(), "https://cdn.example.com/assets/".to_string()),
+    ];
+    config.new_full_file_path = Some(new_file.to_str().unwrap().to_string());
+
+    let call_result = MarkdownToolsImpl::publish_images_impl(config, &Sandbox::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    assertions::assert_file_contains(&new_file, "![图一](https://cdn.example.com/assets/one.png \"第一张\")");
+    assertions::assert_file_contains(&new_file, "src=\"https://icons.example.com/logo.svg\"");
+    assertions::assert_file_contains(&new_file, "![引用图][fig]");
+    assertions::assert_file_contains(&new_file, "[fig]: https://static.example.com/docs/images/fig.png");
+    assertions::assert_file_contains(&new_file, "![远程](https://example.com/remote.png)");
+
+    // 原文件保持不变
+    assertions::assert_file_contains(&md_file, "![图一](assets/one.png \"第一张\")");
+  }
+
+  /// 集成测试：发布只改写图片所在的行，带编号和数字的标题、空白及其他各行逐字节保持原样
+  #[tokio::test]
+  async fn integration_publish_keeps_other_lines() {
+    let content = "# 1. Intro\n\n##   2024 Roadmap\n\n### 1.1.  3 Steps  \n\n![图一](assets/one.png)\n\n行内 <img src=\"assets/two.png\" /> 结束\n\n![引用][fig]\n\n[fig]: assets/fig.png\n\n# 2. 附录\n";
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("numbered.md", content);
+
+    let mut config = publish_config(md_file.to_str().unwrap(), PublishImagesMode::BaseUrl);
+    config.base_url = Some("https://cdn.example.com/docs".to_string());
+    let call_result = MarkdownToolsImpl::publish_images_impl(config, &Sandbox::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    let new_content = std::fs::read_to_string(&md_file).unwrap();
+    assert_eq!(new_content.lines().count(), content.lines().count());
+    for (old_line, new_line) in content.lines().zip(new_content.lines()) {
+      match old_line.contains("assets/") {
+        true => assert!(new_line.contains("https://cdn.example.com/docs/assets/"), "{}", new_line),
+        false => assert_eq!(old_line, new_line),
+      }
+    }
+  }
+}
+
+#[cfg(feature = "mock")]
+mod mock_integration_tests {
+  use super::*;
+
+  /// 集成测试：本地化远程图片后再内嵌为 data URI
+  #[tokio::test]
+  async fn integration_localize_then_inline_round_trip() {
+    let server = MockHttpServer::new().await;
+    server.mock_basic_images().await;
+
+    let content = format!("# 往返\n\n![照片]({}/jpg)\n\n行内图片 ![图标]({}/png) 结束\n", server.url(), server.url());
+
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("round_trip.md", &content);
+    let assets_dir = file_manager.assets_dir();
+
+    let localize_config =
+      ImageLocalizationConfigBuilder::new(md_file.to_str().unwrap()).image_dir(assets_dir.to_str().unwrap()).build();
+    let call_result = MarkdownToolsImpl::localize_images_impl(localize_config, Default::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+    assertions::assert_file_contains(&md_file, "](assets/");
+
+    let publish_config = publish_config(md_file.to_str().unwrap(), PublishImagesMode::Inline);
+    let call_result = MarkdownToolsImpl::publish_images_impl(publish_config, &Sandbox::default()).await.unwrap();
+    assert_eq!(call_result.is_error, Some(false));
+
+    assertions::assert_file_not_contains(&md_file, "assets/");
+    assertions::assert_file_contains(&md_file, "![照片](data:image/jpeg;base64,");
+    assertions::assert_file_contains(&md_file, "行内图片 ![图标](data:image/jpeg;base64,");
+    assertions::assert_file_contains(&md_file, ") 结束");
+  }
+}
@@ -0,0 +1,113 @@
+//! 外部链接检查集成测试
+//!
+//! 使用本地模拟服务器测试 check_links 对 http(s) 链接的检查：状态码、HEAD 回退、重定向、超时、忽略域名和并发限制
+
+use crate::common::{MockHttpServer, TestFileManager};
+use mcp_markdown_tools::config::CheckLinksConfig;
+use mcp_markdown_tools::sandbox::Sandbox;
+use mcp_markdown_tools::tools::MarkdownToolsImpl;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn external_config(md_file: &Path) -> CheckLinksConfig {
+    CheckLinksConfig {
+      full_file_path: md_file.to_str().unwrap().to_string(),
+      check_external: true,
+      timeout_secs: 5,
+      max_concurrency: 4,
+      ignore_domains: Vec::new(),
+    }
+  }
+
+  async fn check_links(config: CheckLinksConfig) -> (bool, String) {
+    let result = MarkdownToolsImpl::check_links_impl(config, &Sandbox::default()).await.unwrap();
+    let text = result.content[0].as_text().unwrap().text.clone();
+    (result.is_error == Some(true), text)
+  }
+
+  /// 集成测试：失效的外部链接报告状态码，不支持 HEAD 的页面改用 GET，重定向报告最终地址
+  #[tokio::test]
+  async fn integration_check_external_links() {
+    let server = MockHttpServer::new().await;
+    server.mock_status_response("/ok", 200).await;
+    server.mock_status_response("/gone", 404).await;
+    server.mock_head_not_allowed("/no-head").await;
+    server.mock_redirect_response("/old", 301, "/new").await;
+    server.mock_status_response("/new", 200).await;
+    server.mock_redirect_response("/loop", 302, "/loop").await;
+
+    let url = server.url();
+    let content = format!(
+      "# 链接\n\n[正常]({url}/ok#part) [失效]({url}/gone) [GET]({url}/no-head)\n\
+       [旧地址][old] [循环]({url}/loop) [锚点](#链接)\n\n[old]: {url}/old\n"
+    );
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("links.md", &content);
+
+    let (is_error, text) = check_links(external_config(&md_file)).await;
+    assert!(is_error, "{}", text);
+    assert!(text.contains("共检查 6 个链接，跳过 0 个外部链接和 0 个无法检查的链接"), "{}", text);
+    let column = format!("[正常]({url}/ok#part) ").chars().count() + 1;
+    assert!(text.contains(&format!("第 3 行第 {column} 列 [失效]({url}/gone)：HTTP 错误: 404 Not Found")), "{}", text);
+    assert!(text.contains("[循环]"), "{}", text);
+    assert!(text.contains("重定向超过 10 次"), "{}", text);
+    assert!(text.contains(&format!("第 4 行第 1 列 [旧地址][old]：301 重定向到 {url}/new")), "{}", text);
+    assert!(!text.contains("[GET]"), "{}", text);
+    assert!(!text.contains("[正常]"), "{}", text);
+
+    // 默认不检查外部链接
+    let mut config = external_config(&md_file);
+    config.check_external = false;
+    let (is_error, text) = check_links(config).await;
+    assert!(!is_error, "{}", text);
+    assert!(text.contains("共检查 1 个链接，跳过 5 个外部链接和 0 个无法检查的链接"), "{}", text);
+  }
+
+  /// 集成测试：请求超时和忽略的域名
+  #[tokio::test]
+  async fn integration_check_external_links_timeout_and_ignored_domains() {
+    let server = MockHttpServer::new().await;
+    server.mock_delayed_image_response("/slow", Duration::from_secs(3)).await;
+
+    let content = format!("[慢]({}/slow) [忽略](https://docs.ignored.example/missing)\n", server.url());
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("slow.md", &content);
+
+    let mut config = external_config(&md_file);
+    config.timeout_secs = 1;
+    config.ignore_domains = vec!["ignored.example".to_string()];
+    let (is_error, text) = check_links(config).await;
+    assert!(is_error, "{}", text);
+    assert!(text.contains("共检查 1 个链接，跳过 0 个外部链接和 1 个无法检查的链接"), "{}", text);
+    assert!(text.contains("[慢]"), "{}", text);
+    assert!(text.contains("请求超时"), "{}", text);
+  }
+
+  /// 集成测试：同时进行的请求数量不超过 max_concurrency，相同的地址只请求一次
+  #[tokio::test]
+  async fn integration_check_external_links_concurrency_limit() {
+    let server = MockHttpServer::new().await;
+    let delay = Duration::from_millis(200);
+    let mut content = String::new();
+    for i in 0..4 {
+      server.mock_delayed_image_response(&format!("/page{}", i), delay).await;
+      content.push_str(&format!("[页面{i}]({}/page{i}) [重复]({}/page{i}#again)\n", server.url(), server.url()));
+    }
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("many.md", &content);
+
+    let mut config = external_config(&md_file);
+    config.max_concurrency = 1;
+    let start = Instant::now();
+    let (is_error, text) = check_links(config).await;
+    assert!(!is_error, "{}", text);
+    assert!(text.contains("共检查 8 个链接"), "{}", text);
+    assert!(start.elapsed() >= delay * 4, "{:?}", start.elapsed());
+
+    assert_eq!(server.request_count().await, 4);
+  }
+}
@@ -5,5 +5,9 @@
 #[path = "../common/mod.rs"]
 mod common;
 
+mod asset_cleaning_integration_tests;
 mod image_localization_integration_tests;
+mod image_publishing_integration_tests;
+mod link_checking_integration_tests;
 mod numbering_integration_tests;
+mod section_integration_tests;
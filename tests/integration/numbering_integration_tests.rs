@@ -3,7 +3,9 @@
 //! 测试编号功能与其他模块的集成，包括解析器、验证器等的协同工作
 
 use crate::common::{assertions, test_data, NumberingConfigBuilder, TestFileManager};
-use mcp_markdown_tools::config::{CheckHeadingConfig, GenerateChapterConfig, RemoveChapterConfig};
+use mcp_markdown_tools::config::{
+  CheckHeadingConfig, GenerateChapterConfig, RemoveChapterConfig, ShiftHeadingLevelsConfig,
+};
 use mcp_markdown_tools::tools::MarkdownToolsImpl;
 
 #[cfg(test)]
@@ -17,7 +19,7 @@ mod tests {
     let md_file = file_manager.create_md_file("workflow.md", test_data::SIMPLE_DOC);
 
     // 第一步：验证原始文档结构
-    let check_config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
@@ -37,7 +39,7 @@ mod tests {
     assertions::assert_file_contains(&md_file, "# 2. 第二章 实现");
 
     // 第三步：验证编号后的文档结构仍然有效
-    let check_config_after = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config_after = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result_after = MarkdownToolsImpl::check_heading_impl(check_config_after).await;
     assert!(check_result_after.is_ok());
@@ -61,7 +63,7 @@ mod tests {
     assertions::assert_file_not_contains(&md_file, "2.");
 
     // 第五步：验证移除编号后的文档结构仍然有效
-    let check_config_final = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config_final = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result_final = MarkdownToolsImpl::check_heading_impl(check_config_final).await;
     assert!(check_result_final.is_ok());
@@ -108,7 +110,7 @@ mod tests {
     assertions::assert_file_contains(&md_file, "### 1.1. 具体步骤");
 
     // 验证编号后的结构
-    let check_config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
@@ -163,7 +165,7 @@ mod tests {
     assertions::assert_file_contains(&md_file, "### 2.1. 实现");
 
     // 验证结构仍然有效
-    let check_config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
@@ -205,7 +207,7 @@ mod tests {
 
     // 验证所有文件的结构都有效
     for file_path in &file_paths {
-      let check_config = CheckHeadingConfig { full_file_path: file_path.to_str().unwrap().to_string() };
+      let check_config = CheckHeadingConfig::new(file_path.to_str().unwrap().to_string());
 
       let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
       assert!(check_result.is_ok());
@@ -259,7 +261,7 @@ mod tests {
     assertions::assert_file_contains(&numbered_file, "### 1.1.1. 背景");
 
     // 验证编号文件的结构
-    let check_config = CheckHeadingConfig { full_file_path: numbered_file.to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(numbered_file.to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
@@ -291,7 +293,7 @@ mod tests {
     let md_file = file_manager.create_md_file("complex.md", test_data::COMPLEX_DOC);
 
     // 先验证标题结构
-    let check_config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await;
     assert!(check_result.is_ok());
@@ -312,10 +314,42 @@ mod tests {
     assertions::assert_file_contains(&md_file, "# 二、第二个主标题");
 
     // 验证编号后结构仍然有效
-    let check_config_after = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let check_config_after = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let check_result_after = MarkdownToolsImpl::check_heading_impl(check_config_after).await;
     assert!(check_result_after.is_ok());
     assert_eq!(check_result_after.unwrap().is_error, Some(false));
   }
+
+  /// 集成测试：编号后调整章节层级，编号按原有风格重新生成且结构仍然有效
+  #[tokio::test]
+  async fn integration_shift_numbered_section() {
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("shift.md", test_data::SIMPLE_DOC);
+
+    let config = NumberingConfigBuilder::new(md_file.to_str().unwrap()).use_chinese_number(true).build();
+    MarkdownToolsImpl::generate_chapter_number_impl(config, "numed").await.unwrap();
+    assertions::assert_file_contains(&md_file, "# 二、第二章 实现");
+
+    // 将第二章降级为第一章的子章节
+    let shift_config = ShiftHeadingLevelsConfig {
+      full_file_path: md_file.to_str().unwrap().to_string(),
+      shift: 1,
+      anchor: Some("二第二章-实现".to_string()),
+      clamp: false,
+      renumber: true,
+      new_full_file_path: None,
+    };
+    let result = MarkdownToolsImpl::shift_heading_levels_impl(shift_config).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    assertions::assert_file_contains(&md_file, "# 一、第一章 介绍");
+    assertions::assert_file_contains(&md_file, "## 2. 目标");
+    assertions::assert_file_contains(&md_file, "## 3. 第二章 实现");
+    assertions::assert_file_contains(&md_file, "### 3.1. 技术选择");
+
+    let check_config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
+    let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await.unwrap();
+    assert_eq!(check_result.is_error, Some(false));
+  }
 }
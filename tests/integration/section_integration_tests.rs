@@ -0,0 +1,136 @@
+//! 章节操作集成测试
+//!
+//! 测试章节读取与替换、文档拆分与合并以及标题检查的协同工作
+
+use crate::common::{assertions, test_data, TestFileManager};
+use mcp_markdown_tools::config::{
+  CheckHeadingConfig, CheckLinksConfig, MergeDocumentsConfig, ReadSectionConfig, ReplaceSectionConfig,
+  SplitDocumentConfig,
+};
+use mcp_markdown_tools::sandbox::Sandbox;
+use mcp_markdown_tools::tools::MarkdownToolsImpl;
+use std::fs;
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// 集成测试：拆分后的索引文件中指向各章节文件的链接都有效，删除章节文件后能检查出失效的链接
+  #[tokio::test]
+  async fn integration_split_index_links_are_valid() {
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("guide.md", test_data::SIMPLE_DOC);
+
+    let split_config = SplitDocumentConfig {
+      full_file_path: md_file.to_str().unwrap().to_string(),
+      level: 1,
+      output_dir: "{full_dir_of_original_file}/parts".to_string(),
+      index_file_name: "index.md".to_string(),
+      move_images: false,
+      overwrite: false,
+    };
+    let result = MarkdownToolsImpl::split_document_impl(split_config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let parts_dir = file_manager.temp_dir.path().join("parts");
+    let check_config = || CheckLinksConfig {
+      full_file_path: parts_dir.join("index.md").to_str().unwrap().to_string(),
+      check_external: false,
+      timeout_secs: 10,
+      max_concurrency: 8,
+      ignore_domains: Vec::new(),
+    };
+    let result = MarkdownToolsImpl::check_links_impl(check_config(), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    fs::rename(parts_dir.join("02-第二章-实现.md"), parts_dir.join("02-实现.md")).unwrap();
+    let result = MarkdownToolsImpl::check_links_impl(check_config(), &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(true));
+    let text = &result.content[0].as_text().unwrap().text;
+    assert!(text.contains("02-第二章-实现.md"), "{}", text);
+  }
+
+  /// 集成测试：拆分文档后按顺序合并回原目录，内容与原文档一致
+  #[tokio::test]
+  async fn integration_split_then_merge_roundtrip() {
+    let file_manager = TestFileManager::new();
+    let content = format!("{}\n![架构](assets/arch.png)\n", test_data::SIMPLE_DOC);
+    let md_file = file_manager.create_md_file("guide.md", &content);
+
+    let split_config = SplitDocumentConfig {
+      full_file_path: md_file.to_str().unwrap().to_string(),
+      level: 1,
+      output_dir: "{full_dir_of_original_file}/parts".to_string(),
+      index_file_name: "index.md".to_string(),
+      move_images: false,
+      overwrite: false,
+    };
+    let result = MarkdownToolsImpl::split_document_impl(split_config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let parts_dir = file_manager.temp_dir.path().join("parts");
+    let second_part = parts_dir.join("02-第二章-实现.md");
+    assertions::assert_file_contains(&second_part, "![架构](../assets/arch.png)");
+    assertions::assert_file_contains(&parts_dir.join("index.md"), "- [第二章 实现](02-第二章-实现.md)");
+
+    // 每个拆分出的文件都是结构有效的文档
+    let check_config = CheckHeadingConfig::new(second_part.to_str().unwrap().to_string());
+    let check_result = MarkdownToolsImpl::check_heading_impl(check_config).await.unwrap();
+    assert_eq!(check_result.is_error, Some(false));
+
+    let merged_file = file_manager.temp_dir.path().join("merged.md");
+    let merge_config = MergeDocumentsConfig {
+      full_file_paths: vec![
+        parts_dir.join("01-第一章-介绍.md").to_str().unwrap().to_string(),
+        second_part.to_str().unwrap().to_string(),
+      ],
+      input_dir: None,
+      output_file_path: merged_file.to_str().unwrap().to_string(),
+      title: None,
+      first_file_as_parent: false,
+      overwrite: false,
+    };
+    let result = MarkdownToolsImpl::merge_documents_impl(merge_config, &Sandbox::default()).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+    assert_eq!(fs::read_to_string(&merged_file).unwrap(), content);
+  }
+
+  /// 集成测试：读取一个章节，修改后写回，文档的其余部分保持不变
+  #[tokio::test]
+  async fn integration_read_then_replace_section() {
+    let file_manager = TestFileManager::new();
+    let md_file = file_manager.create_md_file("guide.md", test_data::SIMPLE_DOC);
+    let full_file_path = md_file.to_str().unwrap().to_string();
+
+    let read_config = ReadSectionConfig {
+      full_file_path: full_file_path.clone(),
+      anchor: None,
+      title_path: None,
+      number_path: Some("1.2".to_string()),
+      line_number: None,
+      include_children: true,
+      max_chars: None,
+    };
+    let result = MarkdownToolsImpl::read_section_impl(read_config).await.unwrap();
+    let section = result.content[0].as_text().unwrap().text.clone();
+    assert_eq!(section, "## 目标\n\n项目目标描述。");
+
+    let replace_config = ReplaceSectionConfig {
+      full_file_path,
+      anchor: None,
+      title_path: None,
+      number_path: Some("1.2".to_string()),
+      line_number: None,
+      content: section.replace("项目目标描述。", "新的目标描述。\n\n### 里程碑\n\n按季度交付。"),
+      replace_heading: true,
+      adjust_levels: false,
+      renumber: false,
+      new_full_file_path: None,
+    };
+    let result = MarkdownToolsImpl::replace_section_impl(replace_config).await.unwrap();
+    assert_eq!(result.is_error, Some(false));
+
+    let expected = test_data::SIMPLE_DOC.replace("项目目标描述。", "新的目标描述。\n\n### 里程碑\n\n按季度交付。");
+    assert_eq!(fs::read_to_string(&md_file).unwrap(), expected);
+  }
+}
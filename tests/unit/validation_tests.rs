@@ -29,7 +29,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("valid.md", valid_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -51,7 +51,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("invalid_space.md", invalid_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -75,7 +75,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("invalid_extra_spaces.md", invalid_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -99,7 +99,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("level_jumping.md", invalid_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -139,7 +139,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("complex_valid.md", valid_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -156,7 +156,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("empty.md", empty_content);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -179,7 +179,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("no_headings.md", content_only);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -192,7 +192,7 @@ mod tests {
   /// 测试错误处理 - 不存在的文件
   #[tokio::test]
   async fn test_validate_heading_nonexistent_file() {
-    let config = CheckHeadingConfig { full_file_path: "/nonexistent/file.md".to_string() };
+    let config = CheckHeadingConfig::new("/nonexistent/file.md".to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());
@@ -224,7 +224,7 @@ mod tests {
     let file_manager = TestFileManager::new();
     let md_file = file_manager.create_md_file("with_preamble.md", content_with_preamble);
 
-    let config = CheckHeadingConfig { full_file_path: md_file.to_str().unwrap().to_string() };
+    let config = CheckHeadingConfig::new(md_file.to_str().unwrap().to_string());
 
     let result = MarkdownToolsImpl::check_heading_impl(config).await;
     assert!(result.is_ok());